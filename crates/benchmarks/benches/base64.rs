@@ -1,4 +1,6 @@
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use shared::radar::{decode_base64_bytes, encode_base64_bytes, extract_radar, split_into_chunks};
+use std::fmt::Write;
 
 const BASE64_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
 
@@ -51,6 +53,38 @@ fn decode_base64_match(input: &str) -> String {
     decoded
 }
 
+// String-based pipeline used before the radar functions worked on bytes, kept for comparison.
+fn to_binary_string(input: &str) -> String {
+    input.chars().fold(String::with_capacity(input.len() * 8), |mut acc, c| {
+        let _ = write!(acc, "{:08b}", c as u8);
+        acc
+    })
+}
+
+fn encode_base64_string(input: &str) -> String {
+    let mut encoded = String::new();
+    for sub in split_into_chunks(&to_binary_string(input), 6) {
+        if let Ok(decimal) = u8::from_str_radix(&sub, 2) {
+            if let Some(c) = BASE64_CHARS.chars().nth(decimal as usize) {
+                encoded.push(c);
+            }
+        }
+    }
+    encoded
+}
+
+fn extract_radar_string(input: &str) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let binary = to_binary_string(input);
+    let horizontal = format!("{}{}{}", &binary[16..24], &binary[8..16], &binary[0..8]);
+    let vertical = format!("{}{}{}", &binary[40..48], &binary[32..40], &binary[24..32]);
+    let parse = |bits: &str, size: usize, count: usize| -> Vec<u8> {
+        (0..count)
+            .filter_map(|i| u8::from_str_radix(&bits[i * size..(i + 1) * size], 2).ok())
+            .collect()
+    };
+    (parse(&horizontal, 2, 12), parse(&vertical, 2, 12), parse(&binary[48..88], 4, 9))
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let small_input = "SGVsbG8gV29ybGQ=";
     let medium_input = "TG9yZW0gaXBzdW0gZG9sb3Igc2l0IGFtZXQ=";
@@ -68,7 +102,29 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     group.bench_function("find_large", |b| b.iter(|| decode_base64_find(black_box(large_input))));
     group.bench_function("match_large", |b| b.iter(|| decode_base64_match(black_box(large_input))));
+    // The game alphabet has no padding
+    let unpadded = large_input.trim_end_matches('=');
+    group.bench_function("bytes_large", |b| b.iter(|| decode_base64_bytes(black_box(unpadded))));
+
+    group.finish();
 
+    let payload = decode_base64_match(large_input);
+    let mut group = c.benchmark_group("Base64 Encode");
+    group.bench_function("string", |b| b.iter(|| encode_base64_string(black_box(&payload))));
+    group
+        .bench_function("bytes", |b| b.iter(|| encode_base64_bytes(black_box(payload.as_bytes()))));
+    group.finish();
+
+    let radar_view = "jivbQjIad/apapa";
+    let mut group = c.benchmark_group("Radar Extract");
+    group.bench_function("string", |b| {
+        b.iter(|| extract_radar_string(&decode_base64_match(black_box(radar_view))))
+    });
+    group.bench_function("bytes", |b| {
+        b.iter(|| {
+            decode_base64_bytes(black_box(radar_view)).and_then(|bytes| extract_radar(&bytes))
+        })
+    });
     group.finish();
 }
 
@@ -1,7 +1,7 @@
 extern crate client;
 extern crate criterion as criterion2;
 extern crate shared;
-use client::maze_parser::maze_parser;
+use client::maze_parser::maze_parser_unchecked;
 use client::maze_solver::{PrintPathMode, bfs_shortest_path};
 use criterion2::{Criterion, black_box, criterion_group, criterion_main};
 use shared::maze::Maze;
@@ -37,11 +37,11 @@ fn parse_all_mazes() -> MazeData {
     let data = load_bench_data();
 
     MazeData {
-        maze1: maze_parser(&data.maze1),
-        maze2: maze_parser(&data.maze2),
-        maze3: maze_parser(&data.maze3),
-        maze4: maze_parser(&data.maze4),
-        maze5: maze_parser(&data.maze5),
+        maze1: maze_parser_unchecked(&data.maze1).expect("rectangular bench maze"),
+        maze2: maze_parser_unchecked(&data.maze2).expect("rectangular bench maze"),
+        maze3: maze_parser_unchecked(&data.maze3).expect("rectangular bench maze"),
+        maze4: maze_parser_unchecked(&data.maze4).expect("rectangular bench maze"),
+        maze5: maze_parser_unchecked(&data.maze5).expect("rectangular bench maze"),
     }
 }
 
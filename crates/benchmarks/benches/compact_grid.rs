@@ -0,0 +1,68 @@
+extern crate client;
+extern crate criterion as criterion2;
+extern crate shared;
+use client::maze_solver::{PrintPathMode, bfs_grid_path, bfs_shortest_path};
+use criterion2::{Criterion, black_box, criterion_group, criterion_main};
+use shared::maze::grid::CompactMaze;
+use shared::maze::{GeneratorAlgorithm, Maze};
+use shared::maze_generator::generate_grid;
+use std::time::Duration;
+
+const SIZES: [usize; 3] = [100, 500, 1000];
+const SEED: u64 = 5849;
+
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Grid Generate");
+    group.warm_up_time(Duration::from_secs(3));
+
+    for size in SIZES {
+        group.bench_function(format!("maze_{}", size), |b| {
+            b.iter(|| {
+                generate_grid::<Maze>(GeneratorAlgorithm::Sidewinder, black_box(size), size, SEED)
+            })
+        });
+        group.bench_function(format!("compact_{}", size), |b| {
+            b.iter(|| {
+                generate_grid::<CompactMaze>(
+                    GeneratorAlgorithm::Sidewinder,
+                    black_box(size),
+                    size,
+                    SEED,
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Grid Solve");
+    group.warm_up_time(Duration::from_secs(3));
+
+    for size in SIZES {
+        let maze: Maze = generate_grid(GeneratorAlgorithm::Sidewinder, size, size, SEED);
+        let compact: CompactMaze = generate_grid(GeneratorAlgorithm::Sidewinder, size, size, SEED);
+
+        group.bench_function(format!("maze_bfs_shortest_path_{}", size), |b| {
+            b.iter(|| bfs_shortest_path(black_box(&maze), PrintPathMode::None))
+        });
+        group.bench_function(format!("maze_bfs_grid_path_{}", size), |b| {
+            b.iter(|| bfs_grid_path(black_box(&maze)))
+        });
+        group.bench_function(format!("compact_bfs_grid_path_{}", size), |b| {
+            b.iter(|| bfs_grid_path(black_box(&compact)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default()
+        .sample_size(20)
+        .warm_up_time(Duration::from_secs(3));
+    targets = bench_generate, bench_solve
+);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 extern crate client;
 extern crate criterion as criterion2;
-use client::maze_parser::maze_parser;
+use client::maze_parser::maze_parser_unchecked;
 use criterion2::{Criterion, black_box, criterion_group, criterion_main};
 use std::time::Duration;
 
@@ -28,11 +28,11 @@ fn bench_maze_parser(c: &mut Criterion) {
     let mut group = c.benchmark_group("maze_parser");
     group.warm_up_time(Duration::from_secs(3));
 
-    group.bench_function("maze1", |b| b.iter(|| maze_parser(black_box(&data.maze1))));
-    group.bench_function("maze2", |b| b.iter(|| maze_parser(black_box(&data.maze2))));
-    group.bench_function("maze3", |b| b.iter(|| maze_parser(black_box(&data.maze3))));
-    group.bench_function("maze4", |b| b.iter(|| maze_parser(black_box(&data.maze4))));
-    group.bench_function("maze5", |b| b.iter(|| maze_parser(black_box(&data.maze5))));
+    group.bench_function("maze1", |b| b.iter(|| maze_parser_unchecked(black_box(&data.maze1))));
+    group.bench_function("maze2", |b| b.iter(|| maze_parser_unchecked(black_box(&data.maze2))));
+    group.bench_function("maze3", |b| b.iter(|| maze_parser_unchecked(black_box(&data.maze3))));
+    group.bench_function("maze4", |b| b.iter(|| maze_parser_unchecked(black_box(&data.maze4))));
+    group.bench_function("maze5", |b| b.iter(|| maze_parser_unchecked(black_box(&data.maze5))));
 
     group.finish();
 }
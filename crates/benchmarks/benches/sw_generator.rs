@@ -1,17 +1,35 @@
 use std::time::Duration;
 extern crate criterion as criterion2;
 use criterion2::{Criterion, criterion_group, criterion_main};
+use shared::maze::{Braiding, GeneratorAlgorithm, Maze};
 use shared::maze_generator::sidewinder;
 
 fn bench_sidewinder_generator(c: &mut Criterion) {
     let mut group = c.benchmark_group("sidewinder_generator");
     group.warm_up_time(Duration::from_secs(3));
 
-    group.bench_function("maze1", |b| b.iter(|| sidewinder(10, 10, false, 5849)));
-    group.bench_function("maze2", |b| b.iter(|| sidewinder(20, 20, false, 5849)));
-    group.bench_function("maze3", |b| b.iter(|| sidewinder(30, 30, false, 5849)));
-    group.bench_function("maze4", |b| b.iter(|| sidewinder(40, 40, false, 5849)));
-    group.bench_function("maze5", |b| b.iter(|| sidewinder(50, 50, false, 5849)));
+    group.bench_function("maze1", |b| b.iter(|| sidewinder(10, 10, 5849)));
+    group.bench_function("maze2", |b| b.iter(|| sidewinder(20, 20, 5849)));
+    group.bench_function("maze3", |b| b.iter(|| sidewinder(30, 30, 5849)));
+    group.bench_function("maze4", |b| b.iter(|| sidewinder(40, 40, 5849)));
+    group.bench_function("maze5", |b| b.iter(|| sidewinder(50, 50, 5849)));
+
+    group.finish();
+}
+
+fn bench_braided_generator(c: &mut Criterion) {
+    let mut group = c.benchmark_group("braided_generator");
+    group.warm_up_time(Duration::from_secs(3));
+
+    for braiding in [Braiding::Perfect, Braiding::Partial(0.5), Braiding::Full] {
+        group.bench_function(braiding.to_string(), |b| {
+            b.iter(|| {
+                let mut maze = Maze::generate(GeneratorAlgorithm::Sidewinder, 30, 30, 5849);
+                maze.braid(braiding, 5849);
+                maze
+            })
+        });
+    }
 
     group.finish();
 }
@@ -21,6 +39,6 @@ criterion_group!(
     config = Criterion::default()
         .sample_size(100)
         .warm_up_time(Duration::from_secs(3));
-    targets = bench_sidewinder_generator
+    targets = bench_sidewinder_generator, bench_braided_generator
 );
 criterion_main!(benches);
@@ -0,0 +1,163 @@
+use crate::data_structures::maze_graph::MazeGraph;
+use crate::instructions;
+use crate::maze_parser::Player;
+use shared::messages::Action;
+use shared::radar::Radar;
+use std::fmt;
+use std::str::FromStr;
+
+/// How an agent picks its next move while exploring the maze one radar view at a time.
+///
+/// The radar view has already been merged into `graph` when the strategy is asked for a
+/// move. The strategy turns and moves `player` along with the returned action.
+pub trait AgentStrategy: Send {
+    fn next_action(
+        &mut self,
+        radar: &Radar,
+        player: &mut Player,
+        graph: &mut MazeGraph,
+        agent_name: &str,
+    ) -> Action;
+}
+
+/// Depth-first exploration marking every passage taken, see [`instructions::tremeaux_solver`].
+pub struct Tremeaux;
+
+impl AgentStrategy for Tremeaux {
+    fn next_action(
+        &mut self,
+        _: &Radar,
+        player: &mut Player,
+        graph: &mut MazeGraph,
+        _: &str,
+    ) -> Action {
+        instructions::tremeaux_solver(player, graph)
+    }
+}
+
+/// Keeps its right hand on the wall, see [`instructions::right_hand_solver`].
+pub struct WallFollower;
+
+impl AgentStrategy for WallFollower {
+    fn next_action(
+        &mut self,
+        radar: &Radar,
+        player: &mut Player,
+        _: &mut MazeGraph,
+        _: &str,
+    ) -> Action {
+        instructions::right_hand_solver(radar, player)
+    }
+}
+
+/// Tremeaux shared between the agents of a team, see [`instructions::alian_solver`].
+pub struct Alian;
+
+impl AgentStrategy for Alian {
+    fn next_action(
+        &mut self,
+        _: &Radar,
+        player: &mut Player,
+        graph: &mut MazeGraph,
+        agent_name: &str,
+    ) -> Action {
+        instructions::alian_solver(player, graph, agent_name)
+    }
+}
+
+/// Every agent strategy by name, used to parse the command line and to create the
+/// strategy of each agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentAlgorithm {
+    Tremeaux,
+    WallFollower,
+    Alian,
+}
+
+impl AgentAlgorithm {
+    pub const ALL: [AgentAlgorithm; 3] =
+        [AgentAlgorithm::Tremeaux, AgentAlgorithm::WallFollower, AgentAlgorithm::Alian];
+
+    /// A new strategy for one agent, agents never share their strategy.
+    pub fn strategy(self) -> Box<dyn AgentStrategy> {
+        match self {
+            AgentAlgorithm::Tremeaux => Box::new(Tremeaux),
+            AgentAlgorithm::WallFollower => Box::new(WallFollower),
+            AgentAlgorithm::Alian => Box::new(Alian),
+        }
+    }
+}
+
+impl fmt::Display for AgentAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AgentAlgorithm::Tremeaux => "Tremeaux",
+            AgentAlgorithm::WallFollower => "WallFollower",
+            AgentAlgorithm::Alian => "Alian",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for AgentAlgorithm {
+    type Err = String;
+
+    /// Parses an algorithm name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AgentAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<String> =
+                    AgentAlgorithm::ALL.iter().map(ToString::to_string).collect();
+                format!("unknown algorithm '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_parser::maze_to_graph;
+    use shared::radar::decode_radar;
+
+    #[test]
+    fn test_names_round_trip() {
+        for algorithm in AgentAlgorithm::ALL {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+            assert_eq!(algorithm.to_string().to_lowercase().parse(), Ok(algorithm));
+        }
+        assert!("RightHand".parse::<AgentAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_strategies_follow_their_algorithm() {
+        let radar = decode_radar("bKgGjsIyap8p8aa").unwrap();
+        let mut graph = MazeGraph::new();
+        maze_to_graph(&radar, &Player::new(), &mut graph).unwrap();
+
+        for algorithm in AgentAlgorithm::ALL {
+            let mut player = Player::new();
+            let action = algorithm.strategy().next_action(
+                &radar,
+                &mut player,
+                &mut graph.clone(),
+                "Player1",
+            );
+            let mut expected_player = Player::new();
+            let expected = match algorithm {
+                AgentAlgorithm::Tremeaux => {
+                    instructions::tremeaux_solver(&mut expected_player, &mut graph.clone())
+                }
+                AgentAlgorithm::WallFollower => {
+                    instructions::right_hand_solver(&radar, &mut expected_player)
+                }
+                AgentAlgorithm::Alian => {
+                    instructions::alian_solver(&mut expected_player, &mut graph.clone(), "Player1")
+                }
+            };
+            assert_eq!(format!("{:?}", action), format!("{:?}", expected), "{}", algorithm);
+            assert_eq!(player.position, expected_player.position, "{}", algorithm);
+        }
+    }
+}
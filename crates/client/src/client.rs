@@ -1,19 +1,27 @@
+use crate::agents::{AgentAlgorithm, AgentStrategy};
 use crate::instructions;
 use crate::maze_parser::maze_to_graph;
 use crate::tui::GameState;
-use crate::{data_structures::maze_graph::MazeGraph, maze_parser::Player};
+use crate::{
+    data_structures::maze_graph::{CellStatus, MazeGraph},
+    maze_parser::Player,
+};
 use shared::utils::print_error;
 use shared::{
     errors::{GameError, GameResult},
+    heartbeat::{HeartbeatConfig, HeartbeatMonitor},
     logger::{LogLevel, Logger},
+    maze::Cell,
     messages::{
-        self, Action, Challenge, Hint, Message, RegisterTeam, RegisterTeamResult, SubscribePlayer,
-        SubscribePlayerResult, receive_message, send_message,
+        self, Action, Challenge, Direction, Hint, Message, RegisterTeam, RegisterTeamResult,
+        SubscribePlayer, SubscribePlayerResult, TeamInfo, TeamMessage, receive_message,
+        send_message,
     },
-    radar::{decode_base64, extract_data},
+    radar::{Radar, decode_radar},
 };
 use std::{
     collections::HashMap,
+    io::{self, Write},
     net::TcpStream,
     sync::{Arc, Mutex},
     thread::{self, ThreadId},
@@ -23,6 +31,9 @@ use std::{
 pub struct ClientConfig {
     pub server_addr: String,
     pub team_name: String,
+    pub heartbeat: HeartbeatConfig,
+    /// Share discoveries with teammates through server-relayed team messages.
+    pub team_chat: bool,
 }
 
 pub struct GameClient {
@@ -38,7 +49,11 @@ struct SecretSumModulo {
 struct PlayerContext {
     graph: MazeGraph,
     player: Player,
-    algorithm: String,
+    strategy: Box<dyn AgentStrategy>,
+    team_chat: bool,
+    /// The radar view the last move was chosen from, with the player before that move and
+    /// the direction taken, to choose again when the server refuses the move.
+    last_move: Option<(Radar, Player, Direction)>,
 }
 
 struct LogContext {
@@ -77,6 +92,16 @@ impl GameClient {
         }
     }
 
+    fn subscribe_agent(stream: &mut TcpStream, name: &str, token: &str) -> GameResult<()> {
+        send_message(
+            stream,
+            &Message::SubscribePlayer(SubscribePlayer {
+                name: name.to_string(),
+                registration_token: token.to_string(),
+            }),
+        )
+    }
+
     fn register_team(&self, stream: &mut TcpStream) -> GameResult<String> {
         send_message(
             stream,
@@ -99,7 +124,7 @@ impl GameClient {
         max_retries: u8,
         num_agents: u8,
         tui_state: Option<Arc<Mutex<GameState>>>,
-        algorithm: String,
+        algorithm: AgentAlgorithm,
     ) -> GameResult<()> {
         let mut stream = Self::connect_to_server(&self.config.server_addr, max_retries)?;
         let token = self.register_team(&mut stream)?;
@@ -114,37 +139,64 @@ impl GameClient {
                 secrets: Arc::clone(&self.challenge_secret_sum.secrets),
             };
             let tui_state = tui_state.clone();
-            let algorithm = algorithm.clone();
 
             let handle = thread::Builder::new().name(agent_name.clone()).spawn(
                 move || -> GameResult<()> {
                     let mut stream = Self::connect_to_server(&config.server_addr, max_retries)?;
 
-                    let mut player_ctx =
-                        PlayerContext { graph: MazeGraph::new(), player: Player::new(), algorithm };
+                    let mut player_ctx = PlayerContext {
+                        graph: MazeGraph::new(),
+                        player: Player::new(),
+                        strategy: algorithm.strategy(),
+                        team_chat: config.team_chat,
+                        last_move: None,
+                    };
 
                     let log_ctx = LogContext { thread_name: agent_name.clone(), tui_state };
 
                     let challenge_ctx = Challenges { secrets_sum };
 
-                    send_message(
-                        &mut stream,
-                        &Message::SubscribePlayer(SubscribePlayer {
-                            name: agent_name,
-                            registration_token: agent_token,
-                        }),
-                    )?;
-
-                    while let Ok(msg) = receive_message(&mut stream) {
-                        Self::handle_server_message(
-                            &mut stream,
-                            msg,
-                            &mut player_ctx,
-                            &log_ctx,
-                            &challenge_ctx,
-                        )?;
+                    Self::subscribe_agent(&mut stream, &agent_name, &agent_token)?;
+                    let mut heartbeat = HeartbeatMonitor::new(config.heartbeat.clone());
+                    // Reconnections since the last message, a server that drops every new
+                    // connection ends the agent after `max_retries` of them
+                    let mut reconnections = 0;
+
+                    loop {
+                        let error = match heartbeat.receive(&mut stream) {
+                            Ok(Some(msg)) => {
+                                reconnections = 0;
+                                Self::handle_server_message(
+                                    &mut stream,
+                                    msg,
+                                    &mut player_ctx,
+                                    &log_ctx,
+                                    &challenge_ctx,
+                                )?;
+                                continue;
+                            }
+                            Ok(None) => GameError::ConnectionError(io::Error::new(
+                                io::ErrorKind::TimedOut,
+                                "server stopped responding",
+                            )),
+                            Err(e) => e,
+                        };
+
+                        reconnections += 1;
+                        if reconnections > max_retries {
+                            return Err(error);
+                        }
+                        Self::log_handler(
+                            &log_ctx.tui_state,
+                            &log_ctx.thread_name,
+                            Logger::get_instance(),
+                            format!("{}, reconnecting...", error),
+                            LogLevel::Warning,
+                        );
+                        stream = Self::connect_to_server(&config.server_addr, max_retries)?;
+                        Self::subscribe_agent(&mut stream, &agent_name, &agent_token)?;
+                        heartbeat = HeartbeatMonitor::new(config.heartbeat.clone());
                     }
-                    Ok(())
                 },
             )?;
 
@@ -159,8 +211,8 @@ impl GameClient {
         Ok(())
     }
 
-    fn handle_server_message(
-        stream: &mut TcpStream,
+    fn handle_server_message<W: Write>(
+        stream: &mut W,
         message: Message,
         player_ctx: &mut PlayerContext,
         log_ctx: &LogContext,
@@ -168,7 +220,8 @@ impl GameClient {
     ) -> GameResult<()> {
         let logger = Logger::get_instance();
 
-        if logger.is_debug_enabled() {
+        // Radar views are logged once decoded, see `handle_radar_view`
+        if logger.is_debug_enabled() && !matches!(message, Message::RadarView(_)) {
             Self::log_handler(
                 &log_ctx.tui_state,
                 &log_ctx.thread_name,
@@ -197,6 +250,9 @@ impl GameClient {
             Message::Hint(hint) => {
                 Self::handle_hint(hint, &challenge_ctx.secrets_sum);
             }
+            Message::TeamMessage(team_message) => {
+                player_ctx.graph.apply_team_message(&team_message);
+            }
             Message::Challenge(value) => {
                 Self::log_handler(
                     &log_ctx.tui_state,
@@ -253,17 +309,22 @@ impl GameClient {
                         format!("Action error: {:?}", err),
                         LogLevel::Error,
                     );
-                    player_ctx.player.revert_move();
-
-                    let action: Action = instructions::alian_solver(
-                        &mut player_ctx.player,
-                        &mut player_ctx.graph,
-                        &log_ctx.thread_name,
-                    );
-
-                    player_ctx.player.update_last_position();
-
-                    send_message(stream, &Message::Action(action.clone()))?;
+                    // The passage closed since the last radar view, let the strategy choose
+                    // again from where the player still stands
+                    if let Some((mut radar_view, player, direction)) = player_ctx.last_move.take() {
+                        player_ctx.player = player;
+                        radar_view.close_passage(&direction);
+                        if Self::play_radar(stream, radar_view, player_ctx, log_ctx)? {
+                            Self::log_handler(
+                                &log_ctx.tui_state,
+                                &log_ctx.thread_name,
+                                logger,
+                                "Found the exit!",
+                                LogLevel::Info,
+                            );
+                            return Ok(());
+                        }
+                    }
                 }
             },
 
@@ -326,35 +387,87 @@ impl GameClient {
         }
     }
 
-    fn handle_radar_view(
-        stream: &mut TcpStream,
+    fn handle_radar_view<W: Write>(
+        stream: &mut W,
         view: messages::RadarView,
         player_ctx: &mut PlayerContext,
         log_ctx: &LogContext,
     ) -> GameResult<bool> {
-        let radar_view = extract_data(&decode_base64(&view.0))
-            .map_err(|e| GameError::MessageError(format!("Failed to decode radar view: {}", e)))?;
-
-        maze_to_graph(&radar_view, &player_ctx.player, &mut player_ctx.graph);
+        let radar_view = decode_radar(&view.0)?;
 
-        let action: Action = match player_ctx.algorithm.as_str() {
-            "Tremeaux" => {
-                instructions::tremeaux_solver(&mut player_ctx.player, &mut player_ctx.graph)
-            }
-            "RightHand" => instructions::right_hand_solver(&radar_view, &mut player_ctx.player),
-            "Alian" => instructions::alian_solver(
-                &mut player_ctx.player,
-                &mut player_ctx.graph,
+        let logger = Logger::get_instance();
+        if logger.is_debug_enabled() {
+            Self::log_handler(
+                &log_ctx.tui_state,
                 &log_ctx.thread_name,
-            ),
-            _ => instructions::tremeaux_solver(&mut player_ctx.player, &mut player_ctx.graph),
-        };
+                logger,
+                format!("Received radar view {}:\n{}", view.0, radar_view),
+                LogLevel::Debug,
+            );
+        }
+
+        Self::play_radar(stream, radar_view, player_ctx, log_ctx)
+    }
+
+    /// Merges the radar view into the graph and sends the move the strategy chooses from it.
+    /// Returns whether the move reaches the exit.
+    fn play_radar<W: Write>(
+        stream: &mut W,
+        radar_view: Radar,
+        player_ctx: &mut PlayerContext,
+        log_ctx: &LogContext,
+    ) -> GameResult<bool> {
+        maze_to_graph(&radar_view, &player_ctx.player, &mut player_ctx.graph)?;
+        let from = player_ctx.player.position;
+        let before = player_ctx.player.clone();
+
+        let action = player_ctx.strategy.next_action(
+            &radar_view,
+            &mut player_ctx.player,
+            &mut player_ctx.graph,
+            &log_ctx.thread_name,
+        );
 
         player_ctx.player.update_last_position();
 
         send_message(stream, &Message::Action(action.clone()))?;
 
-        Ok(instructions::check_win_condition(&radar_view.cells, action))
+        let found_exit = instructions::check_win_condition(&radar_view.cells, action.clone());
+        player_ctx.last_move = match action {
+            Action::MoveTo(direction) => Some((radar_view, before, direction)),
+            _ => None,
+        };
+        if player_ctx.team_chat {
+            Self::share_with_team(stream, from, found_exit, player_ctx, log_ctx)?;
+        }
+
+        Ok(found_exit)
+    }
+
+    fn share_with_team<W: Write>(
+        stream: &mut W,
+        from: Cell,
+        found_exit: bool,
+        player_ctx: &PlayerContext,
+        log_ctx: &LogContext,
+    ) -> GameResult<()> {
+        let to = player_ctx.player.position;
+        let mut infos = Vec::new();
+
+        if player_ctx.graph.get_cell_status(from) == CellStatus::DeadEnd {
+            infos.push(TeamInfo::DeadEnd(from));
+        }
+        if found_exit {
+            infos.push(TeamInfo::Exit(to));
+        } else if player_ctx.graph.get_cell_status(to) == CellStatus::NotVisited {
+            infos.push(TeamInfo::ClaimCorridor { from, to });
+        }
+
+        for info in infos {
+            let message = TeamMessage { sender: log_ctx.thread_name.clone(), info };
+            send_message(stream, &Message::TeamMessage(message))?;
+        }
+        Ok(())
     }
 
     fn handle_hint(hint: Hint, secrets_sum: &SecretSumModulo) {
@@ -365,8 +478,8 @@ impl GameClient {
         }
     }
 
-    fn handle_secret_sum_modulo(
-        stream: &mut TcpStream,
+    fn handle_secret_sum_modulo<W: Write>(
+        stream: &mut W,
         secrets: &Arc<Mutex<HashMap<ThreadId, u128>>>,
         secret_sum: &Arc<Mutex<u128>>,
         new_sum: Option<u128>,
@@ -419,9 +532,67 @@ impl GameClient {
     }
 }
 
+/// An agent fed with messages by the caller rather than by a server, going through the
+/// same handlers as the agents of [`GameClient::run`]. Used by the offline game, see
+/// [`crate::offline`].
+pub(crate) struct LocalAgent {
+    player_ctx: PlayerContext,
+    log_ctx: LogContext,
+    challenge_ctx: Challenges,
+}
+
+impl LocalAgent {
+    pub(crate) fn new(
+        name: String,
+        algorithm: AgentAlgorithm,
+        team_chat: bool,
+        tui_state: Option<Arc<Mutex<GameState>>>,
+    ) -> Self {
+        Self {
+            player_ctx: PlayerContext {
+                graph: MazeGraph::new(),
+                player: Player::new(),
+                strategy: algorithm.strategy(),
+                team_chat,
+                last_move: None,
+            },
+            log_ctx: LogContext { thread_name: name, tui_state },
+            challenge_ctx: Challenges {
+                secrets_sum: SecretSumModulo {
+                    sum: Arc::new(Mutex::new(0)),
+                    secrets: Arc::new(Mutex::new(HashMap::new())),
+                },
+            },
+        }
+    }
+
+    /// Handles `message` as if the server had sent it, returns the messages sent back.
+    pub(crate) fn receive(&mut self, message: Message) -> GameResult<Vec<Message>> {
+        let mut sent = Vec::new();
+        GameClient::handle_server_message(
+            &mut sent,
+            message,
+            &mut self.player_ctx,
+            &self.log_ctx,
+            &self.challenge_ctx,
+        )?;
+
+        let mut frames = sent.as_slice();
+        let mut replies = Vec::new();
+        while !frames.is_empty() {
+            replies.push(receive_message(&mut frames)?);
+        }
+        Ok(replies)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::errors::RadarError;
+    use shared::maze::io::read_text;
+    use shared::maze::{Directions, PositionType};
+    use shared::radar::{encode_radar, maze_radar};
     use std::net::{TcpListener, TcpStream};
     use std::thread;
 
@@ -436,7 +607,9 @@ mod tests {
         let game_ctx = PlayerContext {
             graph: MazeGraph::new(),
             player: Player::new(),
-            algorithm: "Tremeaux".to_string(),
+            strategy: AgentAlgorithm::Tremeaux.strategy(),
+            team_chat: false,
+            last_move: None,
         };
 
         let log_ctx = LogContext { thread_name, tui_state: None };
@@ -483,8 +656,12 @@ mod tests {
         });
 
         let mut stream = TcpStream::connect(addr.clone()).unwrap();
-        let client =
-            GameClient::new(ClientConfig { server_addr: addr, team_name: "team".to_string() });
+        let client = GameClient::new(ClientConfig {
+            server_addr: addr,
+            team_name: "team".to_string(),
+            heartbeat: HeartbeatConfig::default(),
+            team_chat: false,
+        });
 
         let token = client.register_team(&mut stream).unwrap();
         assert_eq!(token, "test_token");
@@ -515,8 +692,12 @@ mod tests {
 
     #[test]
     fn test_new_client() {
-        let config =
-            ClientConfig { server_addr: "addr".to_string(), team_name: "team".to_string() };
+        let config = ClientConfig {
+            server_addr: "addr".to_string(),
+            team_name: "team".to_string(),
+            heartbeat: HeartbeatConfig::default(),
+            team_chat: false,
+        };
 
         let client = GameClient::new(config.clone());
         assert_eq!(client.config.server_addr, config.server_addr);
@@ -576,6 +757,121 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_handle_malformed_radar_view() {
+        let (listener, addr) = setup_mock_server();
+        thread::spawn(move || if let Ok((_stream, _)) = listener.accept() {});
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let message = Message::RadarView(messages::RadarView("bKgGjsIy=p8p8aa".to_string()));
+        let (mut game_ctx, log_ctx, challenge_ctx) = create_test_contexts("Player1".to_string());
+
+        let result = GameClient::handle_server_message(
+            &mut stream,
+            message,
+            &mut game_ctx,
+            &log_ctx,
+            &challenge_ctx,
+        );
+
+        assert!(matches!(
+            result,
+            Err(GameError::RadarError(RadarError::BadAlphabet { character: '=', position: 8 }))
+        ));
+    }
+
+    #[test]
+    fn test_handle_radar_view_shares_with_team() {
+        let (listener, addr) = setup_mock_server();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let action = receive_message(&mut stream).unwrap();
+            let shared = receive_message(&mut stream).unwrap();
+            (action, shared)
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let message = Message::RadarView(messages::RadarView("bKgGjsIyap8p8aa".to_string()));
+        let (mut game_ctx, log_ctx, challenge_ctx) = create_test_contexts("Player1".to_string());
+        game_ctx.team_chat = true;
+
+        GameClient::handle_server_message(
+            &mut stream,
+            message,
+            &mut game_ctx,
+            &log_ctx,
+            &challenge_ctx,
+        )
+        .unwrap();
+
+        let (action, shared) = server.join().unwrap();
+        assert!(matches!(action, Message::Action(_)));
+        assert!(
+            matches!(shared, Message::TeamMessage(TeamMessage { ref sender, .. }) if sender == "Player1")
+        );
+    }
+
+    #[test]
+    fn test_wall_error_asks_the_strategy_again() {
+        Logger::init(false);
+        // The passage on the right of the center cell closed after the radar view was sent
+        let text = "#######\n#2    #\n### ###\n#   # #\n### # #\n#    3#\n#######\n";
+        let mut outdated = read_text(text).unwrap();
+        outdated.map[3][4] = PositionType::SPACE;
+        let center = Cell { row: 3, column: 3 };
+        let view = encode_radar(&maze_radar(&outdated, center, Directions::NORTH));
+
+        let (mut game_ctx, log_ctx, challenge_ctx) = create_test_contexts("Player1".to_string());
+        game_ctx.strategy = AgentAlgorithm::WallFollower.strategy();
+        let mut handle = |message| {
+            let mut sent = Vec::new();
+            GameClient::handle_server_message(
+                &mut sent,
+                message,
+                &mut game_ctx,
+                &log_ctx,
+                &challenge_ctx,
+            )
+            .unwrap();
+            receive_message(&mut sent.as_slice()).unwrap()
+        };
+
+        let action = handle(Message::RadarView(messages::RadarView(view)));
+        assert!(matches!(action, Message::Action(Action::MoveTo(Direction::Right))));
+
+        // Following the right wall, the passage ahead comes next once the right one is closed
+        let retry = handle(Message::ActionError(messages::ActionError::CannotPassThroughWall));
+        assert!(matches!(retry, Message::Action(Action::MoveTo(Direction::Front))), "{:?}", retry);
+        assert_eq!(game_ctx.player.direction, Direction::Front);
+        assert_eq!(game_ctx.player.position, Cell { row: 0, column: -1 });
+    }
+
+    #[test]
+    fn test_handle_team_message() {
+        let (listener, addr) = setup_mock_server();
+        thread::spawn(move || if let Ok((_stream, _)) = listener.accept() {});
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let (mut game_ctx, log_ctx, challenge_ctx) = create_test_contexts("Player1".to_string());
+        let exit = Cell { row: 4, column: 2 };
+        let message = Message::TeamMessage(TeamMessage {
+            sender: "Player2".to_string(),
+            info: TeamInfo::Exit(exit),
+        });
+
+        GameClient::handle_server_message(
+            &mut stream,
+            message,
+            &mut game_ctx,
+            &log_ctx,
+            &challenge_ctx,
+        )
+        .unwrap();
+
+        assert!(game_ctx.graph.contains(&exit));
+    }
+
     #[test]
     fn test_handle_hint() {
         let (listener, addr) = setup_mock_server();
@@ -648,10 +944,96 @@ mod tests {
             }
         });
 
-        let config = ClientConfig { server_addr: addr, team_name: "team".to_string() };
+        let config = ClientConfig {
+            server_addr: addr,
+            team_name: "team".to_string(),
+            heartbeat: HeartbeatConfig::default(),
+            team_chat: false,
+        };
         let client = GameClient::new(config);
 
-        let result = client.run(1, 1, None, "Tremeaux".to_string());
-        assert!(result.is_ok());
+        // Once the mock server is gone the agent cannot reconnect and reports it
+        let result = client.run(1, 1, None, AgentAlgorithm::Tremeaux);
+        assert!(matches!(result, Err(GameError::ConnectionError(_))));
+    }
+
+    #[test]
+    fn test_run_reconnects_after_silent_server() {
+        let (listener, addr) = setup_mock_server();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            receive_message(&mut stream).unwrap();
+            send_message(
+                &mut stream,
+                &Message::RegisterTeamResult(RegisterTeamResult::Ok {
+                    registration_token: "test_token".to_string(),
+                    expected_players: 1,
+                }),
+            )
+            .unwrap();
+
+            // First agent connection stays silent until the client gives up on it.
+            let (mut silent_stream, _) = listener.accept().unwrap();
+            receive_message(&mut silent_stream).unwrap();
+
+            let (mut player_stream, _) = listener.accept().unwrap();
+            let msg = receive_message(&mut player_stream).unwrap();
+            assert!(matches!(msg, Message::SubscribePlayer(_)));
+        });
+
+        let config = ClientConfig {
+            server_addr: addr,
+            team_name: "team".to_string(),
+            heartbeat: HeartbeatConfig {
+                interval: std::time::Duration::from_millis(20),
+                timeout: std::time::Duration::from_millis(100),
+            },
+            team_chat: false,
+        };
+        let client = GameClient::new(config);
+
+        // The mock server closes the last connection too, which ends the agent
+        assert!(client.run(1, 1, None, AgentAlgorithm::Tremeaux).is_err());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_reconnects_after_closed_connection() {
+        let (listener, addr) = setup_mock_server();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            receive_message(&mut stream).unwrap();
+            send_message(
+                &mut stream,
+                &Message::RegisterTeamResult(RegisterTeamResult::Ok {
+                    registration_token: "test_token".to_string(),
+                    expected_players: 1,
+                }),
+            )
+            .unwrap();
+
+            // First agent connection is closed right after subscribing
+            let (mut closed_stream, _) = listener.accept().unwrap();
+            receive_message(&mut closed_stream).unwrap();
+            drop(closed_stream);
+
+            let (mut player_stream, _) = listener.accept().unwrap();
+            let msg = receive_message(&mut player_stream).unwrap();
+            assert!(matches!(msg, Message::SubscribePlayer(_)));
+        });
+
+        let config = ClientConfig {
+            server_addr: addr,
+            team_name: "team".to_string(),
+            heartbeat: HeartbeatConfig::default(),
+            team_chat: false,
+        };
+        let client = GameClient::new(config);
+
+        // The mock server closes the last connection too, which ends the agent
+        assert!(client.run(1, 1, None, AgentAlgorithm::Tremeaux).is_err());
+        server.join().unwrap();
     }
 }
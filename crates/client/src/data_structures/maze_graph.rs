@@ -3,7 +3,11 @@ use std::{
     collections::{HashMap, HashSet},
 };
 
-use shared::{maze::Cell, radar::CellType};
+use shared::{
+    maze::Cell,
+    messages::{TeamInfo, TeamMessage},
+    radar::CellType,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum CellStatus {
@@ -75,6 +79,23 @@ impl MazeGraph {
         }
     }
 
+    /// Forgets the passage between two cells, once a wall has closed it.
+    ///
+    /// Returns whether the passage was known. A cell that was reached through it becomes
+    /// its own parent again, as there is no way back that way anymore.
+    pub fn remove_neighbor(&mut self, cell: &Cell, neighbor: &Cell) -> bool {
+        let mut removed = false;
+        for (from, to) in [(cell, neighbor), (neighbor, cell)] {
+            if let Some(maze_cell) = self.cell_map.get_mut(from) {
+                removed |= maze_cell.neighbors.remove(to);
+                if maze_cell.parent == *to {
+                    maze_cell.parent = *from;
+                }
+            }
+        }
+        removed
+    }
+
     pub fn get_cell(&mut self, position: Cell) -> Option<&mut MazeCell> {
         self.cell_map.get_mut(&position)
     }
@@ -97,6 +118,14 @@ impl MazeGraph {
         }
     }
 
+    /// Replaces the number of walls around a cell, unlike [`MazeGraph::update_walls`] which
+    /// only ever raises it. Used when every side of the cell is known.
+    pub fn set_walls(&mut self, position: Cell, walls: usize) {
+        if let Some(cell) = self.cell_map.get_mut(&position) {
+            cell.walls = walls;
+        }
+    }
+
     pub fn set_parent(&mut self, position: Cell, parent: Cell) {
         if let Some(cell) = self.cell_map.get_mut(&position) {
             cell.parent = parent;
@@ -115,6 +144,32 @@ impl MazeGraph {
         }
     }
 
+    /// Merges what a teammate shared into this graph.
+    pub fn apply_team_message(&mut self, message: &TeamMessage) {
+        match message.info {
+            TeamInfo::DeadEnd(cell) => {
+                if !self.contains(&cell) {
+                    self.add(cell, CellType::NOTHING);
+                }
+                self.update_cell_status(cell, CellStatus::DeadEnd);
+            }
+            TeamInfo::Exit(cell) => {
+                if !self.contains(&cell) {
+                    self.add(cell, CellType::OBJECTIVE);
+                }
+                self.update_cell_type(cell, CellType::OBJECTIVE);
+            }
+            TeamInfo::ClaimCorridor { to, .. } => {
+                if let Some(cell) = self.cell_map.get_mut(&to) {
+                    if cell.status == CellStatus::NotVisited {
+                        cell.status = CellStatus::VISITED;
+                    }
+                    *cell.visited_by.entry(message.sender.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
     pub fn set_visited(&self, position: Cell, thread_name: &str) {
         if let Some(cell) = self.cell_map.get(&position) {
             let mut visited = cell.visited_by.clone();
@@ -162,6 +217,38 @@ mod test {
         assert!(maze_cell.neighbors.contains(&neighbor));
     }
 
+    #[test]
+    fn test_maze_graph_remove_neighbor() {
+        let mut maze_graph = MazeGraph::new();
+        let cell = Cell { row: 0, column: 0 };
+        let neighbor = Cell { row: 0, column: 1 };
+        maze_graph.add(cell, CellType::NOTHING);
+        maze_graph.add(neighbor, CellType::NOTHING);
+        maze_graph.add_neighbor(&cell, &neighbor);
+        maze_graph.add_neighbor(&neighbor, &cell);
+        maze_graph.set_parent(neighbor, cell);
+
+        assert!(maze_graph.remove_neighbor(&cell, &neighbor));
+        assert!(maze_graph.get_cell(cell).unwrap().neighbors.is_empty());
+        let maze_cell = maze_graph.get_cell(neighbor).unwrap();
+        assert!(maze_cell.neighbors.is_empty());
+        assert_eq!(maze_cell.parent, neighbor);
+
+        assert!(!maze_graph.remove_neighbor(&cell, &neighbor));
+    }
+
+    #[test]
+    fn test_maze_graph_set_walls() {
+        let mut maze_graph = MazeGraph::new();
+        let cell = Cell { row: 0, column: 0 };
+        maze_graph.add(cell, CellType::NOTHING);
+        maze_graph.update_walls(cell, 3);
+        maze_graph.update_walls(cell, 2);
+        assert_eq!(maze_graph.get_cell(cell).unwrap().walls, 3);
+        maze_graph.set_walls(cell, 2);
+        assert_eq!(maze_graph.get_cell(cell).unwrap().walls, 2);
+    }
+
     #[test]
     fn test_maze_graph_update_cell_status() {
         let mut maze_graph = MazeGraph::new();
@@ -204,6 +291,35 @@ mod test {
         assert_eq!(maze_graph.get_size(), 2);
     }
 
+    #[test]
+    fn test_apply_team_message() {
+        let mut maze_graph = MazeGraph::new();
+        let corridor = Cell { row: 0, column: -1 };
+        maze_graph.add(corridor, CellType::NOTHING);
+
+        let dead_end = Cell { row: 2, column: 0 };
+        maze_graph.apply_team_message(&TeamMessage {
+            sender: "Player2".to_string(),
+            info: TeamInfo::DeadEnd(dead_end),
+        });
+        assert_eq!(maze_graph.get_cell_status(dead_end), CellStatus::DeadEnd);
+
+        let exit = Cell { row: 5, column: 5 };
+        maze_graph.apply_team_message(&TeamMessage {
+            sender: "Player2".to_string(),
+            info: TeamInfo::Exit(exit),
+        });
+        assert_eq!(maze_graph.get_cell(exit).unwrap().cell_type, CellType::OBJECTIVE);
+
+        maze_graph.apply_team_message(&TeamMessage {
+            sender: "Player2".to_string(),
+            info: TeamInfo::ClaimCorridor { from: Cell { row: 0, column: 0 }, to: corridor },
+        });
+        let claimed = maze_graph.get_cell(corridor).unwrap();
+        assert_eq!(claimed.status, CellStatus::VISITED);
+        assert_eq!(claimed.visited_by.get("Player2"), Some(&1));
+    }
+
     #[test]
     fn test_maze_graph_get_cell_status() {
         let mut maze_graph = MazeGraph::new();
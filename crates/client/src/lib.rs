@@ -1,6 +1,8 @@
+pub mod agents;
 pub mod client;
 pub mod maze_parser;
 pub mod maze_solver;
+pub mod offline;
 pub mod data_structures {
     pub mod maze_graph;
     pub mod priority_queue;
@@ -1,7 +1,13 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use client::agents::AgentAlgorithm;
 use client::client::{ClientConfig, GameClient};
+use client::offline::{OfflineConfig, run_offline};
 use client::tui;
+use shared::heartbeat::{DEFAULT_INTERVAL_SECS, DEFAULT_TIMEOUT_SECS, HeartbeatConfig};
 use shared::logger::Logger;
+use shared::maze::GeneratorAlgorithm;
+use shared::radar::decode_radar;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(name = "Labyrinth-client")]
@@ -25,6 +31,22 @@ struct Args {
     #[arg(help_heading = "SERVER OPTIONS")]
     retries: u8,
 
+    #[arg(
+        long,
+        default_value_t = DEFAULT_INTERVAL_SECS,
+        help = "Seconds of silence before pinging the server, 0 disables heartbeats."
+    )]
+    #[arg(help_heading = "SERVER OPTIONS")]
+    heartbeat_interval: u64,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_TIMEOUT_SECS,
+        help = "Seconds without any message from the server before reconnecting."
+    )]
+    #[arg(help_heading = "SERVER OPTIONS")]
+    heartbeat_timeout: u64,
+
     #[arg(short, long, default_value = "Groupe1", help = "Team name.")]
     #[arg(help_heading = "PLAYER OPTIONS")]
     team: String,
@@ -33,9 +55,40 @@ struct Args {
     #[arg(help_heading = "PLAYER OPTIONS")]
     players: u8,
 
-    #[arg(long, help = "Run the client in offline mode.")]
+    #[arg(long, help = "Share discoveries with teammates through the server.")]
+    #[arg(help_heading = "PLAYER OPTIONS")]
+    team_chat: bool,
+
+    #[arg(long, help = "Play a local game on a generated maze, without a server.")]
+    #[arg(help_heading = "OFFLINE OPTIONS")]
     offline: bool,
 
+    #[arg(long, help = "Seed of the offline maze, random if not set.")]
+    #[arg(help_heading = "OFFLINE OPTIONS")]
+    seed: Option<u64>,
+
+    #[arg(
+        long,
+        default_value = "Sidewinder",
+        help = "Generation algorithm of the offline maze: Sidewinder, Backtracker, Prim, Kruskal, Wilson or Eller."
+    )]
+    #[arg(help_heading = "OFFLINE OPTIONS")]
+    generator: GeneratorAlgorithm,
+
+    #[arg(long, default_value = "10", help = "Width of the offline maze in cells.")]
+    #[arg(help_heading = "OFFLINE OPTIONS")]
+    #[arg(value_parser = clap::value_parser!(u16).range(2..))]
+    maze_width: u16,
+
+    #[arg(long, default_value = "10", help = "Height of the offline maze in cells.")]
+    #[arg(help_heading = "OFFLINE OPTIONS")]
+    #[arg(value_parser = clap::value_parser!(u16).range(2..))]
+    maze_height: u16,
+
+    #[arg(long, default_value = "10000", help = "Moves after which an offline agent gives up.")]
+    #[arg(help_heading = "OFFLINE OPTIONS")]
+    max_steps: u32,
+
     #[arg(long, help = "Enable debug logs.", default_value = "false")]
     debug: bool,
 
@@ -47,11 +100,22 @@ struct Args {
 
     #[arg(
         long,
-        help = "Select the used algorithm by the agents.",
-        default_value = "Tremeaux",
-        value_parser = ["Tremeaux", "WallFollower", "Alian"]
+        help = "Algorithm used by the agents: Tremeaux, WallFollower or Alian.",
+        default_value = "Tremeaux"
     )]
-    algorithm: String,
+    algorithm: AgentAlgorithm,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Decode a radar view as sent by the server and draw it.
+    Radar {
+        #[arg(help = "Base64 radar view, e.g. bKgGjsIyap8p8aa.")]
+        view: String,
+    },
 }
 
 fn main() {
@@ -59,13 +123,28 @@ fn main() {
     Logger::init(args.debug);
     let logger = Logger::get_instance();
 
+    if let Some(Command::Radar { view }) = &args.command {
+        match decode_radar(view) {
+            Ok(radar) => println!("{}", radar),
+            Err(e) => {
+                logger.error(&format!("Cannot decode radar view {:?}: {}", view, e));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     if args.offline {
-        logger.info("Running in offline mode.");
+        run_offline_game(&args, logger);
         return;
     }
 
-    let config =
-        ClientConfig { server_addr: format!("{}:{}", args.host, args.port), team_name: args.team };
+    let config = ClientConfig {
+        server_addr: format!("{}:{}", args.host, args.port),
+        team_name: args.team,
+        heartbeat: HeartbeatConfig::from_secs(args.heartbeat_interval, args.heartbeat_timeout),
+        team_chat: args.team_chat,
+    };
     let client = GameClient::new(config);
 
     if args.tui {
@@ -108,6 +187,59 @@ fn main() {
     }
 }
 
+fn run_offline_game(args: &Args, logger: &'static Logger) {
+    let config = OfflineConfig {
+        generator: args.generator,
+        width: args.maze_width as usize,
+        height: args.maze_height as usize,
+        seed: args.seed.unwrap_or_else(rand::random),
+        agents: args.players,
+        algorithm: args.algorithm,
+        team_chat: args.team_chat,
+        max_steps: args.max_steps,
+        step_delay: if args.tui {
+            Duration::from_millis(args.refresh_rate)
+        } else {
+            Duration::ZERO
+        },
+        ..OfflineConfig::default()
+    };
+    logger.info(&format!("Running in offline mode with seed {}.", config.seed));
+
+    let summaries = if args.tui {
+        let mut tui = tui::Tui::new(args.refresh_rate).expect("Failed to initialize TUI.");
+        let tui_state = tui.get_state();
+        if let Ok(mut state) = tui_state.lock() {
+            for i in 0..args.players {
+                state.register_agent(format!("Player{}", i + 1));
+            }
+        }
+
+        tui.enter().expect("Failed to enter TUI.");
+        let tui_handle = std::thread::spawn(move || tui.run());
+        let summaries = run_offline(&config, Some(tui_state));
+        // The summary is printed once the player leaves the TUI
+        if let Ok(Err(err)) = tui_handle.join() {
+            logger.error(&format!("TUI error: {}", err));
+        }
+        summaries
+    } else {
+        run_offline(&config, None)
+    };
+
+    match summaries {
+        Ok(summaries) => {
+            for summary in summaries {
+                println!("{}", summary);
+            }
+        }
+        Err(e) => {
+            e.log_error(logger);
+            std::process::exit(1);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,10 +252,52 @@ mod tests {
         assert_eq!(args.retries, 5);
         assert_eq!(args.team, "Groupe1");
         assert_eq!(args.players, 3);
+        assert!(!args.team_chat);
         assert!(!args.offline);
         assert!(!args.debug);
         assert!(!args.tui);
         assert_eq!(args.refresh_rate, 150);
+        assert_eq!(args.heartbeat_interval, DEFAULT_INTERVAL_SECS);
+        assert_eq!(args.heartbeat_timeout, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(args.algorithm, AgentAlgorithm::Tremeaux);
+        assert!(args.command.is_none());
+    }
+
+    #[test]
+    fn test_offline_args() {
+        let args = Args::parse_from(["test"]);
+        assert!(args.seed.is_none());
+        assert_eq!(args.generator, GeneratorAlgorithm::Sidewinder);
+        assert_eq!((args.maze_width, args.maze_height), (10, 10));
+        assert_eq!(args.max_steps, 10000);
+
+        let args = Args::parse_from(
+            ["test", "--offline", "--seed", "7", "--generator", "wilson", "--maze-width", "4"]
+                .iter(),
+        );
+        assert!(args.offline);
+        assert_eq!(args.seed, Some(7));
+        assert_eq!(args.generator, GeneratorAlgorithm::Wilson);
+        assert_eq!(args.maze_width, 4);
+        assert!(Args::try_parse_from(["test", "--maze-height", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_algorithms() {
+        for algorithm in AgentAlgorithm::ALL {
+            let name = algorithm.to_string();
+            let args = Args::try_parse_from(["test", "--algorithm", &name]).unwrap();
+            assert_eq!(args.algorithm, algorithm);
+        }
+        assert!(Args::try_parse_from(["test", "--algorithm", "RightHand"]).is_err());
+    }
+
+    #[test]
+    fn test_radar_subcommand() {
+        let args = Args::try_parse_from(["test", "radar", "bKgGjsIyap8p8aa"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Radar { view }) if view == "bKgGjsIyap8p8aa"));
+
+        assert!(Args::try_parse_from(["test", "radar"]).is_err());
     }
 
     #[test]
@@ -159,6 +333,8 @@ mod tests {
         let config = ClientConfig {
             server_addr: format!("{}:{}", args.host, args.port),
             team_name: args.team,
+            heartbeat: HeartbeatConfig::default(),
+            team_chat: args.team_chat,
         };
 
         assert_eq!(config.server_addr, "example.com:9000");
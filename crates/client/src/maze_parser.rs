@@ -1,3 +1,4 @@
+use shared::errors::{MazeError, RadarError};
 use shared::maze::{Maze, PositionType};
 use shared::messages::Direction;
 use shared::{
@@ -133,7 +134,22 @@ impl Default for Player {
     }
 }
 
-pub fn maze_to_graph(radar_view: &Radar, player: &Player, maze_graph: &mut MazeGraph) {
+/// Adds the cells and passages seen on the radar to the graph.
+///
+/// Fails without touching the graph when the radar holds fewer than 12 horizontal or
+/// vertical passages, since the wall lookups below index into both.
+pub fn maze_to_graph(
+    radar_view: &Radar,
+    player: &Player,
+    maze_graph: &mut MazeGraph,
+) -> Result<(), RadarError> {
+    const NUM_PASSAGES: usize = 12;
+    for passages in [&radar_view.horizontal, &radar_view.vertical] {
+        if passages.len() < NUM_PASSAGES {
+            return Err(RadarError::WrongLength { expected: NUM_PASSAGES, actual: passages.len() });
+        }
+    }
+
     let directions_mask = get_direction_mask(player);
 
     for cell_id in 0..radar_view.cells.len() {
@@ -148,7 +164,17 @@ pub fn maze_to_graph(radar_view: &Radar, player: &Player, maze_graph: &mut MazeG
         }
 
         let walls = get_cell_walls(cell_id, &radar_view.horizontal, &radar_view.vertical);
-        maze_graph.update_walls(cell_pos, walls as usize);
+        if are_cell_sides_known(cell_id, &radar_view.horizontal, &radar_view.vertical) {
+            maze_graph.set_walls(cell_pos, walls as usize);
+        } else {
+            maze_graph.update_walls(cell_pos, walls as usize);
+        }
+
+        // Walls of a dynamic maze can close a passage recorded as open by an earlier radar
+        for neighbor_id in walled_neighbors(cell_id, &radar_view.horizontal, &radar_view.vertical) {
+            let neighbor = player.position + directions_mask[neighbor_id];
+            maze_graph.remove_neighbor(&cell_pos, &neighbor);
+        }
 
         let mut neigbors_to_add: Vec<Cell> = Vec::new();
 
@@ -201,6 +227,8 @@ pub fn maze_to_graph(radar_view: &Radar, player: &Player, maze_graph: &mut MazeG
             maze_graph.add_neighbor(&neighbor, &cell_pos);
         }
     }
+
+    Ok(())
 }
 
 fn is_right_cell_accessible(cell_id: usize, vertical: &[Passages]) -> bool {
@@ -219,6 +247,35 @@ fn is_top_cell_accessible(cell_id: usize, horizontal: &[Passages]) -> bool {
     cell_id > 2 && horizontal[cell_id] == Passages::OPEN
 }
 
+/// Radar ids of the cells next to `cell_id` with a wall in between.
+fn walled_neighbors(cell_id: usize, horizontal: &[Passages], vertical: &[Passages]) -> Vec<usize> {
+    let mut neighbors = Vec::new();
+    if cell_id > 2 && horizontal[cell_id] == Passages::WALL {
+        neighbors.push(cell_id - 3);
+    }
+    if cell_id < 6 && horizontal[cell_id + 3] == Passages::WALL {
+        neighbors.push(cell_id + 3);
+    }
+    if cell_id % 3 != 0 && vertical[cell_id + cell_id / 3] == Passages::WALL {
+        neighbors.push(cell_id - 1);
+    }
+    if cell_id % 3 != 2 && vertical[cell_id + cell_id / 3 + 1] == Passages::WALL {
+        neighbors.push(cell_id + 1);
+    }
+    neighbors
+}
+
+fn are_cell_sides_known(cell_id: usize, horizontal: &[Passages], vertical: &[Passages]) -> bool {
+    [
+        &horizontal[cell_id],
+        &horizontal[cell_id + 3],
+        &vertical[cell_id + cell_id / 3],
+        &vertical[cell_id + cell_id / 3 + 1],
+    ]
+    .iter()
+    .all(|&passage| *passage != Passages::UNDEFINED)
+}
+
 fn get_cell_walls(cell_id: usize, horizontal: &[Passages], vertical: &[Passages]) -> u8 {
     let mut walls = 0;
 
@@ -271,14 +328,36 @@ fn get_direction_mask(player: &Player) -> Vec<Cell> {
     cell_mask
 }
 
-pub fn maze_parser(input: &str) -> Maze {
-    if input.is_empty() {
-        return Maze::new(Vec::new(), Cell { row: 0, column: 0 }, Cell { row: 0, column: 0 });
-    }
+/// Parses a text maze where '2' marks the entry, '3' the exit, spaces are open and any
+/// other character is a wall.
+///
+/// Blank lines around the maze are ignored. Every remaining line must have the same
+/// number of characters, and the maze must pass [`Maze::validate`].
+pub fn maze_parser(input: &str) -> Result<Maze, MazeError> {
+    let maze = maze_parser_unchecked(input)?;
+    maze.validate()?;
+    Ok(maze)
+}
 
-    let lines: Vec<&str> =
+/// Parses a text maze like [`maze_parser`] without validating it, for inputs only read to
+/// measure the parser and the solvers. The entry and the exit are at the origin when their
+/// markers are missing.
+pub fn maze_parser_unchecked(input: &str) -> Result<Maze, MazeError> {
+    let mut lines: Vec<&str> =
         input.lines().skip_while(|line| line.chars().all(char::is_whitespace)).collect();
-    let (height, width) = (lines.len(), lines[0].len());
+    while lines.last().is_some_and(|line| line.chars().all(char::is_whitespace)) {
+        lines.pop();
+    }
+
+    let Some(first) = lines.first() else {
+        return Err(MazeError::Empty);
+    };
+    let (height, width) = (lines.len(), first.chars().count());
+    if let Some((row, found)) =
+        lines.iter().map(|line| line.chars().count()).enumerate().find(|&(_, found)| found != width)
+    {
+        return Err(MazeError::RaggedRow { row, expected: width, found });
+    }
 
     let map = vec![vec![0u16; width]; height];
     let entry = Cell { row: 0, column: 0 };
@@ -308,7 +387,7 @@ pub fn maze_parser(input: &str) -> Maze {
         }
     }
 
-    maze
+    Ok(maze)
 }
 
 #[cfg(test)]
@@ -318,7 +397,7 @@ mod tests {
 
     #[test]
     fn test_maze_to_graph() {
-        let decoded = radar::decode_base64("Hjeikcyc/W8a8pa");
+        let decoded = radar::decode_base64("Hjeikcyc/W8a8pa").unwrap();
         let data = radar::extract_data(&decoded).unwrap();
 
         let mut p = Player {
@@ -327,29 +406,135 @@ mod tests {
             last_position: None,
         };
         let mut m = MazeGraph::new();
-        maze_to_graph(&data, &p, &mut m);
+        maze_to_graph(&data, &p, &mut m).unwrap();
 
         p.direction = Direction::Right;
         p.position = p.position + Cell { row: 1, column: 0 };
 
-        let decoded = radar::decode_base64("kOuczzGa//apaaa");
+        let decoded = radar::decode_base64("kOuczzGa//apaaa").unwrap();
         let data = radar::extract_data(&decoded).unwrap();
-        maze_to_graph(&data, &p, &mut m);
+        maze_to_graph(&data, &p, &mut m).unwrap();
     }
 
     #[test]
-    fn test_maze_parser() {
-        let input = "###\n# #\n###";
-        let expected = vec![vec![1, 1, 1], vec![1, 0, 1], vec![1, 1, 1]];
-        assert_eq!(maze_parser(input).map, expected);
+    fn test_maze_to_graph_forgets_closed_passages() {
+        let mut radar = Radar {
+            horizontal: vec![Passages::OPEN; 12],
+            vertical: vec![Passages::OPEN; 12],
+            cells: vec![CellType::NOTHING; 9],
+        };
+        let player = Player::new();
+        let center = player.position;
+        // The cell in front of the player, above the center of the radar
+        let front = player.position + get_direction_mask(&player)[1];
 
-        let input = "#### \n#  ##\n#### ";
-        let expected = vec![vec![1, 1, 1, 1, 0], vec![1, 0, 0, 1, 1], vec![1, 1, 1, 1, 0]];
-        assert_eq!(maze_parser(input).map, expected);
+        let mut graph = MazeGraph::new();
+        maze_to_graph(&radar, &player, &mut graph).unwrap();
+        assert!(graph.get_cell(center).unwrap().neighbors.contains(&front));
+        assert_eq!(graph.get_cell(center).unwrap().walls, 0);
 
-        let input = "#  # \n#  # \n#  # ";
+        // A wall now closes the passage between the center and the front cell
+        radar.horizontal[4] = Passages::WALL;
+        maze_to_graph(&radar, &player, &mut graph).unwrap();
+        assert!(!graph.get_cell(center).unwrap().neighbors.contains(&front));
+        assert!(!graph.get_cell(front).unwrap().neighbors.contains(&center));
+        assert_eq!(graph.get_cell(center).unwrap().walls, 1);
+
+        // And opens again
+        radar.horizontal[4] = Passages::OPEN;
+        maze_to_graph(&radar, &player, &mut graph).unwrap();
+        assert!(graph.get_cell(center).unwrap().neighbors.contains(&front));
+        assert_eq!(graph.get_cell(center).unwrap().walls, 0);
+    }
+
+    #[test]
+    fn test_maze_to_graph_rejects_short_radar() {
+        // Passage code 3 is skipped while decoding, leaving fewer than 12 passages.
+        let radar = Radar {
+            horizontal: vec![Passages::OPEN; 11],
+            vertical: vec![Passages::OPEN; 12],
+            cells: vec![CellType::NOTHING; 9],
+        };
+
+        let mut graph = MazeGraph::new();
+        let result = maze_to_graph(&radar, &Player::new(), &mut graph);
+        assert_eq!(result, Err(RadarError::WrongLength { expected: 12, actual: 11 }));
+        assert!(!graph.contains(&Cell { row: 0, column: 0 }));
+    }
+
+    #[test]
+    fn test_maze_parser() {
+        let input = "#####\n#2 3#\n#####";
+        let expected = vec![vec![1, 1, 1, 1, 1], vec![1, 2, 0, 3, 1], vec![1, 1, 1, 1, 1]];
+        assert_eq!(maze_parser(input).unwrap().map, expected);
+
+        // Any other character is a wall
+        let input = "######\n#2 #x#\n#  3##\n######";
+        let maze = maze_parser(input).unwrap();
+        assert_eq!(maze.map[1], vec![1, 2, 0, 1, 1, 1]);
+        assert_eq!(maze.map[2], vec![1, 0, 0, 3, 1, 1]);
+        assert_eq!(
+            (maze.entry, maze.exit),
+            (Cell { row: 1, column: 1 }, Cell { row: 2, column: 3 })
+        );
+
+        // Without markers and with holes in the outer wall
+        for input in ["###\n# #\n###", "#### \n#  ##\n#### ", "#  # \n#  # \n#  # "] {
+            assert!(matches!(maze_parser(input), Err(MazeError::Invalid(_))));
+        }
         let expected = vec![vec![1, 0, 0, 1, 0], vec![1, 0, 0, 1, 0], vec![1, 0, 0, 1, 0]];
-        assert_eq!(maze_parser(input).map, expected);
+        assert_eq!(maze_parser_unchecked("#  # \n#  # \n#  # ").unwrap().map, expected);
+        assert!(matches!(maze_parser_unchecked("\n\n"), Err(MazeError::Empty)));
+    }
+
+    #[test]
+    fn test_maze_parser_counts_characters() {
+        let maze = maze_parser("\n┌─┐\n2 3\n└─┘\n\n").unwrap();
+        assert_eq!((maze.row_len, maze.col_len), (3, 3));
+        assert_eq!(maze.entry, Cell { row: 1, column: 0 });
+        assert_eq!(maze.exit, Cell { row: 1, column: 2 });
+    }
+
+    #[test]
+    fn test_maze_parser_rejects_malformed_input() {
+        assert_eq!(maze_parser("").err(), Some(MazeError::Empty));
+        assert_eq!(maze_parser(" \n\t\n").err(), Some(MazeError::Empty));
+        assert_eq!(
+            maze_parser("###\n#  #\n###").err(),
+            Some(MazeError::RaggedRow { row: 1, expected: 3, found: 4 })
+        );
+    }
+
+    #[test]
+    fn test_maze_parser_rejects_invalid_mazes() {
+        use shared::maze::validation::MazeIssue;
+
+        let issues = |input| match maze_parser(input) {
+            Err(MazeError::Invalid(issues)) => issues,
+            other => panic!("expected an invalid maze, got {:?}", other.map(|maze| maze.map)),
+        };
+        assert_eq!(
+            issues("#####\n#2#3#\n#####"),
+            vec![
+                MazeIssue::UnreachableExit,
+                MazeIssue::UnreachableRegion { first: Cell { row: 1, column: 3 }, size: 1 },
+            ]
+        );
+        assert_eq!(
+            issues("#####\n#2 3 \n#####"),
+            vec![MazeIssue::OpenBoundary(Cell { row: 1, column: 4 })]
+        );
+        assert_eq!(
+            issues("#####\n#2 2#\n#####"),
+            vec![
+                MazeIssue::MultipleEntries(vec![
+                    Cell { row: 1, column: 1 },
+                    Cell { row: 1, column: 3 }
+                ]),
+                MazeIssue::MissingExit,
+            ]
+        );
+        assert!(maze_parser("#####\n#2 3#\n#####").is_ok());
     }
 
     #[test]
@@ -1,11 +1,130 @@
 use crate::data_structures::priority_queue::{Node, PriorityQueue};
+use shared::maze::grid::{GridPosition, MazeGrid};
+use shared::maze::levels::{LevelCell, LevelMaze};
 use shared::maze::{Cell, Directions, Maze};
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PrintPathMode {
     None = 0,
     Visited = 1,
     VisitedNumber = 2,
+    Path = 3,
+}
+
+impl fmt::Display for PrintPathMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PrintPathMode::None => "none",
+            PrintPathMode::Visited => "visited",
+            PrintPathMode::VisitedNumber => "visited-number",
+            PrintPathMode::Path => "path",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for PrintPathMode {
+    type Err = String;
+
+    /// Parses a print mode name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            PrintPathMode::None,
+            PrintPathMode::Visited,
+            PrintPathMode::VisitedNumber,
+            PrintPathMode::Path,
+        ]
+        .into_iter()
+        .find(|mode| mode.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| {
+            format!("unknown print mode '{}', expected none, visited, visited-number or path", s)
+        })
+    }
+}
+
+/// Shortest path solvers working on a [`Maze`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchAlgorithm {
+    /// Fewest moves, ignoring the terrain.
+    Bfs,
+    /// Cheapest path, visiting the cells in order of cost.
+    Dijkstra,
+    /// Cheapest path, guided toward the exit.
+    AStar,
+}
+
+impl SearchAlgorithm {
+    pub const ALL: [SearchAlgorithm; 3] =
+        [SearchAlgorithm::Bfs, SearchAlgorithm::Dijkstra, SearchAlgorithm::AStar];
+
+    pub fn search(self, maze: &Maze) -> Search {
+        match self {
+            SearchAlgorithm::Bfs => bfs_search(maze),
+            SearchAlgorithm::Dijkstra => dijkstra_search(maze),
+            SearchAlgorithm::AStar => a_star_search(maze),
+        }
+    }
+}
+
+impl fmt::Display for SearchAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SearchAlgorithm::Bfs => "bfs",
+            SearchAlgorithm::Dijkstra => "dijkstra",
+            SearchAlgorithm::AStar => "a-star",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SearchAlgorithm {
+    type Err = String;
+
+    /// Parses a solver name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SearchAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown solver '{}', expected bfs, dijkstra or a-star", s))
+    }
+}
+
+/// Result of a shortest path search.
+pub struct Search {
+    /// Positions from the entry to the exit, empty if the exit cannot be reached.
+    pub path: Vec<Cell>,
+    /// Order in which each position was visited, -1 if it never was.
+    pub visited_points: Vec<Vec<i32>>,
+    /// Number of steps taken by the search.
+    pub steps: i32,
+    /// Cost of walking along the path, its number of moves in a maze without terrain.
+    pub cost: u32,
+}
+
+impl Search {
+    /// Prints the maze to the terminal as requested by `print`, nothing if no path was found.
+    pub fn print(&self, maze: &Maze, print: PrintPathMode) {
+        if self.path.is_empty() {
+            return;
+        }
+        match print {
+            PrintPathMode::Path => maze.print_path(&self.path),
+            PrintPathMode::Visited => {
+                maze.print_visited(&self.visited_points);
+                println!("Number of steps: {}", self.steps);
+            }
+            PrintPathMode::VisitedNumber => {
+                maze.print_visited_number(&self.visited_points);
+                println!("Number of steps: {}", self.steps);
+            }
+            _ => {}
+        }
+    }
 }
 
 /// Finds the shortest path in a maze using the Breadth-First Search (BFS) algorithm.
@@ -30,6 +149,14 @@ pub enum PrintPathMode {
 /// let shortest_path = bfs_shortest_path(&maze, PrintPathMode::None);
 /// ```
 pub fn bfs_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
+    let search = bfs_search(maze);
+    search.print(maze, print);
+    search.path
+}
+
+/// Runs the same search as [`bfs_shortest_path`] and returns what it visited along with
+/// the path.
+pub fn bfs_search(maze: &Maze) -> Search {
     let mut queue: VecDeque<Cell> = VecDeque::new();
 
     let Maze { entry, exit, row_len, col_len, .. } = *maze;
@@ -51,18 +178,9 @@ pub fn bfs_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
         };
 
         if curr == exit {
-            match print {
-                PrintPathMode::Visited => {
-                    maze.print_visited(&visited_points);
-                    println!("Number of steps: {}", index);
-                }
-                PrintPathMode::VisitedNumber => {
-                    maze.print_visited_number(&visited_points);
-                    println!("Number of steps: {}", index);
-                }
-                _ => {}
-            }
-            return reconstruct_shortest_path(maze, previous_path);
+            let path = reconstruct_shortest_path(maze, previous_path);
+            let cost = maze.path_cost(&path);
+            return Search { path, visited_points, steps: index, cost };
         }
 
         for direction in directions.iter() {
@@ -86,6 +204,65 @@ pub fn bfs_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
             index += 1;
         }
     }
+    Search { path: vec![], visited_points, steps: index, cost: 0 }
+}
+
+/// Finds the shortest path from the entry to the exit of any [`MazeGrid`], in cells.
+///
+/// Unlike [`bfs_shortest_path`] the visited cells are tracked in a single flat buffer of
+/// `u32` indices, which keeps very large mazes such as a 10,000 x 10,000
+/// [`CompactMaze`](shared::maze::grid::CompactMaze) within a few hundred megabytes.
+/// Returns an empty path if the exit is unreachable or the maze has more than
+/// `u32::MAX - 1` cells.
+///
+/// # Examples
+///
+/// ```rust
+/// use client::maze_solver::bfs_grid_path;
+/// use shared::maze::GeneratorAlgorithm;
+/// use shared::maze::grid::{CompactMaze, MazeGrid};
+/// use shared::maze_generator::generate_grid;
+///
+/// let maze: CompactMaze = generate_grid(GeneratorAlgorithm::Eller, 100, 100, 42);
+/// let path = bfs_grid_path(&maze);
+/// assert_eq!(path.first(), Some(&maze.entry()));
+/// assert_eq!(path.last(), Some(&maze.exit()));
+/// ```
+pub fn bfs_grid_path<G: MazeGrid>(maze: &G) -> Vec<GridPosition> {
+    const UNVISITED: u32 = u32::MAX;
+    let width = maze.width();
+    let cells = width * maze.height();
+    if cells == 0 || cells >= UNVISITED as usize {
+        return vec![];
+    }
+
+    let index = |(row, column): GridPosition| row * width + column;
+    let (entry, exit) = (maze.entry(), maze.exit());
+
+    // The cell each visited cell was reached from, the entry points to itself
+    let mut previous = vec![UNVISITED; cells];
+    previous[index(entry)] = index(entry) as u32;
+    let mut queue = VecDeque::from([entry]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == exit {
+            let mut path = vec![exit];
+            let mut cell = index(exit);
+            while cell != index(entry) {
+                cell = previous[cell] as usize;
+                path.push((cell / width, cell % width));
+            }
+            path.reverse();
+            return path;
+        }
+
+        for next in maze.open_neighbors(current) {
+            if previous[index(next)] == UNVISITED {
+                previous[index(next)] = index(current) as u32;
+                queue.push_back(next);
+            }
+        }
+    }
     vec![]
 }
 
@@ -108,26 +285,52 @@ fn get_manhattan_distance(source_cell: &Cell, goal_cell: &Cell) -> i32 {
 }
 
 pub fn a_star_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
+    let search = a_star_search(maze);
+    search.print(maze, print);
+    search.path
+}
+
+/// Runs the same search as [`a_star_shortest_path`] and returns what it visited along with
+/// the path.
+///
+/// Steps are weighted by the terrain they lead to, the Manhattan distance to the exit never
+/// overestimates the remaining cost since every step costs at least 1.
+pub fn a_star_search(maze: &Maze) -> Search {
+    let exit = maze.exit;
+    weighted_search(maze, |cell| get_manhattan_distance(cell, &exit))
+}
+
+/// Finds the cheapest path in a maze with terrain, where mud and water cost more to walk
+/// through than plain passages. Same as [`bfs_shortest_path`] on a maze without terrain.
+pub fn dijkstra_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
+    let search = dijkstra_search(maze);
+    search.print(maze, print);
+    search.path
+}
+
+/// Runs the same search as [`dijkstra_shortest_path`] and returns what it visited along
+/// with the path.
+pub fn dijkstra_search(maze: &Maze) -> Search {
+    weighted_search(maze, |_| 0)
+}
+
+/// Best-first search on the cost of the steps so far plus `heuristic`, the estimated cost
+/// left to the exit.
+fn weighted_search(maze: &Maze, heuristic: impl Fn(&Cell) -> i32) -> Search {
     let Maze { entry, exit, row_len, col_len, .. } = *maze;
     let directions = [Directions::NORTH, Directions::SOUTH, Directions::WEST, Directions::EAST];
 
     let mut g_cost = vec![vec![-1; col_len]; row_len];
-    let mut f_cost = vec![vec![-1; col_len]; row_len];
-
     let mut previous_path: Vec<Vec<Cell>> =
         vec![vec![Cell { row: -1, column: -1 }; col_len]; row_len];
     let mut visited_points: Vec<Vec<i32>> = vec![vec![-1; col_len]; row_len];
 
-    let start_row = entry.row as usize;
-    let start_column = entry.column as usize;
-
-    g_cost[start_row][start_column] = 0;
-    f_cost[start_row][start_column] = get_manhattan_distance(&entry, &exit);
+    g_cost[entry.row as usize][entry.column as usize] = 0;
 
     let mut open = PriorityQueue::new();
     let mut index = 0;
 
-    open.enqueue(Node { priority_f: f_cost[start_row][start_column], cell: entry });
+    open.enqueue(Node { priority_f: heuristic(&entry), cell: entry });
 
     while !open.is_empty() {
         let Node { cell: curr_cell, .. } = open.dequeue();
@@ -135,21 +338,17 @@ pub fn a_star_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
         let curr_row = curr_cell.row as usize;
         let curr_col = curr_cell.column as usize;
 
+        // A cell is queued again each time a cheaper way to it is found, only the first
+        // dequeue counts
+        if visited_points[curr_row][curr_col] != -1 {
+            continue;
+        }
         visited_points[curr_row][curr_col] = index;
 
-        if curr_cell.row == maze.exit.row && curr_cell.column == maze.exit.column {
-            match print {
-                PrintPathMode::Visited => {
-                    maze.print_visited(&visited_points);
-                    println!("Number of steps: {}", index);
-                }
-                PrintPathMode::VisitedNumber => {
-                    maze.print_visited_number(&visited_points);
-                    println!("Number of steps: {}", index);
-                }
-                _ => {}
-            }
-            return reconstruct_shortest_path(maze, previous_path);
+        if curr_cell == exit {
+            let path = reconstruct_shortest_path(maze, previous_path);
+            let cost = g_cost[curr_row][curr_col] as u32;
+            return Search { path, visited_points, steps: index, cost };
         }
 
         for direction in directions.iter() {
@@ -157,39 +356,162 @@ pub fn a_star_shortest_path(maze: &Maze, print: PrintPathMode) -> Vec<Cell> {
 
             if maze.is_cell_out_of_bound(&neighbour_cell)
                 || !maze.is_cell_walkable(&neighbour_cell, &visited_points)
-                || visited_points[neighbour_cell.row as usize][neighbour_cell.column as usize] != -1
             {
                 continue;
             }
+            let Some(step_cost) = maze.cost(neighbour_cell) else {
+                continue;
+            };
 
-            let neighbour_g_score = g_cost[curr_row][curr_col] + 1;
-
-            if neighbour_g_score
-                < f_cost[neighbour_cell.row as usize][neighbour_cell.column as usize]
-                || f_cost[neighbour_cell.row as usize][neighbour_cell.column as usize] < 0
-            {
-                if open.contains(&neighbour_cell) {
-                    continue;
-                }
-                let neighbour_h_score = get_manhattan_distance(&neighbour_cell, &exit);
-                let neighbour_f_score = neighbour_g_score + neighbour_h_score;
-
-                previous_path[neighbour_cell.row as usize][neighbour_cell.column as usize] =
-                    curr_cell;
-                g_cost[neighbour_cell.row as usize][neighbour_cell.column as usize] =
-                    neighbour_g_score;
-                f_cost[neighbour_cell.row as usize][neighbour_cell.column as usize] =
-                    neighbour_f_score;
-                open.enqueue(Node { priority_f: neighbour_f_score, cell: neighbour_cell });
+            let row = neighbour_cell.row as usize;
+            let column = neighbour_cell.column as usize;
+            let neighbour_g_score = g_cost[curr_row][curr_col] + step_cost as i32;
+
+            if g_cost[row][column] < 0 || neighbour_g_score < g_cost[row][column] {
+                previous_path[row][column] = curr_cell;
+                g_cost[row][column] = neighbour_g_score;
+                open.enqueue(Node {
+                    priority_f: neighbour_g_score + heuristic(&neighbour_cell),
+                    cell: neighbour_cell,
+                });
             }
         }
         index += 1;
     }
-    vec![]
+    Search { path: vec![], visited_points, steps: index, cost: 0 }
+}
+
+/// Result of a shortest path search through the levels of a [`LevelMaze`].
+pub struct LevelSearch {
+    /// Positions from the entry of the first level to the exit of the last one, empty if
+    /// the exit cannot be reached. Taking the stairs moves to the same map position on
+    /// another level.
+    pub path: Vec<LevelCell>,
+    /// Number of positions visited by the search.
+    pub steps: i32,
+    /// Cost of walking along the path, its number of moves in a maze without terrain.
+    pub cost: u32,
+}
+
+/// Finds the path with the fewest moves through a maze with several levels, taking the
+/// stairs when needed.
+///
+/// # Examples
+///
+/// ```rust
+/// use client::maze_solver::level_bfs_search;
+/// use shared::maze::levels::LevelMaze;
+/// use shared::maze::{Braiding, GeneratorAlgorithm, Placement};
+///
+/// let maze = LevelMaze::generate(
+///     GeneratorAlgorithm::Eller, 10, 10, 3, Braiding::Perfect, Placement::Edges, 42,
+/// );
+/// let search = level_bfs_search(&maze);
+/// assert_eq!(search.path.first(), Some(&maze.entry()));
+/// assert_eq!(search.path.last(), Some(&maze.exit()));
+/// ```
+pub fn level_bfs_search(maze: &LevelMaze) -> LevelSearch {
+    let (entry, exit) = (maze.entry(), maze.exit());
+    let mut previous = HashMap::from([(entry, entry)]);
+    let mut queue = VecDeque::from([entry]);
+    let mut steps = 0;
+
+    while let Some(current) = queue.pop_front() {
+        steps += 1;
+        if current == exit {
+            let path = reconstruct_level_path(&previous, entry, exit);
+            let cost = path.iter().skip(1).filter_map(|&position| maze.cost(position)).sum();
+            return LevelSearch { path, steps, cost };
+        }
+
+        for (next, _) in level_moves(maze, current) {
+            if let Entry::Vacant(slot) = previous.entry(next) {
+                slot.insert(current);
+                queue.push_back(next);
+            }
+        }
+    }
+    LevelSearch { path: vec![], steps, cost: 0 }
+}
+
+/// Finds the cheapest path through a maze with several levels, weighted by the terrain
+/// like [`a_star_search`].
+///
+/// Only the stairs change levels, one level per move, and stairs join the same map
+/// position on both levels. The Manhattan distance to the exit plus the number of levels
+/// left therefore never overestimates the remaining cost.
+pub fn level_a_star_search(maze: &LevelMaze) -> LevelSearch {
+    let (entry, exit) = (maze.entry(), maze.exit());
+    let heuristic = |position: LevelCell| {
+        get_manhattan_distance(&position.cell, &exit.cell)
+            + position.level.abs_diff(exit.level) as i32
+    };
+
+    let mut g_cost = HashMap::from([(entry, 0)]);
+    let mut previous = HashMap::from([(entry, entry)]);
+    let mut visited = HashSet::new();
+    // Positions are ordered by estimated cost, then by level and map position so ties are
+    // broken the same way on every run
+    let key = |priority: i32, position: LevelCell| {
+        Reverse((priority, position.level, position.cell.row, position.cell.column))
+    };
+    let mut open = BinaryHeap::from([key(heuristic(entry), entry)]);
+
+    while let Some(Reverse((_, level, row, column))) = open.pop() {
+        let current = LevelCell { level, cell: Cell { row, column } };
+        // A position is queued again each time a cheaper way to it is found, only the first
+        // pop counts
+        if !visited.insert(current) {
+            continue;
+        }
+
+        let cost = g_cost[&current];
+        if current == exit {
+            let path = reconstruct_level_path(&previous, entry, exit);
+            return LevelSearch { path, steps: visited.len() as i32, cost: cost as u32 };
+        }
+
+        for (next, step_cost) in level_moves(maze, current) {
+            let next_cost = cost + step_cost as i32;
+            if g_cost.get(&next).is_none_or(|&known| next_cost < known) {
+                g_cost.insert(next, next_cost);
+                previous.insert(next, current);
+                open.push(key(next_cost + heuristic(next), next));
+            }
+        }
+    }
+    LevelSearch { path: vec![], steps: visited.len() as i32, cost: 0 }
+}
+
+/// The positions reachable from `position` in one move along with the cost of that move:
+/// its walkable neighbours on the same level and the other end of the stairs, if any.
+fn level_moves(maze: &LevelMaze, position: LevelCell) -> Vec<(LevelCell, u32)> {
+    [Directions::NORTH, Directions::SOUTH, Directions::WEST, Directions::EAST]
+        .into_iter()
+        .map(|direction| LevelCell { level: position.level, cell: position.cell + direction })
+        .chain(maze.stairs_at(position))
+        .filter_map(|next| maze.cost(next).map(|cost| (next, cost)))
+        .collect()
+}
+
+fn reconstruct_level_path(
+    previous: &HashMap<LevelCell, LevelCell>,
+    entry: LevelCell,
+    exit: LevelCell,
+) -> Vec<LevelCell> {
+    let mut path = vec![exit];
+    let mut position = exit;
+    while position != entry {
+        position = previous[&position];
+        path.push(position);
+    }
+    path.reverse();
+    path
 }
 
 #[cfg(test)]
 mod tests {
+    use shared::maze::PositionType;
     use shared::maze_generator::sidewinder;
 
     use super::*;
@@ -343,10 +665,162 @@ mod tests {
         assert_eq!(a_star_shortest_path(&maze, PrintPathMode::None), shortest_path);
     }
 
+    #[test]
+    fn test_bfs_grid_path_matches_bfs_shortest_path() {
+        use shared::maze::GeneratorAlgorithm;
+        use shared::maze::grid::CompactMaze;
+        use shared::maze_generator::generate_grid;
+
+        for algorithm in GeneratorAlgorithm::ALL {
+            let maze: Maze = generate_grid(algorithm, 15, 12, 77);
+            let compact: CompactMaze = generate_grid(algorithm, 15, 12, 77);
+
+            let path = bfs_grid_path(&compact);
+            assert_eq!(path, bfs_grid_path(&maze));
+            // The doubled map also walks through the positions between two cells
+            assert_eq!(path.len() * 2 - 1, bfs_shortest_path(&maze, PrintPathMode::None).len());
+            assert!(
+                path.windows(2).all(|step| compact.open_neighbors(step[0]).any(|n| n == step[1]))
+            );
+        }
+    }
+
+    #[test]
+    fn test_bfs_grid_path_unreachable() {
+        use shared::maze::grid::{CompactMaze, Direction};
+
+        let mut maze = CompactMaze::with_walls(3, 1);
+        maze.set_entry_exit((0, 0), (0, 2));
+        maze.carve((0, 0), Direction::East);
+        assert!(bfs_grid_path(&maze).is_empty());
+
+        maze.carve((0, 1), Direction::East);
+        assert_eq!(bfs_grid_path(&maze), vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
     #[test]
     fn test_random_generated() {
-        let maze = sidewinder(10, 10, false, 5849);
+        let maze = sidewinder(10, 10, 5849);
         let shortest_path = bfs_shortest_path(&maze, PrintPathMode::None);
         assert!(!shortest_path.is_empty());
     }
+
+    #[test]
+    fn test_search_algorithms() {
+        let maze = sidewinder(12, 8, 3);
+        let bfs = SearchAlgorithm::Bfs.search(&maze);
+        let a_star = SearchAlgorithm::AStar.search(&maze);
+
+        assert_eq!(bfs.path, bfs_shortest_path(&maze, PrintPathMode::None));
+        assert_eq!(a_star.path.len(), bfs.path.len());
+        for search in [&bfs, &a_star] {
+            assert_eq!(
+                search.visited_points[maze.entry.row as usize][maze.entry.column as usize],
+                0
+            );
+            assert!(search.path.iter().all(|cell| {
+                search.visited_points[cell.row as usize][cell.column as usize] >= 0
+            }));
+        }
+        // A* is guided toward the exit and visits no more than BFS
+        let visited = |search: &Search| {
+            search.visited_points.iter().flatten().filter(|&&step| step >= 0).count()
+        };
+        assert!(visited(&a_star) <= visited(&bfs));
+
+        assert_eq!("A-Star".parse(), Ok(SearchAlgorithm::AStar));
+        assert_eq!("visited-number".parse(), Ok(PrintPathMode::VisitedNumber));
+        assert_eq!(PrintPathMode::Path.to_string(), "path");
+        assert_eq!(SearchAlgorithm::Bfs.to_string(), "bfs");
+        assert!("dfs".parse::<SearchAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_weighted_search_avoids_mud() {
+        let mud = PositionType::MUD;
+        let maze_map = vec![
+            vec![1, 1, 1, 1, 1, 1, 1],
+            vec![1, 2, mud, mud, mud, 3, 1],
+            vec![1, 0, 1, 1, 1, 0, 1],
+            vec![1, 0, 0, 0, 0, 0, 1],
+            vec![1, 1, 1, 1, 1, 1, 1],
+        ];
+        let maze = Maze::new(maze_map, Cell { row: 1, column: 1 }, Cell { row: 1, column: 5 });
+
+        // BFS walks straight through the mud, 3 mud positions and the exit
+        let bfs = bfs_search(&maze);
+        assert_eq!(bfs.path.len(), 5);
+        assert_eq!(bfs.cost, 3 * 3 + 1);
+
+        // The detour is 8 moves long but cheaper
+        for search in [dijkstra_search(&maze), a_star_search(&maze)] {
+            assert_eq!(search.path.len(), 9);
+            assert_eq!(search.cost, 8);
+            assert_eq!(maze.path_cost(&search.path), search.cost);
+            assert!(search.path.iter().all(|cell| maze.cost(*cell) == Some(1)));
+        }
+        assert_eq!(dijkstra_shortest_path(&maze, PrintPathMode::None).len(), 9);
+    }
+
+    #[test]
+    fn test_weighted_search_matches_bfs_without_terrain() {
+        let maze = sidewinder(12, 8, 5);
+        let bfs = bfs_search(&maze);
+        for algorithm in [SearchAlgorithm::Dijkstra, SearchAlgorithm::AStar] {
+            let search = algorithm.search(&maze);
+            assert_eq!(search.cost, bfs.cost);
+            assert_eq!(search.cost as usize, search.path.len() - 1);
+        }
+        assert_eq!("dijkstra".parse(), Ok(SearchAlgorithm::Dijkstra));
+    }
+
+    #[test]
+    fn test_level_search_climbs_every_level() {
+        use shared::maze::{Braiding, GeneratorAlgorithm, Placement};
+
+        let mut maze = LevelMaze::generate(
+            GeneratorAlgorithm::Wilson,
+            8,
+            6,
+            3,
+            Braiding::Partial(0.5),
+            Placement::Random,
+            11,
+        );
+        let bfs = level_bfs_search(&maze);
+
+        // Each level is crossed from its entry to its exit, one more move takes the stairs
+        let moves: usize = maze.levels.iter().map(|level| bfs_search(level).path.len()).sum();
+        assert_eq!(bfs.path.len(), moves);
+        assert_eq!(bfs.cost as usize, bfs.path.len() - 1);
+        for pair in bfs.path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let distance = get_manhattan_distance(&from.cell, &to.cell) as usize;
+            assert_eq!(distance + from.level.abs_diff(to.level), 1);
+        }
+        assert_eq!(level_a_star_search(&maze).cost, bfs.cost);
+
+        maze.add_terrain(0.4, 11);
+        let a_star = level_a_star_search(&maze);
+        let cheapest: u32 = maze.levels.iter().map(|level| dijkstra_search(level).cost).sum();
+        // Stepping onto the entry of the next level costs 1
+        assert_eq!(a_star.cost, cheapest + 2);
+        assert!(a_star.cost <= level_bfs_search(&maze).cost);
+        assert_eq!(a_star.path.last(), Some(&maze.exit()));
+    }
+
+    #[test]
+    fn test_level_search_without_a_way_up() {
+        use shared::maze::io::read_text;
+
+        // The readers reject a maze without a way out, the passage is closed once loaded
+        let mut top = read_text("#####\n#3 2#\n#####\n").unwrap();
+        top.map[1][2] = PositionType::WALL;
+        let maze = LevelMaze::new(vec![read_text("#####\n#2 3#\n#####\n").unwrap(), top]);
+        for search in [level_bfs_search(&maze), level_a_star_search(&maze)] {
+            assert!(search.path.is_empty());
+            assert_eq!(search.cost, 0);
+            assert!(search.steps > 0);
+        }
+    }
 }
@@ -0,0 +1,304 @@
+use crate::agents::AgentAlgorithm;
+use crate::client::LocalAgent;
+use crate::tui::GameState;
+use shared::errors::GameResult;
+use shared::maze::{Cell, Directions, GeneratorAlgorithm, Maze, Placement};
+use shared::messages::{Action, ActionError, Direction, Message, RadarView};
+use shared::radar::{encode_radar, maze_radar};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Settings of a game played locally, without a server.
+#[derive(Debug, Clone)]
+pub struct OfflineConfig {
+    pub generator: GeneratorAlgorithm,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub agents: u8,
+    pub algorithm: AgentAlgorithm,
+    /// Relay the discoveries of each agent to the others, like the server does for team
+    /// messages.
+    pub team_chat: bool,
+    /// Moves after which an agent that has not found the exit gives up.
+    pub max_steps: u32,
+    /// Pause after each round of moves, to follow the game on the TUI.
+    pub step_delay: Duration,
+    /// Longest time spent pausing between rounds, the rounds left once it is reached are
+    /// played without pause.
+    pub max_delay: Duration,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            generator: GeneratorAlgorithm::Sidewinder,
+            width: 10,
+            height: 10,
+            seed: 0,
+            agents: 3,
+            algorithm: AgentAlgorithm::Tremeaux,
+            team_chat: false,
+            max_steps: 10_000,
+            step_delay: Duration::ZERO,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+/// How an agent did in an offline game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentSummary {
+    pub name: String,
+    /// Moves sent by the agent, the ones stopped by a wall included.
+    pub steps: u32,
+    pub found_exit: bool,
+}
+
+impl fmt::Display for AgentSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let outcome = if self.found_exit { "found the exit" } else { "gave up" };
+        write!(f, "{}: {} steps, {}", self.name, self.steps, outcome)
+    }
+}
+
+/// Where an agent really stands in the maze, which the agent itself only knows relative to
+/// where it started.
+struct Walker {
+    position: Cell,
+    facing: Cell,
+}
+
+impl Walker {
+    /// Turns toward `direction` and moves one cell that way, returns false without moving
+    /// nor turning when a wall is in the way, see [`Maze::step`].
+    fn step(&mut self, maze: &Maze, direction: &Direction) -> bool {
+        match maze.step(self.position, self.facing, direction) {
+            Some((position, facing)) => {
+                self.position = position;
+                self.facing = facing;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The radar view of where the walker stands, oriented by where it looks.
+    fn radar(&self, maze: &Maze) -> Message {
+        Message::RadarView(RadarView(encode_radar(&maze_radar(maze, self.position, self.facing))))
+    }
+
+    /// What the server answers to a move: the next radar view, or an error when a wall is
+    /// in the way.
+    fn answer(&mut self, maze: &Maze, direction: &Direction) -> Message {
+        if self.step(maze, direction) {
+            self.radar(maze)
+        } else {
+            Message::ActionError(ActionError::CannotPassThroughWall)
+        }
+    }
+}
+
+/// The maze of an offline game: the maze a server started with the same generator, size
+/// and seed plays, without terrain nor items.
+pub fn offline_maze(config: &OfflineConfig) -> Maze {
+    let mut maze = Maze::generate(config.generator, config.width, config.height, config.seed);
+    maze.place_entry_exit(Placement::Edges, config.seed);
+    maze
+}
+
+/// Plays a whole game on [`offline_maze`], standing in for the server.
+///
+/// Every agent starts on the entry facing north. Each round, the agents still playing in
+/// turn receive the radar view of where they stand and answer with their move, through the
+/// same handlers as during an online game. A move into a wall is answered with an
+/// [`ActionError::CannotPassThroughWall`] instead of the next radar view. An agent stops
+/// once it reaches the exit or after [`OfflineConfig::max_steps`] moves, the game once
+/// every agent stopped.
+pub fn run_offline(
+    config: &OfflineConfig,
+    tui_state: Option<Arc<Mutex<GameState>>>,
+) -> GameResult<Vec<AgentSummary>> {
+    let maze = offline_maze(config);
+
+    let mut agents: Vec<(LocalAgent, Walker, AgentSummary)> = (0..config.agents)
+        .map(|i| {
+            let name = format!("Player{}", i + 1);
+            let agent = LocalAgent::new(
+                name.clone(),
+                config.algorithm,
+                config.team_chat,
+                tui_state.clone(),
+            );
+            let walker = Walker { position: maze.entry, facing: Directions::NORTH };
+            (agent, walker, AgentSummary { name, steps: 0, found_exit: false })
+        })
+        .collect();
+    let mut next: Vec<Option<Message>> =
+        agents.iter().map(|(_, walker, _)| Some(walker.radar(&maze))).collect();
+    let mut delay = Duration::ZERO;
+
+    while next.iter().any(Option::is_some) {
+        for index in 0..agents.len() {
+            let Some(message) = next[index].take() else {
+                continue;
+            };
+            let (agent, walker, summary) = &mut agents[index];
+            let mut team_messages = Vec::new();
+            let mut reply = None;
+
+            for sent in agent.receive(message)? {
+                match sent {
+                    Message::Action(Action::MoveTo(direction)) => {
+                        summary.steps += 1;
+                        reply = Some(walker.answer(&maze, &direction));
+                    }
+                    Message::TeamMessage(team_message) => team_messages.push(team_message),
+                    _ => {}
+                }
+            }
+
+            summary.found_exit = walker.position == maze.exit;
+            if !summary.found_exit && summary.steps < config.max_steps {
+                next[index] = reply;
+            }
+
+            for team_message in team_messages {
+                for (other, (teammate, _, _)) in agents.iter_mut().enumerate() {
+                    if other != index {
+                        teammate.receive(Message::TeamMessage(team_message.clone()))?;
+                    }
+                }
+            }
+        }
+
+        if next.iter().any(Option::is_some) && delay < config.max_delay {
+            thread::sleep(config.step_delay);
+            delay += config.step_delay;
+        }
+    }
+
+    Ok(agents.into_iter().map(|(_, _, summary)| summary).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::logger::Logger;
+    use shared::maze::PositionType;
+    use shared::maze::io::read_text;
+    use std::time::Instant;
+
+    #[test]
+    fn test_walker_turns_and_stops_at_walls() {
+        let maze = read_text("#####\n#2  #\n### #\n#3  #\n#####\n").unwrap();
+        let mut walker = Walker { position: maze.entry, facing: Directions::NORTH };
+
+        assert!(!walker.step(&maze, &Direction::Front));
+        assert_eq!(walker.facing, Directions::NORTH);
+        assert!(walker.step(&maze, &Direction::Right));
+        assert_eq!(
+            (walker.position, walker.facing),
+            (Cell { row: 1, column: 3 }, Directions::EAST)
+        );
+        assert!(walker.step(&maze, &Direction::Right));
+        assert!(walker.step(&maze, &Direction::Right));
+        assert_eq!((walker.position, walker.facing), (maze.exit, Directions::WEST));
+        assert!(!walker.step(&maze, &Direction::Left));
+    }
+
+    #[test]
+    fn test_offline_game() {
+        Logger::init(false);
+        for algorithm in AgentAlgorithm::ALL {
+            let config = OfflineConfig {
+                width: 6,
+                height: 5,
+                seed: 3,
+                agents: 2,
+                algorithm,
+                max_steps: 500,
+                ..OfflineConfig::default()
+            };
+            let summaries = run_offline(&config, None).unwrap();
+
+            assert_eq!(summaries.len(), 2);
+            for summary in &summaries {
+                assert!(summary.found_exit, "{} with {}", summary, algorithm);
+                assert!(summary.steps > 0);
+            }
+            assert_eq!(
+                summaries[0].to_string(),
+                format!("Player1: {} steps, found the exit", summaries[0].steps)
+            );
+        }
+    }
+
+    #[test]
+    fn test_wall_bump_asks_the_strategy_again() {
+        Logger::init(false);
+        let text = "#######\n#2    #\n### ###\n#   # #\n### # #\n#    3#\n#######\n";
+        let maze = read_text(text).unwrap();
+        // The agent is shown a passage on its right that the maze does not have
+        let mut outdated = read_text(text).unwrap();
+        outdated.map[3][4] = PositionType::SPACE;
+        let mut walker = Walker { position: Cell { row: 3, column: 3 }, facing: Directions::NORTH };
+        let mut agent =
+            LocalAgent::new("Player1".into(), AgentAlgorithm::WallFollower, false, None);
+
+        let moves = agent.receive(walker.radar(&outdated)).unwrap();
+        let [Message::Action(Action::MoveTo(direction))] = moves.as_slice() else {
+            panic!("expected a single move, got {:?}", moves);
+        };
+        assert_eq!(*direction, Direction::Right);
+        let answer = walker.answer(&maze, direction);
+        assert!(matches!(answer, Message::ActionError(ActionError::CannotPassThroughWall)));
+
+        // Following the right wall, the passage ahead comes next
+        let moves = agent.receive(answer).unwrap();
+        let [Message::Action(Action::MoveTo(direction))] = moves.as_slice() else {
+            panic!("expected a single move, got {:?}", moves);
+        };
+        assert_eq!(*direction, Direction::Front);
+        assert!(matches!(walker.answer(&maze, direction), Message::RadarView(_)));
+        assert_eq!(walker.position, Cell { row: 1, column: 3 });
+    }
+
+    #[test]
+    fn test_offline_game_caps_the_delay() {
+        Logger::init(false);
+        let config = OfflineConfig {
+            agents: 1,
+            algorithm: AgentAlgorithm::WallFollower,
+            step_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(200),
+            ..OfflineConfig::default()
+        };
+        let started = Instant::now();
+        let summaries = run_offline(&config, None).unwrap();
+
+        assert!(summaries[0].found_exit);
+        assert!(summaries[0].steps >= 20, "{}", summaries[0]);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_offline_game_gives_up() {
+        Logger::init(false);
+        let config = OfflineConfig {
+            agents: 1,
+            max_steps: 3,
+            width: 20,
+            height: 20,
+            ..OfflineConfig::default()
+        };
+        let summaries = run_offline(&config, None).unwrap();
+
+        assert_eq!(
+            summaries,
+            vec![AgentSummary { name: "Player1".to_string(), steps: 3, found_exit: false }]
+        );
+    }
+}
@@ -272,24 +272,7 @@ impl Tui {
                 f.render_widget(maze_widget, content_chunks[0]);
 
                 let log_height = content_chunks[1].height.saturating_sub(2) as usize;
-                let log_lines: Vec<Line> = logs
-                    .iter()
-                    .rev()
-                    .take(log_height)
-                    .map(|(msg, level)| {
-                        let (color, prefix) = match level {
-                            LogLevel::Info => (Color::Green, "INFO "),
-                            LogLevel::Debug => (Color::Blue, "DEBUG"),
-                            LogLevel::Warning => (Color::Yellow, "WARN "),
-                            LogLevel::Error => (Color::Red, "ERROR"),
-                        };
-                        Line::from(vec![
-                            Span::styled(prefix, Style::default().fg(color)),
-                            Span::raw(" │ "),
-                            Span::styled(msg, Style::default().fg(Color::White)),
-                        ])
-                    })
-                    .collect();
+                let log_lines = Self::create_log_lines(&logs, log_height);
 
                 let logs_widget = Paragraph::new(log_lines)
                     .block(Block::default().borders(Borders::ALL).title("Logs"));
@@ -300,6 +283,35 @@ impl Tui {
         Ok(())
     }
 
+    /// Returns the most recent log lines, newest entry first.
+    ///
+    /// Multi-line messages such as decoded radar views keep their lines in order, with the
+    /// level prefix on the first one only.
+    fn create_log_lines(logs: &[(String, LogLevel)], height: usize) -> Vec<Line<'_>> {
+        logs.iter()
+            .rev()
+            .flat_map(|(msg, level)| {
+                let (color, prefix) = match level {
+                    LogLevel::Info => (Color::Green, "INFO "),
+                    LogLevel::Debug => (Color::Blue, "DEBUG"),
+                    LogLevel::Warning => (Color::Yellow, "WARN "),
+                    LogLevel::Error => (Color::Red, "ERROR"),
+                };
+                msg.lines().enumerate().map(move |(i, line)| {
+                    Line::from(vec![
+                        Span::styled(
+                            if i == 0 { prefix } else { "     " },
+                            Style::default().fg(color),
+                        ),
+                        Span::raw(" │ "),
+                        Span::styled(line, Style::default().fg(Color::White)),
+                    ])
+                })
+            })
+            .take(height)
+            .collect()
+    }
+
     fn create_stats(&self, agent: &AgentState) -> String {
         let graph = &agent.graph;
         let cells = &graph.cell_map;
@@ -399,6 +411,7 @@ impl Tui {
             CellType::ENEMY => " ⚠️ ".to_string(),
             CellType::HELP => " 🆘 ".to_string(),
             CellType::ALLY => " 🟢 ".to_string(),
+            CellType::STAIRS => " ⇅ ".to_string(),
             CellType::NOTHING => match cell.status {
                 CellStatus::VISITED => " · ".to_string(),
                 CellStatus::DeadEnd => " 🔸".to_string(),
@@ -580,6 +593,31 @@ mod tests {
         assert!(agent_state.graph.contains(&cell));
     }
 
+    #[test]
+    fn test_create_log_lines_splits_multiline_messages() {
+        let logs = vec![
+            ("Connected".to_string(), LogLevel::Info),
+            ("Received radar view:\n┌───┐\n│ @ │".to_string(), LogLevel::Debug),
+        ];
+
+        let lines = Tui::create_log_lines(&logs, 10);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "DEBUG │ Received radar view:",
+                "      │ ┌───┐",
+                "      │ │ @ │",
+                "INFO  │ Connected"
+            ]
+        );
+
+        assert_eq!(Tui::create_log_lines(&logs, 2).len(), 2);
+    }
+
     #[test]
     fn test_select_agent_increment() {
         let mut game_state = GameState::new();
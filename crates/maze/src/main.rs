@@ -0,0 +1,681 @@
+use clap::{Args, Parser, Subcommand};
+use client::maze_solver::{PrintPathMode, SearchAlgorithm, bfs_shortest_path};
+use shared::cli::ContentArgs;
+use shared::maze::analysis::analyze;
+use shared::maze::io::{MazeFormat, MazeMetadata};
+use shared::maze::svg::{SvgOptions, render_svg};
+use shared::maze::{Braiding, Cell, GeneratorAlgorithm, ItemKind, Maze, Placement, PositionType};
+use shared::maze_generator::{GenerationEvent, generation};
+use shared::utils::{ColorsAnsi, heat_green, print_error};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "Labyrinth-maze")]
+#[command(version = "1.0")]
+#[command(about = "Toolbox to generate and inspect mazes offline", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a maze and save it in one of the file formats.
+    Generate(SaveArgs),
+    /// Find the shortest path from the entry to the exit.
+    Solve(SolveArgs),
+    /// Print the metrics and difficulty score of a maze.
+    Analyze(SourceArgs),
+    /// Draw a maze in the terminal or as an SVG image.
+    Render(RenderArgs),
+    /// Convert a saved maze to another file format.
+    Convert(ConvertArgs),
+    /// Replay the generation of a maze step by step.
+    Animate(AnimateArgs),
+}
+
+#[derive(Args, Debug)]
+struct MazeArgs {
+    #[arg(
+        short,
+        long,
+        default_value = "Sidewinder",
+        help = "Maze generation algorithm: Sidewinder, Backtracker, Prim, Kruskal, Wilson or Eller."
+    )]
+    generator: GeneratorAlgorithm,
+
+    #[arg(long, default_value = "10", help = "Width of the maze in cells.")]
+    #[arg(value_parser = clap::value_parser!(u16).range(2..))]
+    width: u16,
+
+    #[arg(long, default_value = "10", help = "Height of the maze in cells.")]
+    #[arg(value_parser = clap::value_parser!(u16).range(2..))]
+    height: u16,
+
+    #[arg(short, long, help = "Seed for the maze generation, random if not set.")]
+    seed: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+struct GenerateArgs {
+    #[command(flatten)]
+    maze: MazeArgs,
+
+    #[arg(
+        long,
+        default_value = "perfect",
+        help = "Loops in the maze: perfect, full or the ratio of dead ends to remove."
+    )]
+    braid: Braiding,
+
+    #[arg(
+        long,
+        default_value = "edges",
+        help = "Entry and exit placement: edges, random, farthest or min-distance:<cells>."
+    )]
+    placement: Placement,
+
+    #[command(flatten)]
+    content: ContentArgs,
+}
+
+/// A maze generated from the command line options, or loaded with `--input`.
+#[derive(Args, Debug)]
+struct SourceArgs {
+    #[command(flatten)]
+    generate: GenerateArgs,
+
+    #[arg(short, long, help = "Load the maze from this file instead of generating one.")]
+    input: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Format of the input file: text, box or json, guessed from its content if not set."
+    )]
+    input_format: Option<MazeFormat>,
+}
+
+#[derive(Args, Debug)]
+struct SaveArgs {
+    #[command(flatten)]
+    generate: GenerateArgs,
+
+    #[arg(short, long, default_value = "text", help = "File format: text, box or json.")]
+    format: MazeFormat,
+
+    #[arg(short, long, help = "File to write to, standard output if not set.")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct SolveArgs {
+    #[command(flatten)]
+    source: SourceArgs,
+
+    #[arg(long, default_value = "bfs", help = "Solver: bfs, dijkstra or a-star.")]
+    solver: SearchAlgorithm,
+
+    #[arg(
+        short,
+        long,
+        default_value = "none",
+        help = "What to print: none, visited, visited-number or path."
+    )]
+    print: PrintPathMode,
+
+    #[arg(long, help = "File to write the path to, as a JSON list of positions.")]
+    path_output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RenderStyle {
+    Ansi,
+    Svg,
+}
+
+impl fmt::Display for RenderStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RenderStyle::Ansi => "ansi",
+            RenderStyle::Svg => "svg",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for RenderStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [RenderStyle::Ansi, RenderStyle::Svg]
+            .into_iter()
+            .find(|style| style.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown style '{}', expected ansi or svg", s))
+    }
+}
+
+#[derive(Args, Debug)]
+struct RenderArgs {
+    #[command(flatten)]
+    source: SourceArgs,
+
+    #[arg(long, default_value = "ansi", help = "Drawing style: ansi or svg.")]
+    style: RenderStyle,
+
+    #[arg(long, help = "Draw the shortest path found by this solver: bfs, dijkstra or a-star.")]
+    solver: Option<SearchAlgorithm>,
+
+    #[arg(long, requires = "solver", help = "Color the positions visited by the solver.")]
+    heatmap: bool,
+
+    #[arg(long, default_value = "10", help = "Side of a map position in pixels, for SVG.")]
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    scale: u32,
+
+    #[arg(short, long, help = "File to write to, standard output if not set.")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ConvertArgs {
+    #[arg(help = "Maze file to convert.")]
+    input: PathBuf,
+
+    #[arg(long, help = "Format of the input file: text, box or json, guessed if not set.")]
+    from: Option<MazeFormat>,
+
+    #[arg(long, help = "Format to convert to: text, box or json.")]
+    to: MazeFormat,
+
+    #[arg(short, long, help = "File to write to, standard output if not set.")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct AnimateArgs {
+    #[command(flatten)]
+    maze: MazeArgs,
+
+    #[arg(long, default_value = "50", help = "Milliseconds between two steps.")]
+    delay: u64,
+}
+
+/// A maze ready to work on, with a line telling where it comes from.
+struct Source {
+    maze: Maze,
+    metadata: MazeMetadata,
+    description: String,
+}
+
+impl GenerateArgs {
+    fn generate(&self, seed: u64) -> Source {
+        let MazeArgs { generator, width, height, .. } = self.maze;
+        let mut maze = Maze::generate(generator, width.into(), height.into(), seed);
+        maze.braid(self.braid, seed);
+        maze.place_entry_exit(self.placement, seed);
+        maze.add_terrain(self.content.terrain, seed);
+        maze.place_items(&self.content.items(), seed);
+
+        let terrain = if self.content.terrain > 0.0 {
+            format!(", terrain {}", self.content.terrain)
+        } else {
+            String::new()
+        };
+        let items = if maze.items.is_empty() {
+            String::new()
+        } else {
+            format!(", {} items", maze.items.len())
+        };
+        let description = format!(
+            "{}x{} maze, {}, braiding {}, placement {}{}{}, seed {}",
+            width, height, generator, self.braid, self.placement, terrain, items, seed
+        );
+        let metadata = MazeMetadata {
+            seed: Some(seed),
+            algorithm: Some(generator),
+            perfect: self.braid == Braiding::Perfect,
+        };
+        Source { maze, metadata, description }
+    }
+
+    fn seed(&self) -> u64 {
+        self.maze.seed.unwrap_or_else(rand::random)
+    }
+}
+
+impl SourceArgs {
+    fn load(&self) -> Result<Source, String> {
+        match &self.input {
+            Some(path) => read_maze(path, self.input_format),
+            None => Ok(self.generate.generate(self.generate.seed())),
+        }
+    }
+}
+
+fn read_maze(path: &Path, format: Option<MazeFormat>) -> Result<Source, String> {
+    let input =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let format = format.unwrap_or_else(|| MazeFormat::detect(&input));
+    let (maze, metadata) =
+        format.read(&input).map_err(|e| format!("Cannot load {}: {}", path.display(), e))?;
+    Ok(Source { maze, metadata, description: format!("{}, {} format", path.display(), format) })
+}
+
+fn write_output(output: Option<&Path>, content: &str) -> Result<(), String> {
+    match output {
+        Some(path) => {
+            fs::write(path, content).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+        }
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
+}
+
+fn analyze_report(source: &Source) -> String {
+    let path = bfs_shortest_path(&source.maze, PrintPathMode::None);
+    format!("{}\n\n{}", source.description, analyze(&source.maze, &path))
+}
+
+fn solve(args: &SolveArgs, source: &Source) -> Result<String, String> {
+    let search = args.solver.search(&source.maze);
+    search.print(&source.maze, args.print);
+
+    if let Some(path) = &args.path_output {
+        let json = serde_json::to_string(&search.path).map_err(|e| e.to_string())?;
+        write_output(Some(path), &(json + "\n"))?;
+    }
+    Ok(if search.path.is_empty() {
+        format!("{}\nThe exit cannot be reached, {} steps", source.description, search.steps)
+    } else {
+        format!(
+            "{}\nPath of {} positions and cost {} found by {} in {} steps",
+            source.description,
+            search.path.len(),
+            search.cost,
+            args.solver,
+            search.steps
+        )
+    })
+}
+
+fn render_document(args: &RenderArgs, maze: &Maze) -> String {
+    let search = args.solver.map(|solver| solver.search(maze));
+    let path = search.as_ref().map_or(&[][..], |search| &search.path[..]);
+    let heatmap = search.as_ref().filter(|_| args.heatmap).map(|search| &search.visited_points[..]);
+
+    match args.style {
+        RenderStyle::Ansi => render(maze, path, heatmap),
+        RenderStyle::Svg => {
+            let options = SvgOptions { scale: args.scale, path: Some(path), heatmap };
+            render_svg(maze, &options)
+        }
+    }
+}
+
+/// Draws walls as `#`, the entry as `E`, the exit as `X`, mud as `:` and water as `~`, two
+/// characters per position. Items are drawn as `H` for help, `M` for monsters and `O` for
+/// objectives. The path is drawn as red `*` and the heatmap as a background from yellow to
+/// red.
+fn render(maze: &Maze, path: &[Cell], heatmap: Option<&[Vec<i32>]>) -> String {
+    let path: HashSet<&Cell> = path.iter().collect();
+    let steps = heatmap.and_then(|heatmap| heatmap.iter().flatten().copied().max()).unwrap_or(0);
+    let mut frame = String::with_capacity(maze.row_len * (maze.col_len * 2 + 1));
+
+    for (row, positions) in maze.map.iter().enumerate() {
+        for (column, &position) in positions.iter().enumerate() {
+            let cell = Cell { row: row as i16, column: column as i16 };
+            let glyph = match (position, maze.item_at(cell)) {
+                (PositionType::WALL, _) => "##",
+                (PositionType::ENTRY, _) => "E ",
+                (PositionType::EXIT, _) => "X ",
+                _ if path.contains(&cell) => "**",
+                (_, Some(ItemKind::Help)) => "H ",
+                (_, Some(ItemKind::Monster)) => "M ",
+                (_, Some(ItemKind::Objective)) => "O ",
+                (PositionType::MUD, None) => "::",
+                (PositionType::WATER, None) => "~~",
+                _ => "  ",
+            };
+            let step = heatmap.map_or(-1, |heatmap| heatmap[row][column]);
+
+            if step >= 0 {
+                frame.push_str(&format!("\x1b[48;2;255;{};0m", heat_green(step, steps)));
+            }
+            if glyph == "**" {
+                frame.push_str(ColorsAnsi::RED);
+            }
+            frame.push_str(glyph);
+            if step >= 0 || glyph == "**" {
+                frame.push_str(ColorsAnsi::RESET);
+            }
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+fn animate(args: &AnimateArgs, seed: u64) {
+    let MazeArgs { generator, width, height, .. } = args.maze;
+    let mut maze: Maze = std::iter::empty().collect();
+
+    for event in generation(generator, width.into(), height.into(), seed) {
+        maze.extend([event]);
+        // Carving a cell and opening the wall next to it are shown as a single step
+        if !matches!(event, GenerationEvent::CellCarved(_)) {
+            print!("\x1b[2J\x1b[H{}", render(&maze, &[], None));
+            thread::sleep(Duration::from_millis(args.delay));
+        }
+    }
+    println!("{}, seed {}", generator, seed);
+}
+
+fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Generate(args) => {
+            let source = args.generate.generate(args.generate.seed());
+            let content = args.format.write(&source.maze, &source.metadata);
+            write_output(args.output.as_deref(), &content)
+        }
+        Command::Solve(args) => {
+            let source = args.source.load()?;
+            println!("{}", solve(&args, &source)?);
+            Ok(())
+        }
+        Command::Analyze(args) => {
+            println!("{}", analyze_report(&args.load()?));
+            Ok(())
+        }
+        Command::Render(args) => {
+            let source = args.source.load()?;
+            write_output(args.output.as_deref(), &render_document(&args, &source.maze))
+        }
+        Command::Convert(args) => {
+            let source = read_maze(&args.input, args.from)?;
+            write_output(args.output.as_deref(), &args.to.write(&source.maze, &source.metadata))
+        }
+        Command::Animate(args) => {
+            animate(&args, args.maze.seed.unwrap_or_else(rand::random));
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli.command) {
+        print_error(&e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("maze-cli-{}-{}", std::process::id(), name))
+    }
+
+    fn load(args: &[&str]) -> Source {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Command::Analyze(args) = cli.command else { panic!("expected analyze") };
+        args.load().unwrap()
+    }
+
+    #[test]
+    fn test_analyze_defaults() {
+        let cli = Cli::try_parse_from(["maze", "analyze"]).unwrap();
+        let Command::Analyze(args) = cli.command else { panic!("expected analyze") };
+        assert_eq!(args.generate.maze.generator, GeneratorAlgorithm::Sidewinder);
+        assert_eq!((args.generate.maze.width, args.generate.maze.height), (10, 10));
+        assert_eq!(args.generate.maze.seed, None);
+        assert_eq!(args.generate.braid, Braiding::Perfect);
+        assert_eq!(args.generate.placement, Placement::Edges);
+        assert_eq!(args.generate.content.terrain, 0.0);
+        assert!(args.generate.content.items().is_empty());
+        assert_eq!(args.input, None);
+    }
+
+    #[test]
+    fn test_analyze_report() {
+        let args = ["maze", "analyze", "-g", "wilson", "--width", "8", "--height", "6", "-s", "3"];
+
+        let report = analyze_report(&load(&args));
+        assert!(
+            report.starts_with("8x6 maze, Wilson, braiding perfect, placement edges, seed 3\n")
+        );
+        assert!(report.contains("Shortest path"));
+        assert!(report.ends_with("/ 100"));
+        assert_eq!(report, analyze_report(&load(&args)));
+    }
+
+    #[test]
+    fn test_generate_then_load() {
+        let generated = load(&["maze", "analyze", "-g", "kruskal", "-s", "5"]);
+
+        for format in MazeFormat::ALL {
+            let path = temp_file(&format!("generate.{}", format));
+            let output = path.display().to_string();
+            let cli = Cli::try_parse_from([
+                "maze",
+                "generate",
+                "-g",
+                "kruskal",
+                "-s",
+                "5",
+                "-f",
+                &format.to_string(),
+                "-o",
+                &output,
+            ])
+            .unwrap();
+            run(cli.command).unwrap();
+
+            let loaded = load(&["maze", "analyze", "-i", &output]);
+            assert_eq!(loaded.maze.map, generated.maze.map);
+            assert_eq!(loaded.maze.entry, generated.maze.entry);
+            assert_eq!(loaded.maze.exit, generated.maze.exit);
+            assert!(loaded.description.ends_with(&format!("{} format", format)));
+            if format == MazeFormat::Json {
+                assert_eq!(loaded.metadata, generated.metadata);
+            }
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let (input, output) = (temp_file("convert.txt"), temp_file("convert.json"));
+        fs::write(&input, "#####\n#2  #\n# # #\n# #3#\n#####\n").unwrap();
+
+        let (input_arg, output_arg) = (input.display().to_string(), output.display().to_string());
+        let cli =
+            Cli::try_parse_from(["maze", "convert", &input_arg, "--to", "json", "-o", &output_arg])
+                .unwrap();
+        run(cli.command).unwrap();
+
+        assert_eq!(MazeFormat::detect(&fs::read_to_string(&output).unwrap()), MazeFormat::Json);
+        let converted = read_maze(&output, Some(MazeFormat::Json)).unwrap();
+        assert_eq!(converted.maze.exit, Cell { row: 3, column: 3 });
+
+        assert!(
+            matches!(read_maze(&input, Some(MazeFormat::Box)), Err(e) if e.starts_with("Cannot load"))
+        );
+        fs::remove_file(input).unwrap();
+        fs::remove_file(output).unwrap();
+        assert!(
+            matches!(read_maze(&temp_file("missing.txt"), None), Err(e) if e.starts_with("Cannot read"))
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let path_output = temp_file("path.json");
+        let path_arg = path_output.display().to_string();
+        let cli = Cli::try_parse_from([
+            "maze",
+            "solve",
+            "-s",
+            "2",
+            "--solver",
+            "a-star",
+            "--path-output",
+            &path_arg,
+        ])
+        .unwrap();
+        let Command::Solve(args) = cli.command else { panic!("expected solve") };
+        assert_eq!(args.print, PrintPathMode::None);
+
+        let source = args.source.load().unwrap();
+        let report = solve(&args, &source).unwrap();
+        assert!(report.contains("found by a-star"));
+
+        let path: Vec<Cell> =
+            serde_json::from_str(&fs::read_to_string(&path_output).unwrap()).unwrap();
+        assert_eq!(path.first(), Some(&source.maze.entry));
+        assert_eq!(path.last(), Some(&source.maze.exit));
+        fs::remove_file(path_output).unwrap();
+    }
+
+    #[test]
+    fn test_render_styles() {
+        let cli = Cli::try_parse_from([
+            "maze",
+            "render",
+            "--width",
+            "5",
+            "--height",
+            "4",
+            "-s",
+            "7",
+            "--solver",
+            "bfs",
+            "--heatmap",
+        ])
+        .unwrap();
+        let Command::Render(mut args) = cli.command else { panic!("expected render") };
+        let maze = args.source.load().unwrap().maze;
+
+        let ansi = render_document(&args, &maze);
+        assert!(ansi.contains(&format!("{}**{}", ColorsAnsi::RED, ColorsAnsi::RESET)));
+        assert!(ansi.contains("\x1b[48;2;255;255;0mE "));
+
+        args.style = RenderStyle::Svg;
+        args.scale = 6;
+        let svg = render_document(&args, &maze);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="66" height="54""#)
+        );
+        assert!(svg.contains("heatmap") && svg.contains("polyline"));
+
+        args.solver = None;
+        args.heatmap = false;
+        let svg = render_document(&args, &maze);
+        assert!(!svg.contains("heatmap") && !svg.contains("polyline"));
+    }
+
+    #[test]
+    fn test_terrain() {
+        let cli = Cli::try_parse_from([
+            "maze",
+            "solve",
+            "-s",
+            "4",
+            "--terrain",
+            "0.4",
+            "--solver",
+            "dijkstra",
+        ])
+        .unwrap();
+        let Command::Solve(args) = cli.command else { panic!("expected solve") };
+        let source = args.source.load().unwrap();
+        assert!(source.description.contains("terrain 0.4"));
+
+        let report = solve(&args, &source).unwrap();
+        let cost = source.maze.path_cost(&SearchAlgorithm::Dijkstra.search(&source.maze).path);
+        assert!(report.contains(&format!("cost {} found by dijkstra", cost)));
+
+        let ansi = render(&source.maze, &[], None);
+        assert!(ansi.contains("::") && ansi.contains("~~"));
+
+        assert!(Cli::try_parse_from(["maze", "analyze", "--terrain", "1.5"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "analyze", "--terrain", "mud"]).is_err());
+    }
+
+    #[test]
+    fn test_items() {
+        let args = [
+            "maze",
+            "analyze",
+            "--width",
+            "16",
+            "--height",
+            "12",
+            "-s",
+            "6",
+            "--help-items",
+            "0.05",
+            "--monsters",
+            "0.05",
+            "--objectives",
+            "1",
+            "--monster-distance",
+            "4",
+        ];
+        let source = load(&args);
+        assert_eq!(source.maze.items.len(), 10 + 10 + 1);
+        assert!(source.description.contains(", 21 items, seed 6"));
+        assert_eq!(load(&args).maze.items, source.maze.items);
+
+        let ansi = render(&source.maze, &[], None);
+        assert_eq!(ansi.matches("M ").count(), 10);
+        assert_eq!(ansi.matches("O ").count(), 1);
+
+        assert!(Cli::try_parse_from(["maze", "analyze", "--monsters", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_animate_arguments() {
+        let cli = Cli::try_parse_from(["maze", "animate", "-g", "eller", "--delay", "0"]).unwrap();
+        let Command::Animate(args) = cli.command else { panic!("expected animate") };
+        assert_eq!(args.maze.generator, GeneratorAlgorithm::Eller);
+        assert_eq!(args.delay, 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let maze: Maze = generation(GeneratorAlgorithm::Sidewinder, 3, 2, 0).collect();
+        let frame = render(&maze, &[], None);
+
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|line| line.len() == 14));
+        assert_eq!(lines[0], "##############");
+        // Sidewinder always opens the whole first row
+        assert!(lines[1].starts_with("##") && lines[1][2..12].chars().all(|c| "EX ".contains(c)));
+        assert_eq!(frame.matches('E').count(), 1);
+        assert_eq!(frame.matches('X').count(), 1);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(Cli::try_parse_from(["maze"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "analyze", "--width", "1"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "analyze", "-g", "unknown"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "generate", "-f", "png"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "solve", "--print", "all"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "render", "--heatmap"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "render", "--style", "png"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "render", "--scale", "0"]).is_err());
+        assert!(Cli::try_parse_from(["maze", "convert", "maze.txt"]).is_err());
+    }
+}
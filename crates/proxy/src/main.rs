@@ -0,0 +1,93 @@
+use clap::Parser;
+use proxy::proxy::{DebugProxy, ProxyConfig};
+use shared::logger::Logger;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "Labyrinth-proxy")]
+#[command(version = "1.0")]
+#[command(about = "Debug proxy logging the traffic between a client and a server", long_about = None)]
+struct Args {
+    #[arg(short, long, default_value = "8779", help = "Port the client connects to.")]
+    #[arg(value_parser = clap::value_parser!(u16).range(1024..=65535))]
+    port: u16,
+
+    #[arg(long = "host-address", default_value = "localhost", help = "Address to listen on.")]
+    host: String,
+
+    #[arg(long, default_value = "localhost:8778", help = "Server address to forward to.")]
+    server: String,
+
+    #[arg(long, help = "Save every message as JSON lines to this file.")]
+    session: Option<PathBuf>,
+
+    #[arg(long, default_value = "0", help = "Delay in milliseconds before forwarding a message.")]
+    #[arg(help_heading = "FAULT INJECTION")]
+    delay: u64,
+
+    #[arg(long, default_value = "0", help = "Probability between 0 and 1 to drop a message.")]
+    #[arg(help_heading = "FAULT INJECTION")]
+    #[arg(value_parser = parse_probability)]
+    drop_rate: f64,
+
+    #[arg(short, long, help = "Seed for the dropped messages.")]
+    #[arg(help_heading = "FAULT INJECTION")]
+    seed: Option<u64>,
+
+    #[arg(long, help = "Enable debug logs.", default_value = "false")]
+    debug: bool,
+}
+
+fn parse_probability(value: &str) -> Result<f64, String> {
+    let probability: f64 = value.parse().map_err(|_| format!("`{}` is not a number", value))?;
+    if (0.0..=1.0).contains(&probability) {
+        Ok(probability)
+    } else {
+        Err(format!("{} is not between 0 and 1", probability))
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    Logger::init(args.debug);
+    let logger = Logger::get_instance();
+
+    let config = ProxyConfig {
+        listen_addr: format!("{}:{}", args.host, args.port),
+        server_addr: args.server,
+        session_file: args.session,
+        delay: Duration::from_millis(args.delay),
+        drop_rate: args.drop_rate,
+        seed: args.seed.unwrap_or_else(rand::random),
+    };
+
+    let result = DebugProxy::new(config).and_then(|proxy| proxy.run(logger));
+    if let Err(e) = result {
+        logger.error(&format!("{}", e));
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_values() {
+        let args = Args::try_parse_from(["test"]).unwrap();
+        assert_eq!(args.port, 8779);
+        assert_eq!(args.server, "localhost:8778");
+        assert_eq!(args.delay, 0);
+        assert!(args.session.is_none());
+    }
+
+    #[test]
+    fn test_drop_rate_range() {
+        let args = Args::try_parse_from(["test", "--drop-rate", "0.25"]).unwrap();
+        assert!((args.drop_rate - 0.25).abs() < f64::EPSILON);
+
+        assert!(Args::try_parse_from(["test", "--drop-rate", "1.5"]).is_err());
+        assert!(Args::try_parse_from(["test", "--drop-rate", "often"]).is_err());
+    }
+}
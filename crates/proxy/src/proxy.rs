@@ -0,0 +1,289 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde_json::json;
+use shared::errors::{GameError, GameResult};
+use shared::logger::Logger;
+use shared::messages::{Message, decode_frame, read_frame, write_frame};
+use shared::radar::decode_radar;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    pub listen_addr: String,
+    pub server_addr: String,
+    pub session_file: Option<PathBuf>,
+    pub delay: Duration,
+    pub drop_rate: f64,
+    pub seed: u64,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: "localhost:8779".to_string(),
+            server_addr: "localhost:8778".to_string(),
+            session_file: None,
+            delay: Duration::ZERO,
+            drop_rate: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    ClientToServer,
+    ServerToClient,
+}
+
+impl fmt::Display for Flow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Flow::ClientToServer => write!(f, "client -> server"),
+            Flow::ServerToClient => write!(f, "server -> client"),
+        }
+    }
+}
+
+type Session = Arc<Mutex<Option<BufWriter<File>>>>;
+
+pub struct DebugProxy {
+    config: ProxyConfig,
+    session: Session,
+    started: Instant,
+}
+
+impl DebugProxy {
+    pub fn new(config: ProxyConfig) -> GameResult<Self> {
+        let session = match &config.session_file {
+            Some(path) => Some(BufWriter::new(File::create(path)?)),
+            None => None,
+        };
+
+        Ok(Self { config, session: Arc::new(Mutex::new(session)), started: Instant::now() })
+    }
+
+    pub fn run(&self, logger: &Logger) -> GameResult<()> {
+        let listener =
+            TcpListener::bind(&self.config.listen_addr).map_err(GameError::ConnectionError)?;
+        logger.info(&format!(
+            "Proxy listening on {}, forwarding to {}",
+            self.config.listen_addr, self.config.server_addr
+        ));
+
+        for (connection_id, stream) in (1u64..).zip(listener.incoming()) {
+            let client = stream.map_err(GameError::ConnectionError)?;
+            let server = match TcpStream::connect(&self.config.server_addr) {
+                Ok(server) => server,
+                Err(e) => {
+                    logger.error(&format!("Cannot reach {}: {}", self.config.server_addr, e));
+                    continue;
+                }
+            };
+            logger.info(&format!("Connection #{} opened", connection_id));
+
+            for (flow, from, to) in [
+                (Flow::ClientToServer, client.try_clone()?, server.try_clone()?),
+                (Flow::ServerToClient, server, client.try_clone()?),
+            ] {
+                let pump = self.pump(connection_id, flow);
+                std::thread::spawn(move || {
+                    if let Err(e) = pump.run(from, to) {
+                        Logger::get_instance()
+                            .error(&format!("Connection #{} ({}): {}", connection_id, flow, e));
+                    }
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn pump(&self, connection_id: u64, flow: Flow) -> Pump {
+        let stream_seed = self.config.seed ^ (connection_id << 1) ^ (flow as u64);
+        Pump {
+            connection_id,
+            flow,
+            delay: self.config.delay,
+            drop_rate: self.config.drop_rate,
+            rng: StdRng::seed_from_u64(stream_seed),
+            session: Arc::clone(&self.session),
+            started: self.started,
+        }
+    }
+}
+
+/// Forwards the frames of one direction of a proxied connection.
+struct Pump {
+    connection_id: u64,
+    flow: Flow,
+    delay: Duration,
+    drop_rate: f64,
+    rng: StdRng,
+    session: Session,
+    started: Instant,
+}
+
+impl Pump {
+    fn run(mut self, mut from: TcpStream, mut to: TcpStream) -> GameResult<()> {
+        while let Ok(frame) = read_frame(&mut from) {
+            let dropped = self.drop_rate > 0.0 && self.rng.random_bool(self.drop_rate.min(1.0));
+
+            println!(
+                "[{:>9.3}s] #{} {}{}\n{}",
+                self.started.elapsed().as_secs_f64(),
+                self.connection_id,
+                self.flow,
+                if dropped { " (dropped)" } else { "" },
+                describe_frame(&frame)
+            );
+            self.record(&frame, dropped)?;
+
+            if dropped {
+                continue;
+            }
+            if !self.delay.is_zero() {
+                std::thread::sleep(self.delay);
+            }
+            write_frame(&mut to, &frame)?;
+        }
+
+        to.shutdown(Shutdown::Write).map_err(GameError::ConnectionError)
+    }
+
+    fn record(&self, frame: &[u8], dropped: bool) -> GameResult<()> {
+        let mut session = self.session.lock().map_err(|e| GameError::ThreadError(e.to_string()))?;
+
+        if let Some(writer) = session.as_mut() {
+            let timestamp =
+                SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+            let message = serde_json::from_slice::<serde_json::Value>(frame)
+                .unwrap_or_else(|_| json!(String::from_utf8_lossy(frame)));
+
+            let entry = json!({
+                "timestamp_ms": timestamp,
+                "connection": self.connection_id,
+                "direction": self.flow.to_string(),
+                "dropped": dropped,
+                "message": message,
+            });
+            writeln!(writer, "{}", entry)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a frame for display, expanding radar views into their decoded content.
+pub fn describe_frame(frame: &[u8]) -> String {
+    match decode_frame(frame) {
+        Ok(Message::RadarView(view)) => match decode_radar(&view.0) {
+            Ok(radar) => format!("RadarView({:?})\n{}", view.0, radar),
+            Err(e) => format!("RadarView({:?}) <{}>", view.0, e),
+        },
+        Ok(message) => format!("{:?}", message),
+        Err(e) => format!("<{}> {}", e, String::from_utf8_lossy(frame)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::messages::{Hint, RadarView, receive_message, send_message};
+
+    fn socket_pair() -> (TcpStream, TcpStream) {
+        Logger::init(true);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let remote = TcpStream::connect(addr).unwrap();
+        let (local, _) = listener.accept().unwrap();
+        (local, remote)
+    }
+
+    fn test_pump(config: ProxyConfig) -> Pump {
+        DebugProxy::new(config).unwrap().pump(1, Flow::ClientToServer)
+    }
+
+    #[test]
+    fn test_describe_radar_frame() {
+        let frame =
+            serde_json::to_vec(&Message::RadarView(RadarView("bKgGjsIyap8p8aa".to_string())))
+                .unwrap();
+
+        let description = describe_frame(&frame);
+        assert!(description.starts_with("RadarView(\"bKgGjsIyap8p8aa\")"));
+        assert!(description.ends_with("  · ╵   ╶───╴"));
+    }
+
+    #[test]
+    fn test_describe_malformed_radar_frame() {
+        let frame = serde_json::to_vec(&Message::RadarView(RadarView("bKgG".to_string()))).unwrap();
+        assert_eq!(
+            describe_frame(&frame),
+            "RadarView(\"bKgG\") <Wrong radar length: expected 11, found 3>"
+        );
+    }
+
+    #[test]
+    fn test_describe_invalid_frame() {
+        let description = describe_frame(b"not json");
+        assert!(description.contains("Invalid JSON"));
+        assert!(description.ends_with("not json"));
+    }
+
+    #[test]
+    fn test_pump_forwards_frames() {
+        let (from, mut sender) = socket_pair();
+        let (to, mut receiver) = socket_pair();
+
+        let pump = test_pump(ProxyConfig::default());
+        let handle = std::thread::spawn(move || pump.run(from, to));
+
+        send_message(&mut sender, &Message::Hint(Hint::Secret(42))).unwrap();
+        let forwarded = receive_message(&mut receiver).unwrap();
+        assert!(matches!(forwarded, Message::Hint(Hint::Secret(42))));
+
+        drop(sender);
+        assert!(handle.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_pump_drops_frames() {
+        let (from, mut sender) = socket_pair();
+        let (to, mut receiver) = socket_pair();
+
+        let pump = test_pump(ProxyConfig { drop_rate: 1.0, ..ProxyConfig::default() });
+        let handle = std::thread::spawn(move || pump.run(from, to));
+
+        send_message(&mut sender, &Message::Hint(Hint::Secret(42))).unwrap();
+        drop(sender);
+        handle.join().unwrap().unwrap();
+
+        assert!(receive_message(&mut receiver).is_err());
+    }
+
+    #[test]
+    fn test_session_file() {
+        let path = std::env::temp_dir().join(format!("proxy-session-{}.jsonl", std::process::id()));
+        let pump =
+            test_pump(ProxyConfig { session_file: Some(path.clone()), ..ProxyConfig::default() });
+
+        pump.record(br#"{"Hint":{"Secret":1}}"#, false).unwrap();
+        pump.record(b"garbage", true).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> =
+            content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["message"]["Hint"]["Secret"], 1);
+        assert_eq!(lines[1]["message"], "garbage");
+        assert_eq!(lines[1]["dropped"], true);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
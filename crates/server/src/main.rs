@@ -1,6 +1,10 @@
 use clap::Parser;
 use server::server::{GameServer, ServerConfig};
+use shared::cli::ContentArgs;
+use shared::heartbeat::{DEFAULT_INTERVAL_SECS, DEFAULT_TIMEOUT_SECS, HeartbeatConfig};
 use shared::logger::Logger;
+use shared::maze::{Braiding, GeneratorAlgorithm, Placement};
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(name = "Labyrinth-server")]
@@ -21,14 +25,100 @@ struct Args {
     #[arg(short, long, help = "Seed for the maze generation.")]
     seed: Option<u64>,
 
+    #[arg(
+        short,
+        long,
+        default_value = "Sidewinder",
+        help = "Maze generation algorithm: Sidewinder, Backtracker, Prim, Kruskal, Wilson or Eller."
+    )]
+    generator: GeneratorAlgorithm,
+
+    #[arg(long, default_value = "10", help = "Width of the maze in cells.")]
+    #[arg(value_parser = clap::value_parser!(u16).range(2..))]
+    maze_width: u16,
+
+    #[arg(long, default_value = "10", help = "Height of the maze in cells.")]
+    #[arg(value_parser = clap::value_parser!(u16).range(2..))]
+    maze_height: u16,
+
+    #[arg(long, default_value = "1", help = "Number of levels of the maze, linked by stairs.")]
+    #[arg(value_parser = clap::value_parser!(u16).range(1..))]
+    levels: u16,
+
+    #[arg(
+        long,
+        default_value = "perfect",
+        help = "Loops in the maze: perfect, full or the ratio of dead ends to remove."
+    )]
+    braid: Braiding,
+
+    #[arg(
+        long,
+        default_value = "edges",
+        help = "Entry and exit placement: edges, random, farthest or min-distance:<cells>."
+    )]
+    placement: Placement,
+
+    #[command(flatten)]
+    content: ContentArgs,
+
+    #[arg(
+        long,
+        default_value = "0",
+        help = "Milliseconds a move waits per unit of terrain cost above a plain passage."
+    )]
+    terrain_delay: u64,
+
+    #[arg(
+        long,
+        default_value = "0",
+        help = "Walls opened, and passages closed, at each shift of the maze. 0 keeps it fixed."
+    )]
+    dynamic_walls: usize,
+
+    #[arg(long, default_value = "10", help = "Seconds between two shifts of the maze walls.")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    wall_shift_interval: u64,
+
     #[arg(long, help = "Enable debug logs.", default_value = "false")]
     debug: bool,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_INTERVAL_SECS,
+        help = "Seconds of silence before pinging a player, 0 disables heartbeats."
+    )]
+    heartbeat_interval: u64,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_TIMEOUT_SECS,
+        help = "Seconds without heartbeat before dropping a player."
+    )]
+    heartbeat_timeout: u64,
 }
 
 fn main() {
     let args = Args::parse();
     let seed = args.seed.unwrap_or_else(rand::random);
-    let config = ServerConfig { host: args.host, port: args.port, seed, max_players_per_team: 3 };
+    let config = ServerConfig {
+        host: args.host,
+        port: args.port,
+        seed,
+        max_players_per_team: 3,
+        heartbeat: HeartbeatConfig::from_secs(args.heartbeat_interval, args.heartbeat_timeout),
+        generator: args.generator,
+        maze_width: args.maze_width.into(),
+        maze_height: args.maze_height.into(),
+        levels: args.levels.into(),
+        braiding: args.braid,
+        placement: args.placement,
+        terrain: args.content.terrain,
+        terrain_delay: Duration::from_millis(args.terrain_delay),
+        items: args.content.items(),
+        dynamic_walls: args.dynamic_walls,
+        wall_shift_interval: Duration::from_secs(args.wall_shift_interval),
+    };
     Logger::init(args.debug);
     let logger = Logger::get_instance();
     logger.debug(&format!("Server seed: {:?}", seed));
@@ -42,12 +132,103 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::maze::ItemPlacement;
 
     #[test]
     fn test_default_values() {
         let args = Args::try_parse_from(["test"]).unwrap();
         assert_eq!(args.port, 8778);
         assert_eq!(args.host, "localhost");
+        assert_eq!(args.heartbeat_interval, DEFAULT_INTERVAL_SECS);
+        assert_eq!(args.heartbeat_timeout, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(args.generator, GeneratorAlgorithm::Sidewinder);
+        assert_eq!((args.maze_width, args.maze_height), (10, 10));
+        assert_eq!(args.levels, 1);
+        assert_eq!(args.braid, Braiding::Perfect);
+        assert_eq!(args.placement, Placement::Edges);
+        assert_eq!((args.content.terrain, args.terrain_delay), (0.0, 0));
+        assert_eq!(args.content.items(), ItemPlacement::default());
+        assert_eq!((args.dynamic_walls, args.wall_shift_interval), (0, 10));
+    }
+
+    #[test]
+    fn test_custom_generator() {
+        let args = Args::try_parse_from([
+            "test",
+            "--generator",
+            "kruskal",
+            "--maze-width",
+            "30",
+            "--maze-height",
+            "20",
+        ])
+        .unwrap();
+        assert_eq!(args.generator, GeneratorAlgorithm::Kruskal);
+        assert_eq!((args.maze_width, args.maze_height), (30, 20));
+
+        assert!(Args::try_parse_from(["test", "--generator", "dfs"]).is_err());
+        assert!(Args::try_parse_from(["test", "--maze-height", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_braid() {
+        let args = Args::try_parse_from(["test", "--braid", "0.3"]).unwrap();
+        assert_eq!(args.braid, Braiding::Partial(0.3));
+        let args = Args::try_parse_from(["test", "--braid", "full"]).unwrap();
+        assert_eq!(args.braid, Braiding::Full);
+        assert!(Args::try_parse_from(["test", "--braid", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_placement() {
+        let args = Args::try_parse_from(["test", "--placement", "min-distance:15"]).unwrap();
+        assert_eq!(args.placement, Placement::MinDistance(15));
+        assert!(Args::try_parse_from(["test", "--placement", "corner"]).is_err());
+    }
+
+    #[test]
+    fn test_terrain() {
+        let args =
+            Args::try_parse_from(["test", "--terrain", "0.25", "--terrain-delay", "150"]).unwrap();
+        assert_eq!((args.content.terrain, args.terrain_delay), (0.25, 150));
+        assert!(Args::try_parse_from(["test", "--terrain", "-0.1"]).is_err());
+        assert!(Args::try_parse_from(["test", "--terrain", "lots"]).is_err());
+    }
+
+    #[test]
+    fn test_items() {
+        let args = Args::try_parse_from([
+            "test",
+            "--help-items",
+            "0.1",
+            "--monsters",
+            "0.05",
+            "--objectives",
+            "2",
+            "--monster-distance",
+            "8",
+        ])
+        .unwrap();
+        let items = args.content.items();
+        assert_eq!((items.help, items.monsters, items.objectives), (0.1, 0.05, 2));
+        assert_eq!(items.monster_distance, 8);
+        assert!(Args::try_parse_from(["test", "--help-items", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_dynamic_walls() {
+        let args =
+            Args::try_parse_from(["test", "--dynamic-walls", "3", "--wall-shift-interval", "2"])
+                .unwrap();
+        assert_eq!((args.dynamic_walls, args.wall_shift_interval), (3, 2));
+        assert!(Args::try_parse_from(["test", "--wall-shift-interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_levels() {
+        let args = Args::try_parse_from(["test", "--levels", "3"]).unwrap();
+        assert_eq!(args.levels, 3);
+        assert!(Args::try_parse_from(["test", "--levels", "0"]).is_err());
     }
 
     #[test]
@@ -1,14 +1,20 @@
 use rand::{Rng, rng};
 use shared::errors::{GameError, GameResult};
+use shared::heartbeat::{HeartbeatConfig, HeartbeatMonitor};
 use shared::logger::Logger;
+use shared::maze::levels::{LevelCell, LevelMaze};
+use shared::maze::{Braiding, Cell, Directions, GeneratorAlgorithm, ItemPlacement, Placement};
 use shared::messages::{
-    Action, Challenge, Client, Hint, Message, MessageError, RadarView, RegisterTeamResult,
-    RegistrationError, SubscribePlayerResult, Teams, receive_message, send_message,
+    Action, ActionError, Challenge, Client, Direction, Hint, Message, MessageError, RadarView,
+    RegisterTeamResult, RegistrationError, SubscribePlayerResult, TeamMessage, Teams, send_message,
 };
+use shared::radar::{encode_radar, level_radar};
 use std::collections::HashMap;
+use std::io::{self, Write};
 use std::net::{TcpListener, TcpStream};
-use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::mpsc::{RecvTimeoutError, Sender, channel};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -16,11 +22,46 @@ pub struct ServerConfig {
     pub port: u16,
     pub seed: u64,
     pub max_players_per_team: u8,
+    pub heartbeat: HeartbeatConfig,
+    pub generator: GeneratorAlgorithm,
+    /// Size of the maze in cells.
+    pub maze_width: usize,
+    pub maze_height: usize,
+    /// Number of levels linked by stairs, see [`LevelMaze`].
+    pub levels: usize,
+    pub braiding: Braiding,
+    pub placement: Placement,
+    /// Ratio of the passages covered by mud and water.
+    pub terrain: f64,
+    /// Extra wait per unit of cost above a plain passage, see [`GameServer::move_delay`].
+    pub terrain_delay: Duration,
+    pub items: ItemPlacement,
+    /// Walls opened, and passages closed, every `wall_shift_interval`. 0 keeps the maze as
+    /// generated.
+    pub dynamic_walls: usize,
+    pub wall_shift_interval: Duration,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
-        Self { host: "localhost".to_string(), port: 8080, seed: 0, max_players_per_team: 3 }
+        Self {
+            host: "localhost".to_string(),
+            port: 8080,
+            seed: 0,
+            max_players_per_team: 3,
+            heartbeat: HeartbeatConfig::default(),
+            generator: GeneratorAlgorithm::Sidewinder,
+            maze_width: 10,
+            maze_height: 10,
+            levels: 1,
+            braiding: Braiding::Perfect,
+            placement: Placement::Edges,
+            terrain: 0.0,
+            terrain_delay: Duration::ZERO,
+            items: ItemPlacement::default(),
+            dynamic_walls: 0,
+            wall_shift_interval: Duration::from_secs(10),
+        }
     }
 }
 
@@ -29,15 +70,84 @@ pub struct GameServer {
     teams: Arc<Mutex<HashMap<String, Teams>>>,
     config: ServerConfig,
     game_state: Arc<Mutex<GameState>>,
+    maze: Arc<RwLock<LevelMaze>>,
 }
 
 type ServerResult<T> = Result<T, RegistrationError>;
 
+/// Where a player stands, level included, and where they look. Their radar view is turned
+/// that way and their moves are relative to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerPosition {
+    pub position: LevelCell,
+    pub facing: Cell,
+}
+
+/// The writing half of a connection. Every frame is queued to a single writer thread, so
+/// the frames of a player's own thread and of their teammates never interleave and nobody
+/// waits on a slow peer.
+#[derive(Clone)]
+struct Outbox(Sender<Vec<u8>>);
+
+impl Outbox {
+    fn new(mut stream: TcpStream) -> Self {
+        let (frames, queue) = channel::<Vec<u8>>();
+        std::thread::spawn(move || {
+            for frame in queue {
+                if stream.write_all(&frame).and_then(|_| stream.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self(frames)
+    }
+}
+
+impl Write for Outbox {
+    /// Queues `buf` whole. [`send_message`] writes each frame in a single call, so a frame
+    /// is never split between two writes.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "connection closed"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Shifts the walls of the maze every `wall_shift_interval` on its own thread, until it
+/// is stopped or dropped.
+struct WallShifter {
+    stop: Sender<()>,
+    handle: Option<std::thread::JoinHandle<u64>>,
+}
+
+impl WallShifter {
+    /// Stops the shifts and waits for the thread. Returns the number of shifts made, 0 once
+    /// already stopped.
+    fn stop(&mut self) -> u64 {
+        // A thread that already ended dropped the receiver, nothing to stop then
+        let _ = self.stop.send(());
+        self.handle.take().and_then(|handle| handle.join().ok()).unwrap_or(0)
+    }
+}
+
+impl Drop for WallShifter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
 struct GameState {
     is_started: bool,
     teams: HashMap<String, Teams>,
     clients: HashMap<String, Client>,
-    connections: HashMap<String, TcpStream>,
+    connections: HashMap<String, Outbox>,
+    /// Where each player stands and looks.
+    positions: HashMap<String, PlayerPosition>,
 }
 
 impl GameState {
@@ -47,6 +157,7 @@ impl GameState {
             teams: HashMap::new(),
             clients: HashMap::new(),
             connections: HashMap::new(),
+            positions: HashMap::new(),
         }
     }
 
@@ -58,59 +169,203 @@ impl GameState {
         self.teams.values().all(|team| team.players.len() == max_players_per_team as usize)
     }
 
-    fn notify_all_players(&mut self, message: &Message) -> GameResult<()> {
-        for (_, stream) in self.connections.iter_mut() {
-            send_message(stream, message)?
+    /// Queues `message` for a connected player. A player whose connection is gone is only
+    /// logged, their own thread drops them. Returns whether the message was queued.
+    fn notify_player(&mut self, player_name: &str, message: &Message) -> bool {
+        let Some(outbox) = self.connections.get_mut(player_name) else {
+            return false;
+        };
+        match send_message(outbox, message) {
+            Ok(()) => true,
+            Err(e) => {
+                Logger::get_instance()
+                    .warn(&format!("Failed to send message to {}: {}", player_name, e));
+                false
+            }
         }
-        Ok(())
     }
 
-    fn start_game(&mut self) -> GameResult<()> {
+    fn notify_all_players(&mut self, message: &Message) {
+        let player_names: Vec<String> = self.connections.keys().cloned().collect();
+        for player_name in player_names {
+            self.notify_player(&player_name, message);
+        }
+    }
+
+    /// Sends a team message to every connected teammate of its sender.
+    ///
+    /// Returns the number of players the message was delivered to.
+    fn relay_to_teammates(&mut self, message: TeamMessage) -> usize {
+        let team_name = match self.clients.get(&message.sender) {
+            Some(client) => client.team_name.clone(),
+            None => return 0,
+        };
+
+        let teammates: Vec<String> = match self.teams.get(&team_name) {
+            Some(team) => team
+                .players
+                .iter()
+                .map(|player| player.player_name.clone())
+                .filter(|name| *name != message.sender)
+                .collect(),
+            None => return 0,
+        };
+
+        let relayed = Message::TeamMessage(message);
+        teammates.iter().filter(|teammate| self.notify_player(teammate, &relayed)).count()
+    }
+
+    fn start_game(&mut self, radar_view: &RadarView) {
         if !self.is_started {
             self.is_started = true;
 
-            self.notify_all_players(&Message::Hint(Hint::Secret(17)))?;
+            self.notify_all_players(&Message::Hint(Hint::Secret(17)));
 
             let player_names: Vec<String> = self.connections.keys().cloned().collect();
             if !player_names.is_empty() {
                 let random_index = rand::rng().random_range(0..player_names.len());
                 let selected_player = player_names[random_index].clone();
 
-                if let Some(stream) = self.connections.get_mut(&selected_player) {
-                    send_message(stream, &Message::Challenge(Challenge::SecretSumModulo(23)))?;
-                }
+                self.notify_player(
+                    &selected_player,
+                    &Message::Challenge(Challenge::SecretSumModulo(23)),
+                );
             }
 
-            for (_, stream) in self.connections.iter_mut() {
-                send_message(stream, &Message::Challenge(Challenge::SecretSumModulo(23)))?;
-                send_message(
-                    stream,
-                    &Message::RadarView(RadarView("bieakcGa//+F8pa".to_string())),
-                )?;
+            for player_name in player_names {
+                self.notify_player(
+                    &player_name,
+                    &Message::Challenge(Challenge::SecretSumModulo(23)),
+                );
+                self.notify_player(&player_name, &Message::RadarView(radar_view.clone()));
             }
         }
-        Ok(())
     }
 }
 
 impl GameServer {
     pub fn new(config: ServerConfig) -> Self {
+        let mut maze = LevelMaze::generate(
+            config.generator,
+            config.maze_width,
+            config.maze_height,
+            config.levels,
+            config.braiding,
+            config.placement,
+            config.seed,
+        );
+        maze.add_terrain(config.terrain, config.seed);
+        maze.place_items(&config.items, config.seed);
+
         Self {
             clients: Arc::new(Mutex::new(HashMap::new())),
             teams: Arc::new(Mutex::new(HashMap::new())),
             config: config.clone(),
             game_state: Arc::new(Mutex::new(GameState::new())),
+            maze: Arc::new(RwLock::new(maze)),
         }
     }
 
+    /// The maze generated for this game from the configured generator, size and seed, along
+    /// with its items. Every level is generated from its own seed, see
+    /// [`LevelMaze::generate`].
+    pub fn maze(&self) -> RwLockReadGuard<'_, LevelMaze> {
+        // The maze is only written by `shift_walls`, which cannot leave it half updated
+        self.maze.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Opens and closes `dynamic_walls` walls on every level, keeping every cell of the maze
+    /// reachable. The changes only depend on the seed and on `tick`. Returns the map
+    /// positions that changed.
+    pub fn shift_walls(&self, tick: u64) -> Vec<LevelCell> {
+        let mut maze = self.maze.write().unwrap_or_else(PoisonError::into_inner);
+        maze.shift_walls(self.config.dynamic_walls, self.config.seed.wrapping_add(tick))
+    }
+
+    /// The radar view of the maze as it is now from `player`, turned the way they look.
+    /// Stairs are shown on the level the player is on.
+    pub fn radar_view(&self, player: PlayerPosition) -> RadarView {
+        RadarView(encode_radar(&level_radar(&self.maze(), player.position, player.facing)))
+    }
+
+    /// Where players start: on the entry of the first level, looking north.
+    pub fn start_position(&self) -> PlayerPosition {
+        PlayerPosition { position: self.maze().entry(), facing: Directions::NORTH }
+    }
+
+    /// Where a player stands, the start position until they moved or if they are not
+    /// registered.
+    pub fn player_position(&self, player_name: &str) -> PlayerPosition {
+        let position = self
+            .game_state
+            .lock()
+            .ok()
+            .and_then(|game_state| game_state.positions.get(player_name).copied());
+        position.unwrap_or_else(|| self.start_position())
+    }
+
+    /// Moves a player one cell toward `direction`, relative to where they look, taking the
+    /// stairs they step onto. Returns where they now stand, `None` if a wall is in the way.
+    pub fn move_player(&self, player_name: &str, direction: &Direction) -> Option<PlayerPosition> {
+        let mut game_state = self.game_state.lock().ok()?;
+        let from =
+            game_state.positions.get(player_name).copied().unwrap_or_else(|| self.start_position());
+        let (position, facing) = self.maze().step(from.position, from.facing, direction)?;
+        let to = PlayerPosition { position, facing };
+        game_state.positions.insert(player_name.to_string(), to);
+        Some(to)
+    }
+
+    /// How long a move onto `position` is held back: mud and water cost more than a plain
+    /// passage, each extra unit of cost waits `terrain_delay`. Nothing for plain passages
+    /// and positions a player cannot step onto.
+    pub fn move_delay(&self, position: LevelCell) -> Duration {
+        let extra = self.maze().cost(position).unwrap_or(1).saturating_sub(1);
+        self.config.terrain_delay * extra
+    }
+
     pub fn run(&self, logger: &Logger) -> GameResult<()> {
         let address = format!("{}:{}", self.config.host, self.config.port);
         let listener = TcpListener::bind(&address).map_err(GameError::ConnectionError)?;
 
         logger.info(&format!("Server listening on {}", address));
+        logger.debug(&format!(
+            "Generated a {}x{} maze of {} levels with {} (seed {})",
+            self.config.maze_width,
+            self.config.maze_height,
+            self.maze().levels.len(),
+            self.config.generator,
+            self.config.seed
+        ));
+        let items: usize = self.maze().levels.iter().map(|level| level.items.len()).sum();
+        if items > 0 {
+            logger.debug(&format!("Placed {} items in the maze", items));
+        }
+        // Stopped when dropped, once the server stops accepting connections
+        let _wall_shifter =
+            (self.config.dynamic_walls > 0).then(|| self.shift_walls_periodically());
         self.handle_connections(listener)
     }
 
+    /// Starts shifting the walls every `wall_shift_interval`, see [`GameServer::shift_walls`].
+    fn shift_walls_periodically(&self) -> WallShifter {
+        let server = self.clone();
+        let (stop, stopped) = channel();
+        let handle = std::thread::spawn(move || {
+            let logger = Logger::get_instance();
+            let mut tick = 0;
+            while let Err(RecvTimeoutError::Timeout) =
+                stopped.recv_timeout(server.config.wall_shift_interval)
+            {
+                tick += 1;
+                let changed = server.shift_walls(tick);
+                logger.debug(&format!("Shifted {} walls of the maze", changed.len()));
+            }
+            tick
+        });
+        WallShifter { stop, handle: Some(handle) }
+    }
+
     fn handle_connections(&self, listener: TcpListener) -> GameResult<()> {
         let mut thread_handles: Vec<std::thread::JoinHandle<GameResult<()>>> = Vec::new();
 
@@ -140,7 +395,7 @@ impl GameServer {
     fn handle_player_registration(
         &self,
         player: Client,
-        stream: &mut TcpStream,
+        outbox: &Outbox,
         logger: &Logger,
     ) -> GameResult<SubscribePlayerResult> {
         let result = self.register_client(player.clone(), logger);
@@ -149,22 +404,21 @@ impl GameServer {
             let mut game_state =
                 self.game_state.lock().map_err(|e| GameError::ThreadError(e.to_string()))?;
 
-            let stream_clone =
-                stream.try_clone().map_err(|e| GameError::ThreadError(e.to_string()))?;
-
-            game_state.connections.insert(player.player_name.clone(), stream_clone);
+            game_state.connections.insert(player.player_name.clone(), outbox.clone());
+            let start = self.start_position();
+            game_state.positions.insert(player.player_name.clone(), start);
 
             if !game_state.is_started
                 && game_state.are_all_teams_full(self.config.max_players_per_team)
             {
-                game_state.start_game()?;
+                game_state.start_game(&self.radar_view(start));
             }
         }
 
         Ok(result)
     }
 
-    fn register_client(&self, player: Client, logger: &Logger) -> SubscribePlayerResult {
+    fn register_client(&self, mut player: Client, logger: &Logger) -> SubscribePlayerResult {
         if player.player_name.is_empty() {
             return SubscribePlayerResult::Err(RegistrationError::InvalidName);
         }
@@ -203,6 +457,7 @@ impl GameServer {
                 return SubscribePlayerResult::Err(RegistrationError::TooManyPlayers);
             }
 
+            player.team_name.clone_from(&team_name);
             team.players.push(player.clone());
             clients.insert(player.player_name.clone(), player.clone());
 
@@ -233,6 +488,30 @@ impl GameServer {
         }
     }
 
+    /// Removes a player from every registry so its slot can be taken again.
+    fn drop_player(&self, player_name: &str, logger: &Logger) {
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.remove(player_name);
+        }
+
+        if let Ok(mut teams) = self.teams.lock() {
+            for team in teams.values_mut() {
+                team.players.retain(|player| player.player_name != player_name);
+            }
+        }
+
+        if let Ok(mut game_state) = self.game_state.lock() {
+            game_state.clients.remove(player_name);
+            game_state.connections.remove(player_name);
+            game_state.positions.remove(player_name);
+            for team in game_state.teams.values_mut() {
+                team.players.retain(|player| player.player_name != player_name);
+            }
+        }
+
+        logger.warn(&format!("{} left the game and was dropped", player_name));
+    }
+
     fn find_team_by_token(&self, teams: &HashMap<String, Teams>, token: &str) -> Option<String> {
         teams
             .iter()
@@ -276,13 +555,17 @@ impl GameServer {
     fn handle_message(&self, mut stream: TcpStream) -> GameResult<()> {
         let logger = Logger::get_instance();
         let peer_address = stream.peer_addr().map_err(GameError::ConnectionError)?;
+        let mut outbox = Outbox::new(stream.try_clone().map_err(GameError::ConnectionError)?);
+        let mut heartbeat = HeartbeatMonitor::new(self.config.heartbeat.clone());
+        let mut player_name: Option<String> = None;
 
         loop {
-            let message_result = receive_message(&mut stream);
-            logger.debug(&format!("Received message from {}", peer_address));
+            let message_result = heartbeat.receive_replying(&mut stream, &mut outbox);
 
             match message_result {
-                Ok(message) => {
+                Ok(Some(message)) => {
+                    logger.debug(&format!("Received message from {}", peer_address));
+
                     let response = match message {
                         Message::RegisterTeam(team) => {
                             let team = Teams {
@@ -315,8 +598,14 @@ impl GameServer {
                                 registration_token: player.registration_token,
                             };
 
-                            match self.handle_player_registration(player, &mut stream, logger) {
-                                Ok(result) => Message::SubscribePlayerResult(result),
+                            let name = player.player_name.clone();
+                            match self.handle_player_registration(player, &outbox, logger) {
+                                Ok(result) => {
+                                    if matches!(result, SubscribePlayerResult::Ok) {
+                                        player_name = Some(name);
+                                    }
+                                    Message::SubscribePlayerResult(result)
+                                }
                                 Err(_) => Message::SubscribePlayerResult(
                                     SubscribePlayerResult::Err(RegistrationError::ServerError),
                                 ),
@@ -337,13 +626,54 @@ impl GameServer {
                             } else {
                                 logger.warn(&format!("Incorrect answer from {}", peer_address));
                             }
-                            Message::RadarView(RadarView("bieakcGa//+F8pa".to_string()))
-                        }
-                        Message::Action(Action::MoveTo(_)) => {
-                            logger.info(&format!("Received MoveTo from {}", peer_address));
-
-                            break;
+                            let position = match &player_name {
+                                Some(name) => self.player_position(name),
+                                None => self.start_position(),
+                            };
+                            Message::RadarView(self.radar_view(position))
                         }
+                        Message::TeamMessage(team_message) => match &player_name {
+                            Some(name) => {
+                                let team_message =
+                                    TeamMessage { sender: name.clone(), ..team_message };
+                                let relayed = self
+                                    .game_state
+                                    .lock()
+                                    .map_err(|e| GameError::ThreadError(e.to_string()))
+                                    .map(|mut state| state.relay_to_teammates(team_message));
+
+                                match relayed {
+                                    Ok(count) => {
+                                        logger.debug(&format!(
+                                            "Relayed team message from {} to {} teammates",
+                                            name, count
+                                        ));
+                                        continue;
+                                    }
+                                    Err(e) => Message::MessageError(MessageError {
+                                        message: format!("Failed to relay team message: {}", e),
+                                    }),
+                                }
+                            }
+                            None => Message::MessageError(MessageError {
+                                message: "Subscribe before messaging your team".to_string(),
+                            }),
+                        },
+                        Message::Action(Action::MoveTo(direction)) => match &player_name {
+                            Some(name) => match self.move_player(name, &direction) {
+                                Some(player) => {
+                                    std::thread::sleep(self.move_delay(player.position));
+                                    if player.position == self.maze().exit() {
+                                        logger.info(&format!("{} reached the exit", name));
+                                    }
+                                    Message::RadarView(self.radar_view(player))
+                                }
+                                None => Message::ActionError(ActionError::CannotPassThroughWall),
+                            },
+                            None => Message::MessageError(MessageError {
+                                message: "Subscribe before moving".to_string(),
+                            }),
+                        },
                         _ => {
                             logger.warn(&format!(
                                 "Received invalid message type from {}",
@@ -355,17 +685,26 @@ impl GameServer {
                         }
                     };
 
-                    if let Err(e) = send_message(&mut stream, &response) {
+                    if let Err(e) = send_message(&mut outbox, &response) {
                         logger.error(&format!("Failed to send message to {}: {}", peer_address, e));
                         break;
                     }
                 }
+                Ok(None) => {
+                    logger.warn(&format!("No heartbeat from {}", peer_address));
+                    break;
+                }
                 Err(_) => {
                     break;
                 }
             }
         }
 
+        // Whatever ended the connection, the player will not come back on it
+        if let Some(name) = &player_name {
+            self.drop_player(name, logger);
+        }
+
         logger.info(&format!("Connection closed for {}", peer_address));
         Ok(())
     }
@@ -378,6 +717,7 @@ impl Clone for GameServer {
             teams: Arc::clone(&self.teams),
             config: self.config.clone(),
             game_state: Arc::clone(&self.game_state),
+            maze: Arc::clone(&self.maze),
         }
     }
 }
@@ -385,8 +725,17 @@ impl Clone for GameServer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::maze::analysis::distances_from;
+    use shared::maze::{Maze, PositionType};
+    use shared::messages::{RegisterTeam, SubscribePlayer, receive_message};
+    use shared::radar::{CellType, decode_radar, maze_radar};
     use std::net::SocketAddr;
     use std::str::FromStr;
+    use std::thread;
+    use std::time::Instant;
+
+    const DIRECTIONS: [Direction; 4] =
+        [Direction::Front, Direction::Right, Direction::Back, Direction::Left];
 
     fn setup_test_environment() -> (GameServer, &'static Logger) {
         Logger::init(true);
@@ -405,6 +754,29 @@ mod tests {
         }
     }
 
+    /// Runs `server.handle_message` on a new connection, registers a team of one player
+    /// through it and returns the player's end, once subscribed.
+    fn subscribe_over_tcp(server: &GameServer) -> (TcpStream, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (accepted, _) = listener.accept().unwrap();
+        let handler = server.clone();
+        let handle = thread::spawn(move || handler.handle_message(accepted).unwrap());
+
+        send_message(&mut stream, &Message::RegisterTeam(RegisterTeam { name: "Blue".into() }))
+            .unwrap();
+        let Message::RegisterTeamResult(RegisterTeamResult::Ok { registration_token, .. }) =
+            receive_message(&mut stream).unwrap()
+        else {
+            panic!("team registration failed");
+        };
+        let subscribe = SubscribePlayer { name: "Player1".into(), registration_token };
+        send_message(&mut stream, &Message::SubscribePlayer(subscribe)).unwrap();
+        // The game starts with the only team full, before the subscription is answered
+        while !matches!(receive_message(&mut stream).unwrap(), Message::SubscribePlayerResult(_)) {}
+        (stream, handle)
+    }
+
     fn create_test_team(name: &str) -> Teams {
         Teams {
             team_name: name.to_string(),
@@ -529,4 +901,381 @@ mod tests {
 
         assert_eq!(success_count, 3);
     }
+
+    #[test]
+    fn test_register_client_records_team() {
+        let (server, logger) = setup_test_environment();
+        let team = create_test_team("Blue");
+        let token = server.register_team(team, logger).unwrap();
+        let client = create_test_client("Player1", &token);
+
+        server.register_client(client, logger);
+
+        let clients = server.clients.lock().unwrap();
+        assert_eq!(clients["Player1"].team_name, "Blue");
+    }
+
+    #[test]
+    fn test_relay_only_reaches_teammates() {
+        use shared::maze::Cell;
+        use shared::messages::{TeamInfo, receive_message};
+        use std::net::TcpListener;
+
+        let (server, logger) = setup_test_environment();
+        let blue_token = server.register_team(create_test_team("Blue"), logger).unwrap();
+        let red_token = server.register_team(create_test_team("Red"), logger).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut receivers = HashMap::new();
+
+        for (name, token) in [("Blue1", &blue_token), ("Blue2", &blue_token), ("Red1", &red_token)]
+        {
+            server.register_client(create_test_client(name, token), logger);
+            let remote = TcpStream::connect(addr).unwrap();
+            let (local, _) = listener.accept().unwrap();
+            server
+                .game_state
+                .lock()
+                .unwrap()
+                .connections
+                .insert(name.to_string(), Outbox::new(local));
+            receivers.insert(name, remote);
+        }
+
+        let message = TeamMessage {
+            sender: "Blue1".to_string(),
+            info: TeamInfo::Exit(Cell { row: 1, column: 2 }),
+        };
+        let delivered = server.game_state.lock().unwrap().relay_to_teammates(message);
+        assert_eq!(delivered, 1);
+
+        let received = receive_message(receivers.get_mut("Blue2").unwrap()).unwrap();
+        assert!(
+            matches!(received, Message::TeamMessage(TeamMessage { ref sender, .. }) if sender == "Blue1")
+        );
+
+        let red = receivers.get_mut("Red1").unwrap();
+        red.set_nonblocking(true).unwrap();
+        assert!(receive_message(red).is_err());
+    }
+
+    #[test]
+    fn test_relay_skips_closed_connections() {
+        use shared::maze::Cell;
+        use shared::messages::{TeamInfo, receive_message};
+        use std::net::TcpListener;
+
+        let (server, logger) = setup_test_environment();
+        let token = server.register_team(create_test_team("Blue"), logger).unwrap();
+        for name in ["Blue1", "Blue2", "Blue3"] {
+            server.register_client(create_test_client(name, &token), logger);
+        }
+
+        // Blue2's writer is gone, Blue3 must still get the message
+        let closed = Outbox(channel().0);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut remote = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (local, _) = listener.accept().unwrap();
+        {
+            let mut game_state = server.game_state.lock().unwrap();
+            game_state.connections.insert("Blue2".to_string(), closed);
+            game_state.connections.insert("Blue3".to_string(), Outbox::new(local));
+        }
+
+        let message = TeamMessage {
+            sender: "Blue1".to_string(),
+            info: TeamInfo::Exit(Cell { row: 1, column: 2 }),
+        };
+        let delivered = server.game_state.lock().unwrap().relay_to_teammates(message);
+        assert_eq!(delivered, 1);
+        assert!(matches!(receive_message(&mut remote).unwrap(), Message::TeamMessage(_)));
+    }
+
+    #[test]
+    fn test_drop_player_frees_slot() {
+        let (server, logger) = setup_test_environment();
+        let team = create_test_team("Test Team");
+        let token = server.register_team(team, logger).unwrap();
+
+        for i in 1..=3 {
+            let client = create_test_client(&format!("Player{}", i), &token);
+            server.register_client(client, logger);
+        }
+
+        let client = create_test_client("Player4", &token);
+        let result = server.register_client(client, logger);
+        assert!(matches!(result, SubscribePlayerResult::Err(RegistrationError::TooManyPlayers)));
+
+        server.drop_player("Player2", logger);
+        assert!(!server.clients.lock().unwrap().contains_key("Player2"));
+
+        let client = create_test_client("Player4", &token);
+        let result = server.register_client(client, logger);
+        assert!(matches!(result, SubscribePlayerResult::Ok));
+    }
+
+    #[test]
+    fn test_server_generates_configured_maze() {
+        let config = ServerConfig {
+            generator: GeneratorAlgorithm::Wilson,
+            maze_width: 7,
+            maze_height: 4,
+            seed: 12,
+            ..ServerConfig::default()
+        };
+        let server = GameServer::new(config);
+
+        assert_eq!(server.maze().levels[0].row_len, 9);
+        assert_eq!(server.maze().levels[0].col_len, 15);
+        let expected = Maze::generate(GeneratorAlgorithm::Wilson, 7, 4, 12);
+        assert_eq!(server.maze().levels[0].map, expected.map);
+        assert_eq!(server.clone().maze().levels[0].map, expected.map);
+    }
+
+    #[test]
+    fn test_server_tracks_levels() {
+        let (_, logger) = setup_test_environment();
+        let config = ServerConfig { levels: 3, seed: 4, ..ServerConfig::default() };
+        let server = GameServer::new(config);
+        assert_eq!(server.maze().levels.len(), 3);
+        assert_eq!(server.maze().exit().level, 2);
+        assert_eq!(server.player_position("Player1"), server.start_position());
+
+        // Walk toward the exit of the first level, where the stairs lead up
+        let distances = distances_from(&server.maze().levels[0], server.maze().levels[0].exit);
+        let distance = |cell: Cell| distances[cell.row as usize][cell.column as usize];
+        let mut player = server.start_position();
+        while player.position.level == 0 {
+            let (direction, _) = DIRECTIONS
+                .iter()
+                .filter_map(|direction| {
+                    let (to, _) = server.maze().step(player.position, player.facing, direction)?;
+                    Some((direction, to))
+                })
+                .find(|(_, to)| to.level > 0 || distance(to.cell) < distance(player.position.cell))
+                .expect("a way toward the exit");
+            player = server.move_player("Player1", direction).expect("an open passage");
+            assert_eq!(server.player_position("Player1"), player);
+        }
+
+        // The player arrived on the second level, standing on the stairs
+        assert_eq!(player.position, LevelCell { level: 1, cell: server.maze().levels[1].entry });
+        let radar = decode_radar(&server.radar_view(player).0).unwrap();
+        assert_eq!(radar.cells[4], CellType::STAIRS);
+
+        server.drop_player("Player1", logger);
+        assert_eq!(server.player_position("Player1"), server.start_position());
+    }
+
+    #[test]
+    fn test_move_to_moves_the_player() {
+        Logger::init(true);
+        // Every position but the entry and the exit is mud or water, each move is held back
+        let config = ServerConfig {
+            max_players_per_team: 1,
+            terrain: 1.0,
+            terrain_delay: Duration::from_millis(50),
+            heartbeat: HeartbeatConfig::from_secs(0, 0),
+            ..ServerConfig::default()
+        };
+        let server = GameServer::new(config);
+        let (mut stream, handle) = subscribe_over_tcp(&server);
+        let start = server.start_position();
+
+        // The entry lies in the first column, the outer wall is on its left
+        send_message(&mut stream, &Message::Action(Action::MoveTo(Direction::Left))).unwrap();
+        let reply = receive_message(&mut stream).unwrap();
+        assert!(matches!(reply, Message::ActionError(ActionError::CannotPassThroughWall)));
+        assert_eq!(server.player_position("Player1"), start);
+
+        let (direction, position, facing) = DIRECTIONS
+            .into_iter()
+            .find_map(|direction| {
+                let (position, facing) =
+                    server.maze().step(start.position, start.facing, &direction)?;
+                Some((direction, position, facing))
+            })
+            .expect("an open passage from the entry");
+        let delay = server.move_delay(position);
+        assert!(delay >= Duration::from_millis(100));
+        let sent = Instant::now();
+        send_message(&mut stream, &Message::Action(Action::MoveTo(direction))).unwrap();
+        let Message::RadarView(view) = receive_message(&mut stream).unwrap() else {
+            panic!("expected the radar view after the move");
+        };
+        assert!(sent.elapsed() >= delay);
+        let moved = PlayerPosition { position, facing };
+        assert_eq!(server.player_position("Player1"), moved);
+        assert_eq!(view.0, server.radar_view(moved).0);
+
+        drop(stream);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_closed_connection_drops_the_player() {
+        Logger::init(true);
+        let config = ServerConfig {
+            max_players_per_team: 1,
+            heartbeat: HeartbeatConfig::from_secs(0, 0),
+            ..ServerConfig::default()
+        };
+        let server = GameServer::new(config);
+        let (stream, handle) = subscribe_over_tcp(&server);
+        assert!(server.clients.lock().unwrap().contains_key("Player1"));
+        assert!(server.game_state.lock().unwrap().connections.contains_key("Player1"));
+
+        drop(stream);
+        handle.join().unwrap();
+
+        assert!(!server.clients.lock().unwrap().contains_key("Player1"));
+        let game_state = server.game_state.lock().unwrap();
+        assert!(!game_state.connections.contains_key("Player1"));
+        assert!(game_state.teams.values().all(|team| team.players.is_empty()));
+    }
+
+    #[test]
+    fn test_server_braids_maze() {
+        let config = ServerConfig { braiding: Braiding::Full, ..ServerConfig::default() };
+        let server = GameServer::new(config.clone());
+
+        let mut expected =
+            Maze::generate(config.generator, config.maze_width, config.maze_height, 0);
+        assert_ne!(server.maze().levels[0].map, expected.map);
+        expected.braid(Braiding::Full, 0);
+        assert_eq!(server.maze().levels[0].map, expected.map);
+    }
+
+    #[test]
+    fn test_server_places_entry_exit() {
+        let config = ServerConfig { placement: Placement::Farthest, ..ServerConfig::default() };
+        let server = GameServer::new(config);
+
+        let mut expected = Maze::generate(GeneratorAlgorithm::Sidewinder, 10, 10, 0);
+        expected.place_entry_exit(Placement::Farthest, 0);
+        assert_eq!(server.maze().levels[0].entry, expected.entry);
+        assert_eq!(server.maze().levels[0].exit, expected.exit);
+    }
+
+    #[test]
+    fn test_server_places_items() {
+        let items =
+            ItemPlacement { help: 0.05, monsters: 0.05, objectives: 1, ..Default::default() };
+        let config = ServerConfig { items, ..ServerConfig::default() };
+        let server = GameServer::new(config);
+
+        let mut expected = Maze::generate(GeneratorAlgorithm::Sidewinder, 10, 10, 0);
+        expected.place_entry_exit(Placement::Edges, 0);
+        expected.place_items(&items, 0);
+        assert_eq!(server.maze().levels[0].items.len(), 5 + 5 + 1);
+        assert_eq!(server.maze().levels[0].items, expected.items);
+
+        assert!(GameServer::new(ServerConfig::default()).maze().levels[0].items.is_empty());
+    }
+
+    #[test]
+    fn test_server_shifts_walls() {
+        let config = ServerConfig { dynamic_walls: 2, seed: 3, ..ServerConfig::default() };
+        let server = GameServer::new(config.clone());
+        let before = server.maze().levels[0].map.clone();
+        let view = server.radar_view(server.start_position());
+
+        let changed = server.shift_walls(1);
+        assert_eq!(changed.len(), 4);
+        for position in &changed {
+            assert_eq!(position.level, 0);
+            let (row, column) = (position.cell.row as usize, position.cell.column as usize);
+            assert_ne!(server.maze().levels[0].map[row][column], before[row][column]);
+        }
+        let distances = distances_from(&server.maze().levels[0], server.maze().levels[0].entry);
+        let exit = server.maze().levels[0].exit;
+        assert!(distances[exit.row as usize][exit.column as usize] > 0);
+
+        // Clones share the maze and the same tick shifts the same walls
+        let other = GameServer::new(config);
+        other.shift_walls(1);
+        assert_eq!(other.maze().levels[0].map, server.clone().maze().levels[0].map);
+
+        // The radar is taken from the current maze
+        let radar = decode_radar(&server.radar_view(server.start_position()).0).unwrap();
+        let entry_radar =
+            maze_radar(&server.maze().levels[0], server.maze().levels[0].entry, Directions::NORTH);
+        assert_eq!(radar.horizontal, entry_radar.horizontal);
+        assert_eq!(radar.vertical, entry_radar.vertical);
+        assert!(decode_radar(&view.0).is_ok());
+
+        let server = GameServer::new(ServerConfig::default());
+        assert!(server.shift_walls(1).is_empty());
+    }
+
+    #[test]
+    fn test_wall_shifter_stops() {
+        Logger::init(true);
+        // Stopping does not wait for the next shift
+        let config = ServerConfig {
+            dynamic_walls: 2,
+            wall_shift_interval: Duration::from_secs(3600),
+            ..ServerConfig::default()
+        };
+        let server = GameServer::new(config);
+        let before = server.maze().levels[0].map.clone();
+        assert_eq!(server.shift_walls_periodically().stop(), 0);
+        assert_eq!(server.maze().levels[0].map, before);
+
+        // Once stopped the maze holds exactly the shifts that were made
+        let config = ServerConfig {
+            dynamic_walls: 2,
+            wall_shift_interval: Duration::from_millis(1),
+            ..ServerConfig::default()
+        };
+        let server = GameServer::new(config.clone());
+        let mut shifter = server.shift_walls_periodically();
+        while server.maze().levels[0].map == before {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let ticks = shifter.stop();
+        assert!(ticks > 0);
+
+        let expected = GameServer::new(config);
+        for tick in 1..=ticks {
+            expected.shift_walls(tick);
+        }
+        assert_eq!(server.maze().levels[0].map, expected.maze().levels[0].map);
+    }
+
+    #[test]
+    fn test_server_move_delay() {
+        let config = ServerConfig {
+            terrain: 0.5,
+            terrain_delay: Duration::from_millis(100),
+            ..ServerConfig::default()
+        };
+        let server = GameServer::new(config);
+
+        let mut expected = Maze::generate(GeneratorAlgorithm::Sidewinder, 10, 10, 0);
+        expected.place_entry_exit(Placement::Edges, 0);
+        expected.add_terrain(0.5, 0);
+        assert_eq!(server.maze().levels[0].map, expected.map);
+
+        let find = |position: u16| {
+            let maze = &server.maze().levels[0];
+            (0..maze.row_len)
+                .flat_map(|row| (0..maze.col_len).map(move |column| (row, column)))
+                .find(|&(row, column)| maze.map[row][column] == position)
+                .map(|(row, column)| Cell { row: row as i16, column: column as i16 })
+                .map(|cell| LevelCell { level: 0, cell })
+                .expect("position in the maze")
+        };
+        assert_eq!(server.move_delay(find(PositionType::MUD)), Duration::from_millis(200));
+        assert_eq!(server.move_delay(find(PositionType::WATER)), Duration::from_millis(400));
+        assert_eq!(server.move_delay(server.maze().entry()), Duration::ZERO);
+        assert_eq!(server.move_delay(find(PositionType::WALL)), Duration::ZERO);
+
+        let server = GameServer::new(ServerConfig::default());
+        let maze = server.maze();
+        assert!(
+            maze.levels[0].map.iter().flatten().all(|&position| position <= PositionType::EXIT)
+        );
+    }
 }
@@ -0,0 +1,299 @@
+use crate::errors::Base64Error;
+
+/// Character sets a `Base64` codec can use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// RFC 4648 section 4: `A-Z a-z 0-9 + /`
+    Standard,
+    /// RFC 4648 section 5: `A-Z a-z 0-9 - _`
+    UrlSafe,
+    /// The game server alphabet, lowercase first: `a-z A-Z 0-9 + /`
+    Game,
+}
+
+const STANDARD_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const GAME_CHARS: &[u8; 64] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
+
+const INVALID: u8 = 0xFF;
+
+const fn reverse(chars: &[u8; 64]) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < 64 {
+        table[chars[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+const STANDARD_VALUES: [u8; 256] = reverse(STANDARD_CHARS);
+const URL_SAFE_VALUES: [u8; 256] = reverse(URL_SAFE_CHARS);
+const GAME_VALUES: [u8; 256] = reverse(GAME_CHARS);
+
+impl Alphabet {
+    /// The 64 characters of the alphabet, indexed by their 6-bit value.
+    pub fn chars(self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard => STANDARD_CHARS,
+            Alphabet::UrlSafe => URL_SAFE_CHARS,
+            Alphabet::Game => GAME_CHARS,
+        }
+    }
+
+    /// Returns the 6-bit value of a character, or `None` if it is not part of the alphabet.
+    pub fn value(self, byte: u8) -> Option<u8> {
+        let values = match self {
+            Alphabet::Standard => &STANDARD_VALUES,
+            Alphabet::UrlSafe => &URL_SAFE_VALUES,
+            Alphabet::Game => &GAME_VALUES,
+        };
+        Some(values[byte as usize]).filter(|&value| value != INVALID)
+    }
+}
+
+/// A base64 codec for one alphabet, with or without `=` padding.
+///
+/// # Examples
+/// ```
+/// use shared::base64::Base64;
+/// assert_eq!(Base64::STANDARD.encode(b"fo"), "Zm8=");
+/// assert_eq!(Base64::GAME.encode(b"fo"), "zM8");
+/// assert_eq!(Base64::GAME.decode("zM8").unwrap(), b"fo");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Base64 {
+    pub alphabet: Alphabet,
+    /// Pad the output to a multiple of 4 characters when encoding, and require that
+    /// padding when decoding.
+    pub padding: bool,
+}
+
+impl Base64 {
+    pub const STANDARD: Base64 = Base64 { alphabet: Alphabet::Standard, padding: true };
+    pub const URL_SAFE: Base64 = Base64 { alphabet: Alphabet::UrlSafe, padding: false };
+    pub const GAME: Base64 = Base64 { alphabet: Alphabet::Game, padding: false };
+
+    pub fn new(alphabet: Alphabet, padding: bool) -> Self {
+        Self { alphabet, padding }
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let chars = self.alphabet.chars();
+        let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        let mut buffer: u32 = 0;
+        let mut bits: u32 = 0;
+
+        for &byte in bytes {
+            buffer = (buffer << 8) | u32::from(byte);
+            bits += 8;
+
+            while bits >= 6 {
+                bits -= 6;
+                encoded.push(chars[((buffer >> bits) & 0x3F) as usize] as char);
+            }
+        }
+
+        if bits > 0 {
+            // Pad the last group with 0s on the right
+            encoded.push(chars[((buffer << (6 - bits)) & 0x3F) as usize] as char);
+        }
+
+        if self.padding {
+            while encoded.len() % 4 != 0 {
+                encoded.push('=');
+            }
+        }
+
+        encoded
+    }
+
+    /// Decodes `input`, failing on the first character outside of the alphabet.
+    ///
+    /// With padding enabled the input length must be a multiple of 4, and `=` may only
+    /// appear as the last one or two characters. Without padding the input can stop after
+    /// any number of characters but `4n + 1`, which would leave a byte half decoded.
+    pub fn decode(&self, input: &str) -> Result<Vec<u8>, Base64Error> {
+        let data = if self.padding {
+            if input.len() % 4 != 0 {
+                return Err(Base64Error::MissingPadding {
+                    expected: input.len().next_multiple_of(4),
+                    actual: input.len(),
+                });
+            }
+            let trimmed = input.trim_end_matches('=');
+            if input.len() - trimmed.len() > 2 {
+                return Err(Base64Error::BadCharacter { character: '=', position: trimmed.len() });
+            }
+            trimmed
+        } else {
+            input
+        };
+
+        let mut decoded = Vec::with_capacity(data.len() * 3 / 4);
+        let mut buffer: u32 = 0;
+        let mut bits: u32 = 0;
+
+        for (position, character) in data.chars().enumerate() {
+            let value = u8::try_from(character)
+                .ok()
+                .and_then(|byte| self.alphabet.value(byte))
+                .ok_or(Base64Error::BadCharacter { character, position })?;
+
+            buffer = (buffer << 6) | u32::from(value);
+            bits += 6;
+
+            if bits >= 8 {
+                bits -= 8;
+                decoded.push((buffer >> bits) as u8);
+            }
+        }
+
+        let length = data.chars().count();
+        if length % 4 == 1 {
+            return Err(Base64Error::TruncatedInput { length });
+        }
+        Ok(decoded)
+    }
+
+    /// Re-encodes a payload from this codec into another one.
+    pub fn convert(&self, input: &str, to: &Base64) -> Result<String, Base64Error> {
+        Ok(to.encode(&self.decode(input)?))
+    }
+}
+
+/// Converts a payload in the game format, such as a radar view, to standard padded base64
+/// readable by `base64 -d` and similar tools.
+///
+/// # Examples
+/// ```
+/// use shared::base64::{game_to_standard, standard_to_game};
+/// let standard = game_to_standard("jivbQjIad/apapa").unwrap();
+/// assert_eq!(standard, "JIVBqJiAD/APAPA=");
+/// assert_eq!(standard_to_game(&standard).unwrap(), "jivbQjIad/apapa");
+/// ```
+pub fn game_to_standard(input: &str) -> Result<String, Base64Error> {
+    Base64::GAME.convert(input, &Base64::STANDARD)
+}
+
+/// Converts standard base64, padded or not, to the game format.
+pub fn standard_to_game(input: &str) -> Result<String, Base64Error> {
+    let codec = Base64::new(Alphabet::Standard, input.ends_with('='));
+    codec.convert(input, &Base64::GAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RFC_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    fn all_codecs() -> Vec<Base64> {
+        [Alphabet::Standard, Alphabet::UrlSafe, Alphabet::Game]
+            .into_iter()
+            .flat_map(|alphabet| [Base64::new(alphabet, true), Base64::new(alphabet, false)])
+            .collect()
+    }
+
+    #[test]
+    fn test_rfc_vectors() {
+        for (plain, encoded) in RFC_VECTORS {
+            assert_eq!(Base64::STANDARD.encode(plain.as_bytes()), encoded);
+            assert_eq!(Base64::STANDARD.decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_every_codec() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for codec in all_codecs() {
+            for len in 0..bytes.len() {
+                let encoded = codec.encode(&bytes[..len]);
+                assert_eq!(codec.decode(&encoded).unwrap(), &bytes[..len], "{:?}", codec);
+            }
+        }
+    }
+
+    #[test]
+    fn test_url_safe_alphabet() {
+        assert_eq!(Base64::URL_SAFE.encode(&[0xFB, 0xFF]), "-_8");
+        assert_eq!(Base64::STANDARD.encode(&[0xFB, 0xFF]), "+/8=");
+        assert_eq!(Base64::URL_SAFE.decode("-_8").unwrap(), vec![0xFB, 0xFF]);
+        assert!(Base64::URL_SAFE.decode("+/8").is_err());
+    }
+
+    #[test]
+    fn test_game_alphabet_swaps_letter_case() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let swapped: String = Base64::new(Alphabet::Standard, false)
+            .encode(&bytes)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }
+            })
+            .collect();
+        assert_eq!(Base64::GAME.encode(&bytes), swapped);
+        assert_eq!(Base64::GAME.encode(&[25]), "gq");
+    }
+
+    #[test]
+    fn test_conversion_round_trip() {
+        for view in ["jivbQjIad/apapa", "bKgGjsIyap8p8aa", "bieakcGa//+F8pa"] {
+            let standard = game_to_standard(view).unwrap();
+            assert_eq!(standard.len() % 4, 0);
+            assert_eq!(Base64::STANDARD.decode(&standard), Base64::GAME.decode(view));
+            assert_eq!(standard_to_game(&standard).unwrap(), view);
+
+            let url_safe = Base64::GAME.convert(view, &Base64::URL_SAFE).unwrap();
+            assert_eq!(Base64::URL_SAFE.convert(&url_safe, &Base64::GAME).unwrap(), view);
+        }
+
+        assert_eq!(standard_to_game("Zm9vYg").unwrap(), Base64::GAME.encode(b"foob"));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            Base64::STANDARD.decode("Zm9"),
+            Err(Base64Error::MissingPadding { expected: 4, actual: 3 })
+        );
+        assert_eq!(
+            Base64::STANDARD.decode("Z==="),
+            Err(Base64Error::BadCharacter { character: '=', position: 1 })
+        );
+        assert_eq!(
+            Base64::STANDARD.decode("Z=m="),
+            Err(Base64Error::BadCharacter { character: '=', position: 1 })
+        );
+        assert_eq!(
+            Base64::GAME.decode("zM8="),
+            Err(Base64Error::BadCharacter { character: '=', position: 3 })
+        );
+        assert_eq!(
+            Base64::GAME.decode("zMé"),
+            Err(Base64Error::BadCharacter { character: 'é', position: 2 })
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_input() {
+        for codec in [Base64::GAME, Base64::URL_SAFE, Base64::new(Alphabet::Standard, false)] {
+            assert_eq!(codec.decode("z"), Err(Base64Error::TruncatedInput { length: 1 }));
+            assert_eq!(codec.decode("zM8aq"), Err(Base64Error::TruncatedInput { length: 5 }));
+            assert!(codec.decode("zM").is_ok());
+            assert!(codec.decode("zM8").is_ok());
+            assert!(codec.decode("zM8a").is_ok());
+        }
+        assert_eq!(standard_to_game("Zm9vY"), Err(Base64Error::TruncatedInput { length: 5 }));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::maze::ItemPlacement;
+use clap::Args;
+
+/// Terrain and items of a generated maze, flattened into the options of every binary that
+/// generates one.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ContentArgs {
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = parse_ratio,
+        help = "Ratio of the passages covered by mud and water, between 0 and 1."
+    )]
+    pub terrain: f64,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = parse_ratio,
+        help = "Ratio of the cells holding a help item."
+    )]
+    pub help_items: f64,
+
+    #[arg(
+        long,
+        default_value = "0",
+        value_parser = parse_ratio,
+        help = "Ratio of the cells holding a monster."
+    )]
+    pub monsters: f64,
+
+    #[arg(long, default_value = "0", help = "Number of objectives on top of the exit.")]
+    pub objectives: usize,
+
+    #[arg(long, default_value = "5", help = "Fewest moves from the entry to a monster.")]
+    pub monster_distance: usize,
+
+    #[arg(long, default_value = "1", help = "Fewest cells between two items.")]
+    pub item_spacing: usize,
+}
+
+impl ContentArgs {
+    pub fn items(&self) -> ItemPlacement {
+        ItemPlacement {
+            help: self.help_items,
+            monsters: self.monsters,
+            objectives: self.objectives,
+            monster_distance: self.monster_distance,
+            spacing: self.item_spacing,
+        }
+    }
+}
+
+/// Parses a ratio between 0 and 1, both included.
+pub fn parse_ratio(value: &str) -> Result<f64, String> {
+    let ratio: f64 = value.parse().map_err(|_| format!("`{}` is not a number", value))?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(ratio)
+    } else {
+        Err(format!("{} is not between 0 and 1", ratio))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser, Debug)]
+    struct Cli {
+        #[command(flatten)]
+        content: ContentArgs,
+    }
+
+    #[test]
+    fn test_parse_ratio() {
+        assert_eq!(parse_ratio("0"), Ok(0.0));
+        assert_eq!(parse_ratio("0.25"), Ok(0.25));
+        assert_eq!(parse_ratio("1"), Ok(1.0));
+        assert!(parse_ratio("-0.1").is_err());
+        assert!(parse_ratio("1.5").is_err());
+        assert!(parse_ratio("lots").is_err());
+    }
+
+    #[test]
+    fn test_items() {
+        let cli = Cli::try_parse_from(["test"]).unwrap();
+        assert_eq!(cli.content.terrain, 0.0);
+        assert_eq!(cli.content.items(), ItemPlacement::default());
+
+        let cli = Cli::try_parse_from([
+            "test",
+            "--help-items",
+            "0.1",
+            "--monsters",
+            "0.05",
+            "--objectives",
+            "2",
+            "--item-spacing",
+            "3",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.content.items(),
+            ItemPlacement {
+                help: 0.1,
+                monsters: 0.05,
+                objectives: 2,
+                monster_distance: 5,
+                spacing: 3
+            }
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::logger::Logger;
+use crate::maze::validation::MazeIssue;
 use std::error::Error;
 use std::fmt;
 use std::io;
@@ -11,6 +12,7 @@ pub enum GameError {
     MessageError(String),
     ThreadError(String),
     SerializationError(String),
+    RadarError(RadarError),
 }
 
 impl fmt::Display for GameError {
@@ -24,6 +26,7 @@ impl fmt::Display for GameError {
             GameError::MessageError(msg) => write!(f, "Message error: {}", msg),
             GameError::ThreadError(msg) => write!(f, "Thread error: {}", msg),
             GameError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            GameError::RadarError(err) => write!(f, "Radar error: {}", err),
         }
     }
 }
@@ -42,6 +45,12 @@ impl From<serde_json::Error> for GameError {
     }
 }
 
+impl From<RadarError> for GameError {
+    fn from(err: RadarError) -> Self {
+        GameError::RadarError(err)
+    }
+}
+
 impl GameError {
     pub fn log_error(&self, logger: &Logger) {
         logger.error(&self.to_string());
@@ -50,6 +59,133 @@ impl GameError {
 
 pub type GameResult<T> = Result<T, GameError>;
 
+/// Errors raised while decoding the binary content of a radar view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadarError {
+    BadAlphabet { character: char, position: usize },
+    WrongLength { expected: usize, actual: usize },
+    NotBinary(char),
+    UnknownCell { index: usize, code: u8 },
+    UnknownPassage { index: usize, code: u8 },
+}
+
+impl fmt::Display for RadarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadarError::BadAlphabet { character, position } => {
+                write!(f, "Invalid base64 character {:?} at position {}", character, position)
+            }
+            RadarError::WrongLength { expected, actual } => {
+                write!(f, "Wrong radar length: expected {}, found {}", expected, actual)
+            }
+            RadarError::NotBinary(c) => write!(f, "Invalid binary digit: {:?}", c),
+            RadarError::UnknownCell { index, code } => {
+                write!(f, "Unknown code {} for cell {}", code, index)
+            }
+            RadarError::UnknownPassage { index, code } => {
+                write!(f, "Unknown code {} for passage {}", code, index)
+            }
+        }
+    }
+}
+
+impl Error for RadarError {}
+
+impl From<Base64Error> for RadarError {
+    fn from(err: Base64Error) -> Self {
+        match err {
+            Base64Error::BadCharacter { character, position } => {
+                RadarError::BadAlphabet { character, position }
+            }
+            Base64Error::MissingPadding { expected, actual } => {
+                RadarError::WrongLength { expected, actual }
+            }
+            Base64Error::TruncatedInput { length } => {
+                RadarError::WrongLength { expected: length - 1, actual: length }
+            }
+        }
+    }
+}
+
+/// Errors raised while decoding base64, see [`Base64`](crate::base64::Base64).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base64Error {
+    /// A character outside of the alphabet, or padding before the end of the input.
+    BadCharacter { character: char, position: usize },
+    /// A padded input whose length is not a multiple of 4.
+    MissingPadding { expected: usize, actual: usize },
+    /// An input of `4n + 1` characters: the last one holds 6 bits, not enough for a byte.
+    TruncatedInput { length: usize },
+}
+
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base64Error::BadCharacter { character, position } => {
+                write!(f, "Invalid base64 character {:?} at position {}", character, position)
+            }
+            Base64Error::MissingPadding { expected, actual } => {
+                write!(f, "Padded base64 of {} characters, expected {}", actual, expected)
+            }
+            Base64Error::TruncatedInput { length } => {
+                write!(f, "Truncated base64 of {} characters", length)
+            }
+        }
+    }
+}
+
+impl Error for Base64Error {}
+
+/// Errors raised while parsing a maze from its text representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    Empty,
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownCharacter {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+    MissingMarker(char),
+    OutOfBounds {
+        row: i16,
+        column: i16,
+    },
+    InvalidJson(String),
+    /// The maze was read but is not playable, see
+    /// [`validate`](crate::maze::validation::validate).
+    Invalid(Vec<MazeIssue>),
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::Empty => write!(f, "Maze is empty"),
+            MazeError::RaggedRow { row, expected, found } => {
+                write!(f, "Row {} has {} columns, expected {}", row, found, expected)
+            }
+            MazeError::UnknownCharacter { row, column, character } => {
+                write!(f, "Unknown character {:?} at row {}, column {}", character, row, column)
+            }
+            MazeError::MissingMarker(marker) => write!(f, "Maze has no {:?} marker", marker),
+            MazeError::OutOfBounds { row, column } => {
+                write!(f, "Position ({}, {}) is outside of the maze", row, column)
+            }
+            MazeError::InvalidJson(msg) => write!(f, "Invalid maze JSON: {}", msg),
+            MazeError::Invalid(issues) => {
+                let issues: Vec<String> = issues.iter().map(ToString::to_string).collect();
+                write!(f, "Invalid maze: {}", issues.join(", "))
+            }
+        }
+    }
+}
+
+impl Error for MazeError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +214,47 @@ mod tests {
 
         let err = GameError::SerializationError("invalid JSON".to_string());
         assert_eq!(err.to_string(), "Serialization error: invalid JSON");
+
+        let err = GameError::from(RadarError::WrongLength { expected: 11, actual: 3 });
+        assert_eq!(err.to_string(), "Radar error: Wrong radar length: expected 11, found 3");
+    }
+
+    #[test]
+    fn test_decoding_error_display() {
+        let err = RadarError::WrongLength { expected: 24, actual: 3 };
+        assert_eq!(err.to_string(), "Wrong radar length: expected 24, found 3");
+
+        let err = RadarError::NotBinary('x');
+        assert_eq!(err.to_string(), "Invalid binary digit: 'x'");
+
+        let err = RadarError::BadAlphabet { character: '=', position: 15 };
+        assert_eq!(err.to_string(), "Invalid base64 character '=' at position 15");
+
+        let err = RadarError::UnknownCell { index: 4, code: 5 };
+        assert_eq!(err.to_string(), "Unknown code 5 for cell 4");
+
+        let err = RadarError::UnknownPassage { index: 11, code: 3 };
+        assert_eq!(err.to_string(), "Unknown code 3 for passage 11");
+
+        assert_eq!(MazeError::Empty.to_string(), "Maze is empty");
+        let err = MazeError::RaggedRow { row: 2, expected: 5, found: 4 };
+        assert_eq!(err.to_string(), "Row 2 has 4 columns, expected 5");
+        let err = MazeError::UnknownCharacter { row: 1, column: 3, character: 'x' };
+        assert_eq!(err.to_string(), "Unknown character 'x' at row 1, column 3");
+        assert_eq!(MazeError::MissingMarker('2').to_string(), "Maze has no '2' marker");
+        let err = MazeError::OutOfBounds { row: -1, column: 4 };
+        assert_eq!(err.to_string(), "Position (-1, 4) is outside of the maze");
+
+        let err = Base64Error::BadCharacter { character: '=', position: 1 };
+        assert_eq!(err.to_string(), "Invalid base64 character '=' at position 1");
+        let err = Base64Error::MissingPadding { expected: 8, actual: 6 };
+        assert_eq!(err.to_string(), "Padded base64 of 6 characters, expected 8");
+        let err = Base64Error::TruncatedInput { length: 5 };
+        assert_eq!(err.to_string(), "Truncated base64 of 5 characters");
+        assert_eq!(
+            RadarError::from(Base64Error::TruncatedInput { length: 13 }),
+            RadarError::WrongLength { expected: 12, actual: 13 }
+        );
     }
 
     #[test]
@@ -141,6 +318,7 @@ mod tests {
             GameError::MessageError("malformed message".to_string()),
             GameError::ThreadError("thread crashed".to_string()),
             GameError::SerializationError("invalid JSON format".to_string()),
+            GameError::RadarError(RadarError::NotBinary('2')),
         ];
 
         for error in errors {
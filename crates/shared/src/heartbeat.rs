@@ -0,0 +1,173 @@
+use crate::errors::{GameError, GameResult};
+use crate::messages::{Heartbeat, Message, poll_message, receive_message, send_message};
+use std::io::Write;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// Seconds of silence before a `Ping` is sent, shared by the server and the client.
+pub const DEFAULT_INTERVAL_SECS: u64 = 5;
+/// Seconds of silence before the peer is considered dead, shared by the server and the
+/// client so neither waits longer than the other.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    /// How long a connection may stay quiet before a `Ping` is sent.
+    /// A zero interval disables heartbeats entirely.
+    pub interval: Duration,
+    /// How long the peer may stay silent before it is considered dead.
+    pub timeout: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self::from_secs(DEFAULT_INTERVAL_SECS, DEFAULT_TIMEOUT_SECS)
+    }
+}
+
+impl HeartbeatConfig {
+    pub fn from_secs(interval: u64, timeout: u64) -> Self {
+        Self { interval: Duration::from_secs(interval), timeout: Duration::from_secs(timeout) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.interval.is_zero()
+    }
+}
+
+/// Tracks the liveness of the peer on one connection.
+///
+/// Both the server and the client wrap their receive loop with a monitor: it answers
+/// `Ping` messages, pings the peer when the connection goes quiet and reports when the
+/// peer has been silent for longer than the configured timeout.
+pub struct HeartbeatMonitor {
+    config: HeartbeatConfig,
+    last_seen: Instant,
+    sequence: u32,
+}
+
+impl HeartbeatMonitor {
+    pub fn new(config: HeartbeatConfig) -> Self {
+        Self { config, last_seen: Instant::now(), sequence: 0 }
+    }
+
+    pub fn record_activity(&mut self) {
+        self.last_seen = Instant::now();
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.last_seen.elapsed() >= self.config.timeout
+    }
+
+    pub fn next_ping(&mut self) -> Message {
+        self.sequence = self.sequence.wrapping_add(1);
+        Message::Ping(Heartbeat { sequence: self.sequence })
+    }
+
+    /// Receives the next application message from the stream.
+    ///
+    /// Heartbeat traffic is handled here and never returned to the caller.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(message))` - The next non-heartbeat message
+    /// * `Ok(None)` - The peer missed its heartbeats and should be considered dead
+    /// * `Err(_)` - The connection was closed or the message could not be read
+    pub fn receive(&mut self, stream: &mut TcpStream) -> GameResult<Option<Message>> {
+        let mut replies = stream.try_clone().map_err(GameError::ConnectionError)?;
+        self.receive_replying(stream, &mut replies)
+    }
+
+    /// Same as [`HeartbeatMonitor::receive`], with the `Pong` and `Ping` messages written
+    /// to `replies` instead of `stream`, for connections whose writes go through a queue.
+    pub fn receive_replying<W: Write>(
+        &mut self,
+        stream: &mut TcpStream,
+        replies: &mut W,
+    ) -> GameResult<Option<Message>> {
+        if !self.config.is_enabled() {
+            return receive_message(stream).map(Some);
+        }
+
+        loop {
+            match poll_message(stream, self.config.interval)? {
+                Some(Message::Ping(heartbeat)) => {
+                    self.record_activity();
+                    send_message(replies, &Message::Pong(heartbeat))?;
+                }
+                Some(Message::Pong(_)) => self.record_activity(),
+                Some(message) => {
+                    self.record_activity();
+                    return Ok(Some(message));
+                }
+                None if self.is_expired() => return Ok(None),
+                None => {
+                    let ping = self.next_ping();
+                    send_message(replies, &ping)?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::Logger;
+    use crate::messages::RegisterTeam;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn connected_pair() -> (TcpStream, TcpStream) {
+        Logger::init(true);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (client, server)
+    }
+
+    #[test]
+    fn test_disabled_config() {
+        assert!(HeartbeatConfig::default().is_enabled());
+        assert!(!HeartbeatConfig::from_secs(0, 10).is_enabled());
+    }
+
+    #[test]
+    fn test_next_ping_increments_sequence() {
+        let mut monitor = HeartbeatMonitor::new(HeartbeatConfig::default());
+        assert!(matches!(monitor.next_ping(), Message::Ping(Heartbeat { sequence: 1 })));
+        assert!(matches!(monitor.next_ping(), Message::Ping(Heartbeat { sequence: 2 })));
+    }
+
+    #[test]
+    fn test_answers_ping_and_returns_message() {
+        let (mut client, mut server) = connected_pair();
+
+        let peer = thread::spawn(move || {
+            send_message(&mut server, &Message::Ping(Heartbeat { sequence: 7 })).unwrap();
+            let pong = receive_message(&mut server).unwrap();
+            send_message(&mut server, &Message::RegisterTeam(RegisterTeam { name: "t".into() }))
+                .unwrap();
+            pong
+        });
+
+        let mut monitor = HeartbeatMonitor::new(HeartbeatConfig::default());
+        let message = monitor.receive(&mut client).unwrap();
+        assert!(matches!(message, Some(Message::RegisterTeam(_))));
+        assert!(matches!(peer.join().unwrap(), Message::Pong(Heartbeat { sequence: 7 })));
+    }
+
+    #[test]
+    fn test_silent_peer_expires() {
+        let (mut client, server) = connected_pair();
+        let config = HeartbeatConfig {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(50),
+        };
+
+        let mut monitor = HeartbeatMonitor::new(config);
+        assert!(monitor.receive(&mut client).unwrap().is_none());
+        drop(server);
+    }
+}
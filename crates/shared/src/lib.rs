@@ -1,8 +1,12 @@
+pub mod base64;
+pub mod cli;
 pub mod errors;
+pub mod heartbeat;
 pub mod logger;
 pub mod maze;
 pub mod maze_generator;
 pub mod messages;
 pub mod radar;
+pub mod schema;
 pub mod to_binary;
 pub mod utils;
@@ -1,6 +1,23 @@
-use crate::maze_generator::sidewinder;
-use crate::utils::ColorsAnsi;
+use crate::errors::MazeError;
+use crate::maze_generator::{
+    add_terrain, backtracker, braid, eller, kruskal, place_entry_exit, place_items, prim,
+    shift_walls, sidewinder, wilson,
+};
+use crate::messages::Direction;
+use crate::utils::{ColorsAnsi, heat_green};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 use std::ops::Add;
+use std::str::FromStr;
+
+pub mod analysis;
+pub mod grid;
+pub mod io;
+pub mod levels;
+pub mod svg;
+pub mod validation;
 
 pub struct Maze {
     pub map: Vec<Vec<u16>>,
@@ -8,29 +25,308 @@ pub struct Maze {
     pub col_len: usize,
     pub entry: Cell,
     pub exit: Cell,
+    /// Help items, monster spawn points and extra objectives, see [`Maze::place_items`].
+    pub items: Vec<Item>,
+    /// What the passages closed by [`Maze::shift_walls`] held, restored when they reopen.
+    pub closed_passages: HashMap<Cell, u16>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GeneratorAlgorithm {
     Sidewinder,
+    Backtracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    Eller,
+}
+
+impl GeneratorAlgorithm {
+    pub const ALL: [GeneratorAlgorithm; 6] = [
+        GeneratorAlgorithm::Sidewinder,
+        GeneratorAlgorithm::Backtracker,
+        GeneratorAlgorithm::Prim,
+        GeneratorAlgorithm::Kruskal,
+        GeneratorAlgorithm::Wilson,
+        GeneratorAlgorithm::Eller,
+    ];
+}
+
+impl fmt::Display for GeneratorAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GeneratorAlgorithm::Sidewinder => "Sidewinder",
+            GeneratorAlgorithm::Backtracker => "Backtracker",
+            GeneratorAlgorithm::Prim => "Prim",
+            GeneratorAlgorithm::Kruskal => "Kruskal",
+            GeneratorAlgorithm::Wilson => "Wilson",
+            GeneratorAlgorithm::Eller => "Eller",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for GeneratorAlgorithm {
+    type Err = String;
+
+    /// Parses an algorithm name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GeneratorAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<String> =
+                    GeneratorAlgorithm::ALL.iter().map(ToString::to_string).collect();
+                format!("unknown generator '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// How many loops to add to a generated maze, see [`Maze::braid`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Braiding {
+    /// Exactly one path between any two cells.
+    #[default]
+    Perfect,
+    /// Remove this fraction of the dead ends, between 0 and 1.
+    Partial(f64),
+    /// Remove every dead end.
+    Full,
+}
+
+impl Braiding {
+    /// The fraction of dead ends removed.
+    pub fn ratio(self) -> f64 {
+        match self {
+            Braiding::Perfect => 0.0,
+            Braiding::Partial(ratio) => ratio,
+            Braiding::Full => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for Braiding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Braiding::Perfect => write!(f, "perfect"),
+            Braiding::Partial(ratio) => write!(f, "{}", ratio),
+            Braiding::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl FromStr for Braiding {
+    type Err = String;
+
+    /// Parses `perfect`, `full` or a ratio of dead ends to remove such as `0.3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("perfect") {
+            return Ok(Braiding::Perfect);
+        }
+        if s.eq_ignore_ascii_case("full") {
+            return Ok(Braiding::Full);
+        }
+        match s.parse::<f64>() {
+            Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(Braiding::Partial(ratio)),
+            _ => Err(format!(
+                "invalid braiding '{}', expected perfect, full or a ratio between 0 and 1",
+                s
+            )),
+        }
+    }
+}
+
+/// Where to put the entry and the exit of a generated maze, see [`Maze::place_entry_exit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Placement {
+    /// Entry in the first column and exit in the last one, on random rows.
+    #[default]
+    Edges,
+    /// Two distinct random cells.
+    Random,
+    /// The two cells with the longest path between them.
+    Farthest,
+    /// Random cells at least this many cells apart, or the farthest pair if there are
+    /// none.
+    MinDistance(usize),
+}
+
+impl fmt::Display for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Placement::Edges => write!(f, "edges"),
+            Placement::Random => write!(f, "random"),
+            Placement::Farthest => write!(f, "farthest"),
+            Placement::MinDistance(distance) => write!(f, "min-distance:{}", distance),
+        }
+    }
+}
+
+impl FromStr for Placement {
+    type Err = String;
+
+    /// Parses `edges`, `random`, `farthest` or `min-distance:<cells>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "edges" => Ok(Placement::Edges),
+            "random" => Ok(Placement::Random),
+            "farthest" => Ok(Placement::Farthest),
+            _ => lower
+                .strip_prefix("min-distance:")
+                .and_then(|distance| distance.parse().ok())
+                .map(Placement::MinDistance)
+                .ok_or_else(|| {
+                    format!(
+                        "invalid placement '{}', expected edges, random, farthest or min-distance:<cells>",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+/// What can be placed on a cell besides the entry and the exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    Help,
+    Monster,
+    Objective,
+}
+
+/// An item and the map position of the cell holding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub cell: Cell,
+}
+
+/// How many items to place in a generated maze and how far apart, see
+/// [`Maze::place_items`]. The default places nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemPlacement {
+    /// Ratio of the cells holding a help item.
+    pub help: f64,
+    /// Ratio of the cells holding a monster spawn point.
+    pub monsters: f64,
+    /// Number of objectives on top of the exit.
+    pub objectives: usize,
+    /// Fewest moves from the entry to a monster.
+    pub monster_distance: usize,
+    /// Fewest cells between two items, counted along rows and columns.
+    pub spacing: usize,
+}
+
+impl Default for ItemPlacement {
+    fn default() -> Self {
+        Self { help: 0.0, monsters: 0.0, objectives: 0, monster_distance: 5, spacing: 1 }
+    }
+}
+
+impl ItemPlacement {
+    /// Whether this placement puts anything in the maze.
+    pub fn is_empty(&self) -> bool {
+        self.help <= 0.0 && self.monsters <= 0.0 && self.objectives == 0
+    }
 }
 
 impl Maze {
     pub fn new(map: Vec<Vec<u16>>, entry: Cell, exit: Cell) -> Self {
         let row_len = map.len();
         let col_len = if map.is_empty() { 0 } else { map[0].len() };
-        Self { map, row_len, col_len, entry, exit }
+        Self {
+            map,
+            row_len,
+            col_len,
+            entry,
+            exit,
+            items: Vec::new(),
+            closed_passages: HashMap::new(),
+        }
     }
 
-    pub fn generate(
-        algorithm: GeneratorAlgorithm,
-        width: usize,
-        height: usize,
-        print: bool,
-        seed: u64,
-    ) -> Self {
+    pub fn generate(algorithm: GeneratorAlgorithm, width: usize, height: usize, seed: u64) -> Self {
         match algorithm {
-            GeneratorAlgorithm::Sidewinder => sidewinder(width, height, print, seed),
+            GeneratorAlgorithm::Sidewinder => sidewinder(width, height, seed),
+            GeneratorAlgorithm::Backtracker => backtracker(width, height, seed),
+            GeneratorAlgorithm::Prim => prim(width, height, seed),
+            GeneratorAlgorithm::Kruskal => kruskal(width, height, seed),
+            GeneratorAlgorithm::Wilson => wilson(width, height, seed),
+            GeneratorAlgorithm::Eller => eller(width, height, seed),
+        }
+    }
+
+    /// Adds loops by removing dead ends, see [`braid`] for the details.
+    pub fn braid(&mut self, braiding: Braiding, seed: u64) {
+        if braiding != Braiding::Perfect {
+            braid(self, braiding.ratio(), seed);
+        }
+    }
+
+    /// Moves the entry and the exit, see [`place_entry_exit`] for the details.
+    pub fn place_entry_exit(&mut self, placement: Placement, seed: u64) {
+        place_entry_exit(self, placement, seed);
+    }
+
+    /// Covers a `ratio` of the passages with mud and water, see [`add_terrain`] for the
+    /// details.
+    pub fn add_terrain(&mut self, ratio: f64, seed: u64) {
+        if ratio > 0.0 {
+            add_terrain(self, ratio, seed);
+        }
+    }
+
+    /// Replaces the items with new ones, see [`place_items`] for the details.
+    pub fn place_items(&mut self, placement: &ItemPlacement, seed: u64) {
+        place_items(self, placement, seed);
+    }
+
+    /// Opens `count` walls and closes as many passages, see [`shift_walls`] for the
+    /// details. Returns the map positions that changed.
+    pub fn shift_walls(&mut self, count: usize, seed: u64) -> Vec<Cell> {
+        shift_walls(self, count, seed)
+    }
+
+    /// Checks the maze is well formed, see [`validate`](validation::validate) for the
+    /// details.
+    pub fn validate(&self) -> Result<(), MazeError> {
+        checked(validation::validate(self))
+    }
+
+    /// Checks the maze is well formed and perfect, see
+    /// [`validate_perfect`](validation::validate_perfect) for the details.
+    pub fn validate_perfect(&self) -> Result<(), MazeError> {
+        checked(validation::validate_perfect(self))
+    }
+
+    /// The item on `cell`, if any.
+    pub fn item_at(&self, cell: Cell) -> Option<ItemKind> {
+        self.items.iter().find(|item| item.cell == cell).map(|item| item.kind)
+    }
+
+    /// Cost of stepping onto `cell`, `None` for walls and positions outside of the maze.
+    pub fn cost(&self, cell: Cell) -> Option<u32> {
+        if self.is_cell_out_of_bound(&cell) {
+            return None;
         }
+        PositionType::cost(self.map[cell.row as usize][cell.column as usize])
+    }
+
+    /// Where a player standing on `cell` and looking toward `facing` ends up after moving
+    /// one cell toward `direction`, and where they look then. `None` when a wall is in the
+    /// way or the cell behind it cannot be walked on, the player then neither moves nor
+    /// turns.
+    pub fn step(&self, cell: Cell, facing: Cell, direction: &Direction) -> Option<(Cell, Cell)> {
+        let facing = Directions::turn(facing, direction);
+        let passage = cell + facing;
+        self.cost(passage)?;
+        self.cost(passage + facing)?;
+        Some((passage + facing, facing))
+    }
+
+    /// Total cost of walking along `path`, the first position being where the walk starts.
+    pub fn path_cost(&self, path: &[Cell]) -> u32 {
+        path.iter().skip(1).filter_map(|&cell| self.cost(cell)).sum()
     }
 
     pub fn print_maze(maze: &Maze) {
@@ -41,6 +337,8 @@ impl Maze {
                     match *cell {
                         PositionType::WALL => '#',
                         PositionType::SPACE => ' ',
+                        PositionType::MUD => ':',
+                        PositionType::WATER => '~',
                         _ => '?',
                     }
                 );
@@ -52,10 +350,7 @@ impl Maze {
     pub fn print_visited(&self, visited_points: &[Vec<i32>]) {
         let steps = *visited_points.iter().flatten().max().unwrap_or(&0);
         let gradient: Vec<String> = (0..=steps)
-            .map(|step| {
-                let gradient_value = 255 - (255 * step / steps);
-                format!("\x1b[38;2;255;{};0m", gradient_value)
-            })
+            .map(|step| format!("\x1b[38;2;255;{};0m", heat_green(step, steps)))
             .collect();
 
         for (row_idx, row) in self.map.iter().enumerate() {
@@ -78,6 +373,8 @@ impl Maze {
                         match cell {
                             PositionType::WALL => '#',
                             PositionType::SPACE => ' ',
+                            PositionType::MUD => ':',
+                            PositionType::WATER => '~',
                             _ => '?',
                         }
                     );
@@ -91,10 +388,7 @@ impl Maze {
     pub fn print_visited_number(&self, visited_points: &[Vec<i32>]) {
         let steps = *visited_points.iter().flatten().max().unwrap_or(&0);
         let gradient: Vec<String> = (0..=steps)
-            .map(|step| {
-                let gradient_value = 255 - (255 * step / steps);
-                format!("\x1b[38;2;255;{};0m", gradient_value)
-            })
+            .map(|step| format!("\x1b[38;2;255;{};0m", heat_green(step, steps)))
             .collect();
 
         for (row_idx, row) in self.map.iter().enumerate() {
@@ -133,6 +427,8 @@ impl Maze {
                     match cell {
                         PositionType::WALL => print!("#####"),
                         PositionType::SPACE => print!("     "),
+                        PositionType::MUD => print!(" ::: "),
+                        PositionType::WATER => print!(" ~~~ "),
                         _ => print!("?  "),
                     }
                 }
@@ -159,6 +455,8 @@ impl Maze {
                         match cell {
                             PositionType::WALL => '#',
                             PositionType::SPACE => ' ',
+                            PositionType::MUD => ':',
+                            PositionType::WATER => '~',
                             _ => '?',
                         }
                     );
@@ -182,7 +480,11 @@ impl Maze {
     }
 }
 
-#[derive(Clone, PartialEq, Copy, Debug, Eq, Hash)]
+fn checked(issues: Vec<validation::MazeIssue>) -> Result<(), MazeError> {
+    if issues.is_empty() { Ok(()) } else { Err(MazeError::Invalid(issues)) }
+}
+
+#[derive(Clone, PartialEq, Copy, Debug, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct Cell {
     pub row: i16,
     pub column: i16,
@@ -203,6 +505,19 @@ impl PositionType {
     pub const SPACE: u16 = 0;
     pub const ENTRY: u16 = 2;
     pub const EXIT: u16 = 3;
+    /// Walkable terrain slowing the players down, see [`PositionType::cost`].
+    pub const MUD: u16 = 4;
+    pub const WATER: u16 = 5;
+
+    /// Cost of stepping onto a position, `None` for walls. A plain passage costs 1.
+    pub fn cost(position: u16) -> Option<u32> {
+        match position {
+            PositionType::WALL => None,
+            PositionType::MUD => Some(3),
+            PositionType::WATER => Some(5),
+            _ => Some(1),
+        }
+    }
 }
 
 pub struct Directions;
@@ -212,6 +527,17 @@ impl Directions {
     pub const SOUTH: Cell = Cell { row: 1, column: 0 };
     pub const WEST: Cell = Cell { row: 0, column: -1 };
     pub const EAST: Cell = Cell { row: 0, column: 1 };
+
+    /// Where a player looking toward `facing` looks after turning toward `direction`,
+    /// `direction` being relative to where they look.
+    pub fn turn(facing: Cell, direction: &Direction) -> Cell {
+        match direction {
+            Direction::Front => facing,
+            Direction::Right => Cell { row: facing.column, column: -facing.row },
+            Direction::Back => Cell { row: -facing.row, column: -facing.column },
+            Direction::Left => Cell { row: -facing.column, column: facing.row },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -300,11 +626,117 @@ mod tests {
     fn test_maze_generate() {
         let width = 5;
         let height = 10;
-        let maze = Maze::generate(GeneratorAlgorithm::Sidewinder, width, height, false, 0);
+        for algorithm in GeneratorAlgorithm::ALL {
+            let maze = Maze::generate(algorithm, width, height, 0);
+
+            assert_eq!(maze.row_len, height * 2 + 1);
+            assert_eq!(maze.col_len, width * 2 + 1);
+            assert!(!maze.is_cell_out_of_bound(&maze.entry));
+            assert!(!maze.is_cell_out_of_bound(&maze.exit));
+        }
+    }
+
+    #[test]
+    fn test_braiding_from_str() {
+        assert_eq!("perfect".parse(), Ok(Braiding::Perfect));
+        assert_eq!("Full".parse(), Ok(Braiding::Full));
+        assert_eq!("0.25".parse(), Ok(Braiding::Partial(0.25)));
+        assert!("1.5".parse::<Braiding>().is_err());
+        assert!("some".parse::<Braiding>().is_err());
+
+        for braiding in [Braiding::Perfect, Braiding::Partial(0.4), Braiding::Full] {
+            assert_eq!(braiding.to_string().parse(), Ok(braiding));
+        }
+    }
+
+    #[test]
+    fn test_placement_from_str() {
+        assert_eq!("edges".parse(), Ok(Placement::Edges));
+        assert_eq!("Farthest".parse(), Ok(Placement::Farthest));
+        assert_eq!("min-distance:12".parse(), Ok(Placement::MinDistance(12)));
+        assert!("min-distance:".parse::<Placement>().is_err());
+        assert!("center".parse::<Placement>().is_err());
+
+        for placement in
+            [Placement::Edges, Placement::Random, Placement::Farthest, Placement::MinDistance(3)]
+        {
+            assert_eq!(placement.to_string().parse(), Ok(placement));
+        }
+    }
+
+    #[test]
+    fn test_maze_place_entry_exit() {
+        let mut maze = Maze::generate(GeneratorAlgorithm::Prim, 8, 8, 2);
+        maze.place_entry_exit(Placement::Random, 2);
 
-        assert_eq!(maze.row_len, height * 2 + 1);
-        assert_eq!(maze.col_len, width * 2 + 1);
-        assert!(!maze.is_cell_out_of_bound(&maze.entry));
-        assert!(!maze.is_cell_out_of_bound(&maze.exit));
+        let entry = maze.map[maze.entry.row as usize][maze.entry.column as usize];
+        let exit = maze.map[maze.exit.row as usize][maze.exit.column as usize];
+        assert_eq!((entry, exit), (PositionType::ENTRY, PositionType::EXIT));
+    }
+
+    #[test]
+    fn test_maze_braid() {
+        let perfect = Maze::generate(GeneratorAlgorithm::Backtracker, 12, 12, 4);
+        let mut maze = Maze::generate(GeneratorAlgorithm::Backtracker, 12, 12, 4);
+        maze.braid(Braiding::Perfect, 4);
+        assert_eq!(maze.map, perfect.map);
+
+        maze.braid(Braiding::Full, 4);
+        assert_ne!(maze.map, perfect.map);
+    }
+
+    #[test]
+    fn test_terrain_cost() {
+        let map = vec![vec![
+            PositionType::ENTRY,
+            PositionType::MUD,
+            PositionType::WATER,
+            PositionType::EXIT,
+            PositionType::WALL,
+        ]];
+        let maze = Maze::new(map, Cell { row: 0, column: 0 }, Cell { row: 0, column: 3 });
+
+        assert_eq!(maze.cost(Cell { row: 0, column: 1 }), Some(3));
+        assert_eq!(maze.cost(Cell { row: 0, column: 2 }), Some(5));
+        assert_eq!(maze.cost(Cell { row: 0, column: 4 }), None);
+        assert_eq!(maze.cost(Cell { row: 1, column: 0 }), None);
+
+        let path: Vec<Cell> = (0..4).map(|column| Cell { row: 0, column }).collect();
+        assert_eq!(maze.path_cost(&path), 3 + 5 + 1);
+        assert_eq!(maze.path_cost(&path[..1]), 0);
+    }
+
+    #[test]
+    fn test_step() {
+        let mut maze = io::read_text("#####\n#2  #\n### #\n#3  #\n#####\n").expect("valid maze");
+        let entry = maze.entry;
+
+        assert_eq!(Directions::turn(Directions::NORTH, &Direction::Right), Directions::EAST);
+        assert_eq!(Directions::turn(Directions::NORTH, &Direction::Left), Directions::WEST);
+        assert_eq!(Directions::turn(Directions::EAST, &Direction::Back), Directions::WEST);
+        assert_eq!(Directions::turn(Directions::EAST, &Direction::Right), Directions::SOUTH);
+
+        assert_eq!(maze.step(entry, Directions::NORTH, &Direction::Front), None);
+        let (cell, facing) =
+            maze.step(entry, Directions::NORTH, &Direction::Right).expect("open passage");
+        assert_eq!((cell, facing), (Cell { row: 1, column: 3 }, Directions::EAST));
+        let (cell, facing) = maze.step(cell, facing, &Direction::Right).expect("open passage");
+        assert_eq!(maze.step(cell, facing, &Direction::Right), Some((maze.exit, Directions::WEST)));
+
+        // An open passage leading onto a wall does not move the player either
+        maze.map[1][3] = PositionType::WALL;
+        assert_eq!(maze.step(entry, Directions::NORTH, &Direction::Right), None);
+    }
+
+    #[test]
+    fn test_generator_algorithm_from_str() {
+        for algorithm in GeneratorAlgorithm::ALL {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+        }
+        assert_eq!("wilson".parse(), Ok(GeneratorAlgorithm::Wilson));
+        assert_eq!("BACKTRACKER".parse(), Ok(GeneratorAlgorithm::Backtracker));
+        assert!(
+            "dfs".parse::<GeneratorAlgorithm>().unwrap_err().contains("Sidewinder, Backtracker")
+        );
     }
 }
@@ -0,0 +1,406 @@
+use crate::maze::{Cell, Directions, Maze, PositionType};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+const DIRECTIONS: [Cell; 4] =
+    [Directions::NORTH, Directions::EAST, Directions::SOUTH, Directions::WEST];
+
+/// Metrics describing the shape of a maze and how hard it is to solve.
+///
+/// Every distance is counted in steps between walkable positions of `Maze.map`, like the
+/// solvers do, so a generated maze has two steps per cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazeAnalysis {
+    /// Walkable positions of the map.
+    pub open_positions: usize,
+    /// Positions with a single way out.
+    pub dead_ends: usize,
+    /// Positions where three or four passages meet.
+    pub junctions: usize,
+    /// Number of corridors of each length, a corridor running between two dead ends or
+    /// junctions.
+    pub corridor_lengths: BTreeMap<usize, usize>,
+    /// Average number of new passages offered when reaching a junction.
+    pub branching_factor: f64,
+    /// Steps from the entry to the exit, `None` if the exit cannot be reached.
+    pub shortest_path: Option<usize>,
+    /// Junctions crossed by the shortest path, each one is a choice for the player.
+    pub path_decisions: usize,
+    /// Direction changes along the shortest path.
+    pub path_turns: usize,
+    /// Longest distance between two positions reachable from the entry. Exact for
+    /// perfect mazes, a lower bound once there are loops.
+    pub diameter: usize,
+    /// Average length of the corridors ending in a dead end. Mazes with a high river
+    /// have few but long blind alleys.
+    pub river: f64,
+    /// Fraction of the corridor positions where the passage turns.
+    pub turn_ratio: f64,
+    /// Score between 0 and 100 combining the metrics above.
+    ///
+    /// It is only meaningful to compare mazes of the same size, see [`analyze`].
+    pub difficulty: f64,
+}
+
+impl MazeAnalysis {
+    pub fn corridors(&self) -> usize {
+        self.corridor_lengths.values().sum()
+    }
+
+    pub fn mean_corridor_length(&self) -> f64 {
+        let total: usize = self.corridor_lengths.iter().map(|(length, count)| length * count).sum();
+        ratio(total, self.corridors())
+    }
+
+    pub fn longest_corridor(&self) -> usize {
+        self.corridor_lengths.keys().next_back().copied().unwrap_or(0)
+    }
+}
+
+/// Analyzes a maze given its shortest entry-to-exit path.
+///
+/// `shortest_path` is the list of positions from the entry to the exit returned by a
+/// solver such as `client::maze_solver::bfs_shortest_path`, or empty if there is none.
+///
+/// The difficulty is the weighted average of four ratios between 0 and 1:
+/// - the shortest path over the diameter, long solutions are harder (30%),
+/// - the share of all junctions met on the shortest path (30%),
+/// - the river over the river plus the mean corridor length, long dead ends waste more
+///   time (20%),
+/// - the turns per step along the shortest path (20%).
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::analysis::analyze;
+/// use shared::maze::{Cell, Maze};
+///
+/// let maze = Maze::new(
+///     vec![vec![1, 1, 1, 1], vec![1, 2, 3, 1], vec![1, 1, 1, 1]],
+///     Cell { row: 1, column: 1 },
+///     Cell { row: 1, column: 2 },
+/// );
+/// let analysis = analyze(&maze, &[maze.entry, maze.exit]);
+/// assert_eq!(analysis.shortest_path, Some(1));
+/// assert_eq!(analysis.dead_ends, 2);
+/// ```
+pub fn analyze(maze: &Maze, shortest_path: &[Cell]) -> MazeAnalysis {
+    let mut open_positions = 0;
+    let mut dead_ends = 0;
+    let mut junctions = 0;
+    let mut corridor_positions = 0;
+    let mut turning_positions = 0;
+    let mut branches = 0;
+
+    for cell in open_cells(maze) {
+        open_positions += 1;
+        let exits = open_neighbors(maze, cell);
+        match exits.len() {
+            1 => dead_ends += 1,
+            2 => {
+                corridor_positions += 1;
+                if exits[0].row != exits[1].row && exits[0].column != exits[1].column {
+                    turning_positions += 1;
+                }
+            }
+            3 | 4 => {
+                junctions += 1;
+                branches += exits.len() - 1;
+            }
+            _ => {}
+        }
+    }
+
+    let mut corridor_lengths = BTreeMap::new();
+    let mut dead_end_lengths = Vec::new();
+    for cell in open_cells(maze).filter(|&cell| !is_corridor(maze, cell)) {
+        for next in open_neighbors(maze, cell) {
+            let length = corridor_length(maze, cell, next);
+            // Every corridor is walked once from each end
+            *corridor_lengths.entry(length).or_insert(0) += 1;
+            if open_neighbors(maze, cell).len() == 1 {
+                dead_end_lengths.push(length);
+            }
+        }
+    }
+    for count in corridor_lengths.values_mut() {
+        *count /= 2;
+    }
+    corridor_lengths.retain(|_, count| *count > 0);
+
+    let steps = shortest_path.len().checked_sub(1);
+    let path_decisions = shortest_path
+        .iter()
+        .skip(1)
+        .take(steps.unwrap_or(0).saturating_sub(1))
+        .filter(|&&cell| open_neighbors(maze, cell).len() >= 3)
+        .count();
+    let path_turns = shortest_path
+        .windows(3)
+        .filter(|window| {
+            let first = (window[1].row - window[0].row, window[1].column - window[0].column);
+            let second = (window[2].row - window[1].row, window[2].column - window[1].column);
+            first != second
+        })
+        .count();
+
+    let diameter = diameter(maze);
+    let river = ratio(dead_end_lengths.iter().sum(), dead_end_lengths.len());
+
+    let mut analysis = MazeAnalysis {
+        open_positions,
+        dead_ends,
+        junctions,
+        corridor_lengths,
+        branching_factor: ratio(branches, junctions),
+        shortest_path: steps,
+        path_decisions,
+        path_turns,
+        diameter,
+        river,
+        turn_ratio: ratio(turning_positions, corridor_positions),
+        difficulty: 0.0,
+    };
+
+    let mean_corridor = analysis.mean_corridor_length();
+    let steps = steps.unwrap_or(0);
+    analysis.difficulty = 100.0
+        * (0.3 * ratio(steps, diameter).min(1.0)
+            + 0.3 * ratio(path_decisions, junctions)
+            + 0.2 * if river > 0.0 { river / (river + mean_corridor) } else { 0.0 }
+            + 0.2 * ratio(path_turns, steps));
+    analysis
+}
+
+/// Returns the number of steps from `start` to every position of the maze, -1 for walls
+/// and unreachable positions, in the same layout as the `visited_points` of the solvers.
+pub fn distances_from(maze: &Maze, start: Cell) -> Vec<Vec<i32>> {
+    let mut distances = vec![vec![-1; maze.col_len]; maze.row_len];
+    if !is_open(maze, start) {
+        return distances;
+    }
+
+    distances[start.row as usize][start.column as usize] = 0;
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+        let distance = distances[cell.row as usize][cell.column as usize];
+        for next in open_neighbors(maze, cell) {
+            let slot = &mut distances[next.row as usize][next.column as usize];
+            if *slot == -1 {
+                *slot = distance + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Runs two breadth-first searches, the farthest position from any position being one
+/// end of a longest path in a tree.
+fn diameter(maze: &Maze) -> usize {
+    let farthest = |distances: &[Vec<i32>]| {
+        let mut best = (0, maze.entry);
+        for (row, line) in distances.iter().enumerate() {
+            for (column, &distance) in line.iter().enumerate() {
+                if distance > best.0 {
+                    best = (distance, Cell { row: row as i16, column: column as i16 });
+                }
+            }
+        }
+        best
+    };
+
+    let (_, end) = farthest(&distances_from(maze, maze.entry));
+    let (distance, _) = farthest(&distances_from(maze, end));
+    distance as usize
+}
+
+/// Steps from `from` through `next` until reaching a position that is not a corridor.
+fn corridor_length(maze: &Maze, from: Cell, next: Cell) -> usize {
+    let (mut previous, mut current) = (from, next);
+    let mut length = 1;
+    while is_corridor(maze, current) {
+        let Some(following) =
+            open_neighbors(maze, current).into_iter().find(|&cell| cell != previous)
+        else {
+            break;
+        };
+        (previous, current) = (current, following);
+        length += 1;
+        // A loop made only of corridor positions never ends
+        if current == next {
+            break;
+        }
+    }
+    length
+}
+
+fn is_open(maze: &Maze, cell: Cell) -> bool {
+    !maze.is_cell_out_of_bound(&cell)
+        && maze.map[cell.row as usize][cell.column as usize] != PositionType::WALL
+}
+
+fn is_corridor(maze: &Maze, cell: Cell) -> bool {
+    open_neighbors(maze, cell).len() == 2
+}
+
+fn open_neighbors(maze: &Maze, cell: Cell) -> Vec<Cell> {
+    DIRECTIONS
+        .iter()
+        .map(|&direction| cell + direction)
+        .filter(|&next| is_open(maze, next))
+        .collect()
+}
+
+fn open_cells(maze: &Maze) -> impl Iterator<Item = Cell> + '_ {
+    (0..maze.row_len)
+        .flat_map(move |row| (0..maze.col_len).map(move |column| (row, column)))
+        .map(|(row, column)| Cell { row: row as i16, column: column as i16 })
+        .filter(|&cell| is_open(maze, cell))
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 }
+}
+
+impl fmt::Display for MazeAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Open positions    {}", self.open_positions)?;
+        writeln!(f, "Dead ends         {}", self.dead_ends)?;
+        writeln!(f, "Junctions         {}", self.junctions)?;
+        writeln!(f, "Branching factor  {:.2}", self.branching_factor)?;
+        writeln!(
+            f,
+            "Corridors         {} (mean {:.1}, longest {})",
+            self.corridors(),
+            self.mean_corridor_length(),
+            self.longest_corridor()
+        )?;
+        for (length, count) in &self.corridor_lengths {
+            writeln!(f, "  {:>5} steps     {}", length, count)?;
+        }
+        match self.shortest_path {
+            Some(steps) => writeln!(
+                f,
+                "Shortest path     {} steps, {} decisions, {} turns",
+                steps, self.path_decisions, self.path_turns
+            )?,
+            None => writeln!(f, "Shortest path     unreachable exit")?,
+        }
+        writeln!(f, "Diameter          {}", self.diameter)?;
+        writeln!(f, "River             {:.2}", self.river)?;
+        writeln!(f, "Turn ratio        {:.2}", self.turn_ratio)?;
+        write!(f, "Difficulty        {:.1} / 100", self.difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::{backtracker, braid, sidewinder};
+
+    /// Walks back from the exit along decreasing distances.
+    fn shortest_path(maze: &Maze) -> Vec<Cell> {
+        let distances = distances_from(maze, maze.entry);
+        let mut path = vec![maze.exit];
+        let mut current = maze.exit;
+        while distances[current.row as usize][current.column as usize] > 0 {
+            let distance = distances[current.row as usize][current.column as usize];
+            current = open_neighbors(maze, current)
+                .into_iter()
+                .find(|cell| distances[cell.row as usize][cell.column as usize] == distance - 1)
+                .unwrap();
+            path.push(current);
+        }
+        path.reverse();
+        path
+    }
+
+    /// ```text
+    /// #######
+    /// #E  # #
+    /// # # # #
+    /// #   X #
+    /// #######
+    /// ```
+    fn small_maze() -> Maze {
+        let map = vec![
+            vec![1, 1, 1, 1, 1, 1, 1],
+            vec![1, 2, 0, 0, 1, 0, 1],
+            vec![1, 0, 1, 0, 1, 0, 1],
+            vec![1, 0, 0, 0, 3, 0, 1],
+            vec![1, 1, 1, 1, 1, 1, 1],
+        ];
+        Maze::new(map, Cell { row: 1, column: 1 }, Cell { row: 3, column: 4 })
+    }
+
+    #[test]
+    fn test_distances_from() {
+        let maze = small_maze();
+        let distances = distances_from(&maze, maze.entry);
+        assert_eq!(distances[1][1], 0);
+        assert_eq!(distances[3][3], 4);
+        assert_eq!(distances[3][4], 5);
+        assert_eq!(distances[1][5], 8);
+        assert_eq!(distances[0][0], -1);
+    }
+
+    #[test]
+    fn test_analyze_small_maze() {
+        let maze = small_maze();
+        let analysis = analyze(&maze, &shortest_path(&maze));
+
+        assert_eq!(analysis.open_positions, 12);
+        assert_eq!(analysis.dead_ends, 1);
+        assert_eq!(analysis.junctions, 1);
+        assert_eq!(analysis.branching_factor, 2.0);
+        // The loop around the center wall from the junction and the way to the dead end
+        assert_eq!(analysis.corridor_lengths, BTreeMap::from([(4, 1), (8, 1)]));
+        assert_eq!(analysis.shortest_path, Some(5));
+        assert_eq!(analysis.path_decisions, 1);
+        assert_eq!(analysis.path_turns, 2);
+        assert_eq!(analysis.diameter, 8);
+        assert_eq!(analysis.river, 4.0);
+    }
+
+    #[test]
+    fn test_analyze_unreachable_exit() {
+        let maze = Maze::new(
+            vec![vec![1, 1, 1, 1, 1], vec![1, 2, 1, 3, 1], vec![1, 1, 1, 1, 1]],
+            Cell { row: 1, column: 1 },
+            Cell { row: 1, column: 3 },
+        );
+        let analysis = analyze(&maze, &[]);
+        assert_eq!(analysis.shortest_path, None);
+        assert_eq!(analysis.diameter, 0);
+        assert_eq!(analysis.difficulty, 0.0);
+        assert!(analysis.to_string().contains("unreachable exit"));
+    }
+
+    #[test]
+    fn test_perfect_maze_metrics() {
+        let maze = backtracker(12, 12, 8);
+        let analysis = analyze(&maze, &shortest_path(&maze));
+
+        // In a tree, the leaves are two more than the extra branches of the junctions
+        let extra_branches = (analysis.branching_factor * analysis.junctions as f64).round();
+        assert_eq!(analysis.dead_ends as f64, 2.0 + extra_branches - analysis.junctions as f64);
+        assert!(analysis.shortest_path.unwrap() <= analysis.diameter);
+        assert!((0.0..=100.0).contains(&analysis.difficulty));
+        assert!(analysis.to_string().ends_with("/ 100"));
+    }
+
+    #[test]
+    fn test_braiding_removes_dead_ends() {
+        let perfect = sidewinder(15, 15, 21);
+        let mut braided = sidewinder(15, 15, 21);
+        braid(&mut braided, 1.0, 21);
+
+        let before = analyze(&perfect, &shortest_path(&perfect));
+        let after = analyze(&braided, &shortest_path(&braided));
+        assert!(after.dead_ends < before.dead_ends);
+        assert!(after.shortest_path <= before.shortest_path);
+        assert!(after.open_positions > before.open_positions);
+        assert!(braided.map.iter().flatten().any(|&p| p == PositionType::ENTRY));
+    }
+}
@@ -0,0 +1,399 @@
+use crate::maze::{Cell, Maze, PositionType};
+
+/// Coordinates of a cell as (row, column), counted in cells rather than map positions.
+pub type GridPosition = (usize, usize);
+
+/// The largest width and height of a [`Maze`] built from a [`MazeGrid`], whose map
+/// positions must fit the `i16` coordinates of [`Cell`]. Larger mazes need a
+/// [`CompactMaze`].
+pub const MAX_MAZE_SIZE: usize = (i16::MAX as usize - 1) / 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] =
+        [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// The direction leading from `from` to the adjacent cell `to`.
+    pub fn between(from: GridPosition, to: GridPosition) -> Option<Direction> {
+        match (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize) {
+            (-1, 0) => Some(Direction::North),
+            (0, 1) => Some(Direction::East),
+            (1, 0) => Some(Direction::South),
+            (0, -1) => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// The neighbour of `cell` in this direction, `None` if it is outside of a `width` x
+    /// `height` grid.
+    pub fn step(
+        self,
+        (row, column): GridPosition,
+        width: usize,
+        height: usize,
+    ) -> Option<GridPosition> {
+        match self {
+            Direction::North => row.checked_sub(1).map(|row| (row, column)),
+            Direction::East => (column + 1 < width).then_some((row, column + 1)),
+            Direction::South => (row + 1 < height).then_some((row + 1, column)),
+            Direction::West => column.checked_sub(1).map(|column| (row, column)),
+        }
+    }
+}
+
+/// A rectangular maze of cells separated by walls, the storage shared by the generators
+/// and the grid solvers.
+pub trait MazeGrid {
+    /// An empty `width` x `height` grid with every wall up.
+    fn with_walls(width: usize, height: usize) -> Self
+    where
+        Self: Sized;
+
+    /// Width in cells.
+    fn width(&self) -> usize;
+
+    /// Height in cells.
+    fn height(&self) -> usize;
+
+    /// Whether the wall between `cell` and its neighbour in `direction` is removed.
+    /// Always `false` on the border.
+    fn is_open(&self, cell: GridPosition, direction: Direction) -> bool;
+
+    /// Removes the wall between `cell` and its neighbour in `direction`.
+    fn carve(&mut self, cell: GridPosition, direction: Direction);
+
+    /// Whether `cell` is part of the maze, for layouts where cells can be walls too.
+    fn is_cell_open(&self, _cell: GridPosition) -> bool {
+        true
+    }
+
+    /// Makes `cell` part of the maze, for layouts where cells can be walls too.
+    fn open_cell(&mut self, _cell: GridPosition) {}
+
+    fn entry(&self) -> GridPosition;
+
+    fn exit(&self) -> GridPosition;
+
+    fn set_entry_exit(&mut self, entry: GridPosition, exit: GridPosition);
+
+    /// The neighbours of `cell` reachable without crossing a wall.
+    fn open_neighbors(&self, cell: GridPosition) -> impl Iterator<Item = GridPosition> + '_ {
+        Direction::ALL.into_iter().filter_map(move |direction| {
+            if self.is_open(cell, direction) {
+                direction.step(cell, self.width(), self.height())
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// The doubled layout of `Maze.map`, cell `(row, column)` being stored at
+/// `(row * 2 + 1, column * 2 + 1)` with the walls in between. Mazes using another layout,
+/// such as the parsed ones, cannot be read through this trait.
+///
+/// # Panics
+///
+/// `with_walls` panics on a width or height above [`MAX_MAZE_SIZE`].
+impl MazeGrid for Maze {
+    fn with_walls(width: usize, height: usize) -> Self {
+        assert!(
+            width <= MAX_MAZE_SIZE && height <= MAX_MAZE_SIZE,
+            "a {}x{} maze does not fit the i16 coordinates of Maze, at most {} cells per side",
+            width,
+            height,
+            MAX_MAZE_SIZE
+        );
+        Maze::new(
+            vec![vec![PositionType::WALL; width * 2 + 1]; height * 2 + 1],
+            Cell { row: 0, column: 0 },
+            Cell { row: 0, column: 0 },
+        )
+    }
+
+    fn width(&self) -> usize {
+        self.col_len.saturating_sub(1) / 2
+    }
+
+    fn height(&self) -> usize {
+        self.row_len.saturating_sub(1) / 2
+    }
+
+    fn is_open(&self, (row, column): GridPosition, direction: Direction) -> bool {
+        if direction.step((row, column), self.width(), self.height()).is_none() {
+            return false;
+        }
+        let (row, column) = wall_position((row, column), direction);
+        self.map[row][column] != PositionType::WALL
+    }
+
+    fn carve(&mut self, cell: GridPosition, direction: Direction) {
+        if direction.step(cell, self.width(), self.height()).is_some() {
+            let (row, column) = wall_position(cell, direction);
+            self.map[row][column] = PositionType::SPACE;
+        }
+    }
+
+    fn is_cell_open(&self, (row, column): GridPosition) -> bool {
+        self.map[row * 2 + 1][column * 2 + 1] != PositionType::WALL
+    }
+
+    fn open_cell(&mut self, (row, column): GridPosition) {
+        self.map[row * 2 + 1][column * 2 + 1] = PositionType::SPACE;
+    }
+
+    fn entry(&self) -> GridPosition {
+        (self.entry.row as usize / 2, self.entry.column as usize / 2)
+    }
+
+    fn exit(&self) -> GridPosition {
+        (self.exit.row as usize / 2, self.exit.column as usize / 2)
+    }
+
+    fn set_entry_exit(&mut self, entry: GridPosition, exit: GridPosition) {
+        for marker in [self.entry, self.exit] {
+            let position = self
+                .map
+                .get_mut(marker.row as usize)
+                .and_then(|row| row.get_mut(marker.column as usize));
+            if let Some(position) = position {
+                if *position == PositionType::ENTRY || *position == PositionType::EXIT {
+                    *position = PositionType::SPACE;
+                }
+            }
+        }
+
+        (self.entry, self.exit) = (to_map(entry), to_map(exit));
+        self.map[self.entry.row as usize][self.entry.column as usize] = PositionType::ENTRY;
+        self.map[self.exit.row as usize][self.exit.column as usize] = PositionType::EXIT;
+    }
+}
+
+/// The map position of a cell of a [`Maze`], which holds at most [`MAX_MAZE_SIZE`] cells
+/// per side.
+pub(crate) fn to_map((row, column): GridPosition) -> Cell {
+    let coordinate = |value: usize| {
+        i16::try_from(value * 2 + 1).expect("a Maze holds at most MAX_MAZE_SIZE cells per side")
+    };
+    Cell { row: coordinate(row), column: coordinate(column) }
+}
+
+fn wall_position((row, column): GridPosition, direction: Direction) -> (usize, usize) {
+    let (row, column) = (row * 2 + 1, column * 2 + 1);
+    match direction {
+        Direction::North => (row - 1, column),
+        Direction::East => (row, column + 1),
+        Direction::South => (row + 1, column),
+        Direction::West => (row, column - 1),
+    }
+}
+
+/// A maze storing two bits per cell, whether its east and south walls are removed, in a
+/// single flat buffer.
+///
+/// A 10,000 x 10,000 maze takes 25 MB where `Maze` needs about 800 MB, and the `usize`
+/// coordinates are not limited to the `i16` range of [`Cell`].
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::GeneratorAlgorithm;
+/// use shared::maze::grid::{CompactMaze, Direction, MazeGrid};
+/// use shared::maze_generator::generate_grid;
+///
+/// let maze: CompactMaze = generate_grid(GeneratorAlgorithm::Sidewinder, 20_000, 3, 42);
+/// assert_eq!(maze.width(), 20_000);
+/// // Sidewinder always opens the whole first row
+/// assert!(maze.is_open((0, 15_000), Direction::East));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactMaze {
+    width: usize,
+    height: usize,
+    passages: Vec<u64>,
+    entry: GridPosition,
+    exit: GridPosition,
+}
+
+impl CompactMaze {
+    const EAST: usize = 0;
+    const SOUTH: usize = 1;
+
+    /// Bytes used by the walls.
+    pub fn heap_size(&self) -> usize {
+        self.passages.capacity() * size_of::<u64>()
+    }
+
+    fn bit(&self, (row, column): GridPosition, wall: usize) -> usize {
+        (row * self.width + column) * 2 + wall
+    }
+
+    /// The cell and the wall bit holding the passage of `cell` in `direction`.
+    fn passage(&self, cell: GridPosition, direction: Direction) -> Option<usize> {
+        let neighbor = direction.step(cell, self.width, self.height)?;
+        Some(match direction {
+            Direction::East => self.bit(cell, Self::EAST),
+            Direction::South => self.bit(cell, Self::SOUTH),
+            Direction::West => self.bit(neighbor, Self::EAST),
+            Direction::North => self.bit(neighbor, Self::SOUTH),
+        })
+    }
+}
+
+impl MazeGrid for CompactMaze {
+    fn with_walls(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            passages: vec![0; (width * height * 2).div_ceil(64)],
+            entry: (0, 0),
+            exit: (0, 0),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn is_open(&self, cell: GridPosition, direction: Direction) -> bool {
+        self.passage(cell, direction)
+            .is_some_and(|bit| (self.passages[bit / 64] >> (bit % 64)) & 1 == 1)
+    }
+
+    fn carve(&mut self, cell: GridPosition, direction: Direction) {
+        if let Some(bit) = self.passage(cell, direction) {
+            self.passages[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    fn entry(&self) -> GridPosition {
+        self.entry
+    }
+
+    fn exit(&self) -> GridPosition {
+        self.exit
+    }
+
+    fn set_entry_exit(&mut self, entry: GridPosition, exit: GridPosition) {
+        (self.entry, self.exit) = (entry, exit);
+    }
+}
+
+/// # Panics
+///
+/// Panics on a `CompactMaze` wider or taller than [`MAX_MAZE_SIZE`].
+impl From<&CompactMaze> for Maze {
+    fn from(compact: &CompactMaze) -> Self {
+        let mut maze = Maze::with_walls(compact.width, compact.height);
+        for row in 0..compact.height {
+            for column in 0..compact.width {
+                maze.open_cell((row, column));
+                for direction in [Direction::East, Direction::South] {
+                    if compact.is_open((row, column), direction) {
+                        maze.carve((row, column), direction);
+                    }
+                }
+            }
+        }
+        if compact.width > 0 && compact.height > 0 {
+            maze.set_entry_exit(compact.entry, compact.exit);
+        }
+        maze
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_step() {
+        assert_eq!(Direction::North.step((0, 0), 3, 3), None);
+        assert_eq!(Direction::West.step((1, 0), 3, 3), None);
+        assert_eq!(Direction::East.step((1, 2), 3, 3), None);
+        assert_eq!(Direction::South.step((1, 2), 3, 3), Some((2, 2)));
+        for direction in Direction::ALL {
+            let next = direction.step((1, 1), 3, 3).unwrap();
+            assert_eq!(Direction::between((1, 1), next), Some(direction));
+        }
+        assert_eq!(Direction::between((0, 0), (1, 1)), None);
+    }
+
+    #[test]
+    fn test_compact_carve() {
+        let mut maze = CompactMaze::with_walls(33, 3);
+        assert!(!maze.is_open((1, 31), Direction::East));
+
+        maze.carve((1, 31), Direction::East);
+        maze.carve((1, 31), Direction::North);
+        assert!(maze.is_open((1, 31), Direction::East));
+        assert!(maze.is_open((1, 32), Direction::West));
+        assert!(maze.is_open((0, 31), Direction::South));
+        assert!(!maze.is_open((1, 31), Direction::South));
+        assert_eq!(maze.open_neighbors((1, 31)).collect::<Vec<_>>(), vec![(0, 31), (1, 32)]);
+
+        // The border has no neighbour to open onto
+        maze.carve((2, 32), Direction::East);
+        maze.carve((0, 0), Direction::North);
+        assert!(!maze.is_open((2, 32), Direction::East));
+        assert!(!maze.is_open((0, 0), Direction::North));
+        assert_eq!(maze.heap_size(), 32);
+    }
+
+    #[test]
+    fn test_maze_grid_layout() {
+        let mut maze = Maze::with_walls(3, 2);
+        maze.open_cell((0, 0));
+        maze.open_cell((0, 1));
+        maze.carve((0, 1), Direction::West);
+        maze.set_entry_exit((0, 0), (0, 1));
+
+        assert_eq!((maze.width(), maze.height()), (3, 2));
+        assert_eq!(maze.map[1], vec![1, 2, 0, 3, 1, 1, 1]);
+        assert!(maze.is_open((0, 0), Direction::East));
+        assert!(maze.is_cell_open((0, 1)) && !maze.is_cell_open((1, 1)));
+        assert_eq!((maze.entry(), maze.exit()), ((0, 0), (0, 1)));
+
+        maze.set_entry_exit((0, 1), (0, 0));
+        assert_eq!(maze.map[1], vec![1, 3, 0, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_compact_to_maze() {
+        let mut compact = CompactMaze::with_walls(2, 2);
+        compact.carve((0, 0), Direction::East);
+        compact.carve((0, 1), Direction::South);
+        compact.carve((1, 1), Direction::West);
+        compact.set_entry_exit((0, 0), (1, 0));
+
+        let maze = Maze::from(&compact);
+        assert_eq!(
+            maze.map,
+            vec![
+                vec![1, 1, 1, 1, 1],
+                vec![1, 2, 0, 0, 1],
+                vec![1, 1, 1, 0, 1],
+                vec![1, 3, 0, 0, 1],
+                vec![1, 1, 1, 1, 1],
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit the i16 coordinates of Maze")]
+    fn test_compact_too_large_for_maze() {
+        let compact = CompactMaze::with_walls(MAX_MAZE_SIZE + 1, 1);
+        let _ = Maze::from(&compact);
+    }
+}
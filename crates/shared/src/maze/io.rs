@@ -0,0 +1,656 @@
+use crate::errors::MazeError;
+use crate::maze::{Cell, GeneratorAlgorithm, Item, Maze, PositionType};
+use crate::radar::JUNCTIONS;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Arms of a box-drawing glyph, the bits indexing `JUNCTIONS`
+const UP: usize = 1;
+const RIGHT: usize = 2;
+const DOWN: usize = 4;
+const LEFT: usize = 8;
+
+const ENTRY_MARKER: char = '2';
+const EXIT_MARKER: char = '3';
+const MUD: char = ':';
+const WATER: char = '~';
+
+/// File formats a maze can be saved to and loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MazeFormat {
+    /// One character per map position: `#` for walls, `2` for the entry, `3` for the exit,
+    /// `:` for mud and `~` for water.
+    #[default]
+    Text,
+    /// Thin walls drawn with box-drawing glyphs, as in the benchmark inputs. Terrain is not
+    /// drawn.
+    Box,
+    /// The text rows along with the entry, the exit, the items and how the maze was
+    /// generated.
+    Json,
+}
+
+impl MazeFormat {
+    pub const ALL: [MazeFormat; 3] = [MazeFormat::Text, MazeFormat::Box, MazeFormat::Json];
+
+    /// Parses a maze, the metadata is only filled by the formats storing it.
+    pub fn read(self, input: &str) -> Result<(Maze, MazeMetadata), MazeError> {
+        match self {
+            MazeFormat::Text => read_text(input).map(|maze| (maze, MazeMetadata::default())),
+            MazeFormat::Box => read_box(input).map(|maze| (maze, MazeMetadata::default())),
+            MazeFormat::Json => read_json(input),
+        }
+    }
+
+    /// Guesses the format of a saved maze from its content.
+    pub fn detect(input: &str) -> MazeFormat {
+        if input.trim_start().starts_with('{') {
+            MazeFormat::Json
+        } else if input.chars().any(|character| arms(character).is_some_and(|arms| arms != 0)) {
+            MazeFormat::Box
+        } else {
+            MazeFormat::Text
+        }
+    }
+
+    pub fn write(self, maze: &Maze, metadata: &MazeMetadata) -> String {
+        match self {
+            MazeFormat::Text => write_text(maze),
+            MazeFormat::Box => write_box(maze),
+            MazeFormat::Json => write_json(maze, metadata),
+        }
+    }
+}
+
+impl fmt::Display for MazeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MazeFormat::Text => "text",
+            MazeFormat::Box => "box",
+            MazeFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for MazeFormat {
+    type Err = String;
+
+    /// Parses a format name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MazeFormat::ALL
+            .into_iter()
+            .find(|format| format.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown maze format '{}', expected text, box or json", s))
+    }
+}
+
+/// How a saved maze was generated, when it is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MazeMetadata {
+    pub seed: Option<u64>,
+    pub algorithm: Option<GeneratorAlgorithm>,
+    /// Whether the maze has exactly one path between two positions, checked when loading.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub perfect: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MazeFile {
+    #[serde(flatten)]
+    metadata: MazeMetadata,
+    entry: Cell,
+    exit: Cell,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    items: Vec<Item>,
+    rows: Vec<String>,
+}
+
+/// Reads the `#`/space format, any character other than `#`, a space, `2`, `3`, `:` or `~`
+/// is an error. So is a maze that does not pass [`Maze::validate`].
+pub fn read_text(input: &str) -> Result<Maze, MazeError> {
+    let map = parse(&grid(&lines(input))?, |character| match character {
+        ENTRY_MARKER => Some(PositionType::ENTRY),
+        EXIT_MARKER => Some(PositionType::EXIT),
+        _ => terrain(character),
+    })?;
+    let entry = find(&map, PositionType::ENTRY).ok_or(MazeError::MissingMarker(ENTRY_MARKER))?;
+    let exit = find(&map, PositionType::EXIT).ok_or(MazeError::MissingMarker(EXIT_MARKER))?;
+    let maze = Maze::new(map, entry, exit);
+    maze.validate()?;
+    Ok(maze)
+}
+
+pub fn write_text(maze: &Maze) -> String {
+    let mut text = String::with_capacity(maze.row_len * (maze.col_len + 1));
+    for row in &maze.map {
+        text.extend(row.iter().map(|&position| match position {
+            PositionType::ENTRY => ENTRY_MARKER,
+            PositionType::EXIT => EXIT_MARKER,
+            _ => terrain_char(position),
+        }));
+        text.push('\n');
+    }
+    text
+}
+
+/// Reads the box-drawing format of the benchmark inputs.
+///
+/// Each line of the drawing is a lattice row of the map: posts on even columns and the walls
+/// between them on odd columns, the vertical walls of the rows in between are rebuilt from
+/// the arms of the posts. A line of `w` characters and `h` lines gives a `w` x `2h - 1` map
+/// in the layout of the generators. A marker hides the wall it is drawn on, the posts on
+/// each side tell whether there is one, and stands for the cell below it, above it on the
+/// last line. The maze must pass [`Maze::validate`].
+pub fn read_box(input: &str) -> Result<Maze, MazeError> {
+    let lines = grid(&lines(input))?;
+    let height = lines.len() * 2 - 1;
+    let mut map = vec![vec![PositionType::SPACE; lines[0].len()]; height];
+    let (mut entry, mut exit) = (None, None);
+
+    for (line, glyphs) in lines.iter().enumerate() {
+        let row = line * 2;
+        for (column, &glyph) in glyphs.iter().enumerate() {
+            let walls = match glyph {
+                ENTRY_MARKER | EXIT_MARKER => {
+                    let cell = Cell {
+                        row: if row + 1 < height { row + 1 } else { row.saturating_sub(1) } as i16,
+                        column: column as i16,
+                    };
+                    if glyph == ENTRY_MARKER {
+                        entry = Some(cell);
+                    } else {
+                        exit = Some(cell);
+                    }
+                    let left = column.checked_sub(1).and_then(|left| arms(glyphs[left]));
+                    let right = glyphs.get(column + 1).and_then(|&right| arms(right));
+                    if left.is_some_and(|arms| arms & RIGHT != 0)
+                        || right.is_some_and(|arms| arms & LEFT != 0)
+                    {
+                        LEFT | RIGHT
+                    } else {
+                        0
+                    }
+                }
+                _ => arms(glyph).ok_or(MazeError::UnknownCharacter {
+                    row: line,
+                    column,
+                    character: glyph,
+                })?,
+            };
+
+            if column % 2 == 0 || walls != 0 {
+                map[row][column] = PositionType::WALL;
+            }
+            if column % 2 == 0 && row + 1 < height {
+                let below = arms(lines[line + 1][column]).unwrap_or(0);
+                if walls & DOWN != 0 || below & UP != 0 {
+                    map[row + 1][column] = PositionType::WALL;
+                }
+            }
+        }
+    }
+
+    let entry = entry.ok_or(MazeError::MissingMarker(ENTRY_MARKER))?;
+    let exit = exit.ok_or(MazeError::MissingMarker(EXIT_MARKER))?;
+    map[entry.row as usize][entry.column as usize] = PositionType::ENTRY;
+    map[exit.row as usize][exit.column as usize] = PositionType::EXIT;
+    let maze = Maze::new(map, entry, exit);
+    maze.validate()?;
+    Ok(maze)
+}
+
+/// Draws the maze with box-drawing glyphs, see [`read_box`] for the layout.
+///
+/// Only the even rows of the map are drawn, the cells of the odd rows are expected to be
+/// open as in generated mazes. The entry and the exit are drawn on the line above their
+/// cell.
+pub fn write_box(maze: &Maze) -> String {
+    let wall = |row: usize, column: usize| {
+        maze.map.get(row).and_then(|positions| positions.get(column)) == Some(&PositionType::WALL)
+    };
+
+    let mut lines: Vec<Vec<char>> = (0..maze.row_len)
+        .step_by(2)
+        .map(|row| {
+            (0..maze.col_len)
+                .map(|column| match (wall(row, column), column % 2) {
+                    (false, _) => ' ',
+                    (true, 1) => '─',
+                    (true, _) => {
+                        let around = [
+                            row.checked_sub(1).is_some_and(|up| wall(up, column)),
+                            wall(row, column + 1),
+                            wall(row + 1, column),
+                            column.checked_sub(1).is_some_and(|left| wall(row, left)),
+                        ];
+                        JUNCTIONS[around
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, wall)| **wall)
+                            .map(|(i, _)| 1 << i)
+                            .sum::<usize>()]
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    for (cell, marker) in [(maze.entry, ENTRY_MARKER), (maze.exit, EXIT_MARKER)] {
+        if let Some(glyph) =
+            lines.get_mut(cell.row as usize / 2).and_then(|line| line.get_mut(cell.column as usize))
+        {
+            *glyph = marker;
+        }
+    }
+
+    let mut text = String::with_capacity(lines.len() * (maze.col_len * 3 + 1));
+    for line in lines {
+        text.extend(line);
+        text.push('\n');
+    }
+    text
+}
+
+/// Reads a maze saved by [`write_json`], checking it with [`Maze::validate_perfect`] when
+/// its metadata claims it is perfect and [`Maze::validate`] otherwise.
+pub fn read_json(input: &str) -> Result<(Maze, MazeMetadata), MazeError> {
+    let file: MazeFile =
+        serde_json::from_str(input).map_err(|err| MazeError::InvalidJson(err.to_string()))?;
+    let rows: Vec<&str> = file.rows.iter().map(String::as_str).collect();
+    let mut map = parse(&grid(&rows)?, terrain)?;
+
+    for (cell, position) in [(file.entry, PositionType::ENTRY), (file.exit, PositionType::EXIT)] {
+        *slot(&mut map, cell)? = position;
+    }
+    for item in &file.items {
+        slot(&mut map, item.cell)?;
+    }
+    let mut maze = Maze::new(map, file.entry, file.exit);
+    maze.items = file.items;
+    if file.metadata.perfect { maze.validate_perfect() } else { maze.validate() }?;
+    Ok((maze, file.metadata))
+}
+
+fn slot(map: &mut [Vec<u16>], cell: Cell) -> Result<&mut u16, MazeError> {
+    usize::try_from(cell.row)
+        .ok()
+        .zip(usize::try_from(cell.column).ok())
+        .and_then(|(row, column)| map.get_mut(row).and_then(|row| row.get_mut(column)))
+        .ok_or(MazeError::OutOfBounds { row: cell.row, column: cell.column })
+}
+
+/// Saves the walls and the terrain as rows of the `#`/space format, with the entry, the
+/// exit and the items as fields.
+pub fn write_json(maze: &Maze, metadata: &MazeMetadata) -> String {
+    let rows = maze
+        .map
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&position| match position {
+                    PositionType::ENTRY | PositionType::EXIT => ' ',
+                    _ => terrain_char(position),
+                })
+                .collect()
+        })
+        .collect();
+    let file = MazeFile {
+        metadata: *metadata,
+        entry: maze.entry,
+        exit: maze.exit,
+        items: maze.items.clone(),
+        rows,
+    };
+    serde_json::to_string_pretty(&file).expect("a maze always serializes") + "\n"
+}
+
+/// Position stored as `character` in the text rows, markers aside.
+fn terrain(character: char) -> Option<u16> {
+    match character {
+        '#' => Some(PositionType::WALL),
+        ' ' => Some(PositionType::SPACE),
+        MUD => Some(PositionType::MUD),
+        WATER => Some(PositionType::WATER),
+        _ => None,
+    }
+}
+
+fn terrain_char(position: u16) -> char {
+    match position {
+        PositionType::WALL => '#',
+        PositionType::MUD => MUD,
+        PositionType::WATER => WATER,
+        _ => ' ',
+    }
+}
+
+/// Walls drawn by a box-drawing glyph, `None` for characters that are not one.
+fn arms(glyph: char) -> Option<usize> {
+    JUNCTIONS.iter().position(|&junction| junction == glyph)
+}
+
+/// The lines of the input without the blank lines around the maze.
+fn lines(input: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> =
+        input.lines().skip_while(|line| line.chars().all(char::is_whitespace)).collect();
+    while lines.last().is_some_and(|line| line.chars().all(char::is_whitespace)) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Splits the lines into characters, checking they form a non empty rectangle.
+fn grid(lines: &[&str]) -> Result<Vec<Vec<char>>, MazeError> {
+    let Some(first) = lines.first() else {
+        return Err(MazeError::Empty);
+    };
+    let width = first.chars().count();
+    lines
+        .iter()
+        .enumerate()
+        .map(|(row, line)| {
+            let characters: Vec<char> = line.chars().collect();
+            if characters.len() == width {
+                Ok(characters)
+            } else {
+                Err(MazeError::RaggedRow { row, expected: width, found: characters.len() })
+            }
+        })
+        .collect()
+}
+
+fn parse(
+    grid: &[Vec<char>],
+    position: impl Fn(char) -> Option<u16>,
+) -> Result<Vec<Vec<u16>>, MazeError> {
+    grid.iter()
+        .enumerate()
+        .map(|(row, characters)| {
+            characters
+                .iter()
+                .enumerate()
+                .map(|(column, &character)| {
+                    position(character).ok_or(MazeError::UnknownCharacter {
+                        row,
+                        column,
+                        character,
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn find(map: &[Vec<u16>], position: u16) -> Option<Cell> {
+    map.iter().enumerate().find_map(|(row, positions)| {
+        positions
+            .iter()
+            .position(|&p| p == position)
+            .map(|column| Cell { row: row as i16, column: column as i16 })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::analysis::distances_from;
+    use crate::maze::validation::MazeIssue;
+    use crate::maze::{Braiding, ItemPlacement, Placement};
+
+    const BOX: &str = "┌2──┐\n│ ╷3│\n└─┴─┘\n";
+    const TEXT: &str = "#####\n#2  #\n# # #\n# #3#\n#####\n";
+
+    fn assert_same(actual: &Maze, expected: &Maze) {
+        assert_eq!(actual.map, expected.map);
+        assert_eq!((actual.row_len, actual.col_len), (expected.row_len, expected.col_len));
+        assert_eq!(actual.entry, expected.entry);
+        assert_eq!(actual.exit, expected.exit);
+    }
+
+    fn generated() -> Vec<Maze> {
+        GeneratorAlgorithm::ALL
+            .into_iter()
+            .flat_map(|algorithm| {
+                [Braiding::Perfect, Braiding::Full].into_iter().map(move |braiding| {
+                    let mut maze = Maze::generate(algorithm, 9, 6, 11);
+                    maze.braid(braiding, 11);
+                    maze.place_entry_exit(Placement::Random, 11);
+                    maze
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let maze = read_text(TEXT).unwrap();
+        assert_eq!(maze.entry, Cell { row: 1, column: 1 });
+        assert_eq!(maze.exit, Cell { row: 3, column: 3 });
+        assert_eq!(write_text(&maze), TEXT);
+
+        for maze in generated() {
+            assert_same(&read_text(&write_text(&maze)).unwrap(), &maze);
+        }
+    }
+
+    #[test]
+    fn test_box_round_trip() {
+        let maze = read_box(BOX).unwrap();
+        assert_same(&maze, &read_text(TEXT).unwrap());
+        assert_eq!(write_box(&maze), BOX);
+
+        for maze in generated() {
+            assert_same(&read_box(&write_box(&maze)).unwrap(), &maze);
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let metadata = MazeMetadata {
+            seed: Some(11),
+            algorithm: Some(GeneratorAlgorithm::Prim),
+            perfect: false,
+        };
+        for maze in generated() {
+            let (loaded, loaded_metadata) = read_json(&write_json(&maze, &metadata)).unwrap();
+            assert_same(&loaded, &maze);
+            assert_eq!(loaded_metadata, metadata);
+        }
+
+        let json = write_json(&read_text(TEXT).unwrap(), &metadata);
+        assert!(json.contains("\"seed\": 11"));
+        assert!(json.contains("\"algorithm\": \"Prim\""));
+        // The markers are only stored as fields
+        assert!(json.contains("\"#   #\""));
+        assert!(!json.contains("perfect"));
+    }
+
+    #[test]
+    fn test_json_perfect_mazes() {
+        let perfect = MazeMetadata { perfect: true, ..MazeMetadata::default() };
+        let maze = Maze::generate(GeneratorAlgorithm::Kruskal, 9, 6, 4);
+        let json = write_json(&maze, &perfect);
+        assert!(json.contains("\"perfect\": true"));
+        assert_eq!(read_json(&json).unwrap().1, perfect);
+
+        let mut braided = Maze::generate(GeneratorAlgorithm::Kruskal, 9, 6, 4);
+        braided.braid(Braiding::Full, 4);
+        assert!(read_json(&write_json(&braided, &MazeMetadata::default())).is_ok());
+        assert!(matches!(
+            read_json(&write_json(&braided, &perfect)),
+            Err(MazeError::Invalid(issues))
+                if matches!(issues[..], [MazeIssue::Loops { count, .. }] if count > 0)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_mazes_are_rejected() {
+        let issues = |result: Result<Maze, MazeError>| match result {
+            Err(MazeError::Invalid(issues)) => issues,
+            other => panic!("expected an invalid maze, got {:?}", other.map(|maze| maze.map)),
+        };
+
+        assert_eq!(
+            issues(read_text("#####\n#2#3#\n#####\n")),
+            vec![
+                MazeIssue::UnreachableExit,
+                MazeIssue::UnreachableRegion { first: Cell { row: 1, column: 3 }, size: 1 },
+            ]
+        );
+        assert_eq!(
+            issues(read_text("#####\n#2 3#\n## ##\n")),
+            vec![MazeIssue::OpenBoundary(Cell { row: 2, column: 2 })]
+        );
+        assert_eq!(
+            issues(read_text("#####\n#2 3#\n#3###\n#####\n")),
+            vec![MazeIssue::MultipleExits(vec![
+                Cell { row: 1, column: 3 },
+                Cell { row: 2, column: 1 }
+            ]),]
+        );
+        // The marker sits between two cell rows, the exit opens onto both of them
+        assert_eq!(
+            issues(read_box("┌2┬─┐\n│ │3│\n└─┴─┘\n")),
+            vec![
+                MazeIssue::UnreachableExit,
+                MazeIssue::UnreachableRegion { first: Cell { row: 1, column: 3 }, size: 3 },
+            ]
+        );
+
+        let json = serde_json::json!({
+            "entry": { "row": 1, "column": 1 },
+            "exit": { "row": 1, "column": 2 },
+            "rows": ["####", "#   ", "####"],
+        });
+        let err = read_json(&json.to_string()).err().expect("an open boundary");
+        assert_eq!(
+            err,
+            MazeError::Invalid(vec![MazeIssue::OpenBoundary(Cell { row: 1, column: 3 })])
+        );
+        assert_eq!(err.to_string(), "Invalid maze: open boundary at row 1, column 3");
+    }
+
+    #[test]
+    fn test_terrain_round_trip() {
+        let mut maze = Maze::generate(GeneratorAlgorithm::Backtracker, 9, 6, 11);
+        maze.add_terrain(0.5, 11);
+        let text = write_text(&maze);
+        assert!(text.contains(MUD) && text.contains(WATER));
+
+        assert_same(&read_text(&text).unwrap(), &maze);
+        let (loaded, _) = read_json(&write_json(&maze, &MazeMetadata::default())).unwrap();
+        assert_same(&loaded, &maze);
+
+        // Box-drawing glyphs only keep the walls
+        let walls = read_box(&write_box(&maze)).unwrap();
+        assert!(walls.map.iter().flatten().all(|&position| position <= PositionType::EXIT));
+    }
+
+    #[test]
+    fn test_json_items() {
+        let mut maze = Maze::generate(GeneratorAlgorithm::Prim, 12, 8, 3);
+        let placement =
+            ItemPlacement { help: 0.1, monsters: 0.1, objectives: 1, ..Default::default() };
+        maze.place_items(&placement, 3);
+        assert!(!maze.items.is_empty());
+
+        let json = write_json(&maze, &MazeMetadata::default());
+        assert!(json.contains("\"kind\": \"Monster\""));
+        let (loaded, _) = read_json(&json).unwrap();
+        assert_eq!(loaded.items, maze.items);
+
+        // Mazes without items keep the file as it was
+        let json = write_json(&read_text(TEXT).unwrap(), &MazeMetadata::default());
+        assert!(!json.contains("items"));
+
+        let json = serde_json::json!({
+            "entry": { "row": 1, "column": 1 },
+            "exit": { "row": 1, "column": 2 },
+            "items": [{ "kind": "Help", "cell": { "row": 7, "column": 1 } }],
+            "rows": ["####", "#  #", "####"],
+        });
+        assert!(matches!(
+            read_json(&json.to_string()),
+            Err(MazeError::OutOfBounds { row: 7, column: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_json_without_metadata() {
+        let json = serde_json::json!({
+            "entry": { "row": 1, "column": 1 },
+            "exit": { "row": 1, "column": 2 },
+            "rows": ["####", "#  #", "####"],
+        });
+        let (maze, metadata) = read_json(&json.to_string()).unwrap();
+        assert_eq!(metadata, MazeMetadata::default());
+        assert_eq!(maze.map[1], vec![1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_format_dispatch() {
+        let maze = read_text(TEXT).unwrap();
+        for format in MazeFormat::ALL {
+            let text = format.write(&maze, &MazeMetadata::default());
+            assert_same(&format.read(&text).unwrap().0, &maze);
+            assert_eq!(format.to_string().to_uppercase().parse::<MazeFormat>(), Ok(format));
+        }
+        assert!("svg".parse::<MazeFormat>().is_err());
+
+        assert_eq!(MazeFormat::detect(BOX), MazeFormat::Box);
+        assert_eq!(MazeFormat::detect(TEXT), MazeFormat::Text);
+        assert_eq!(
+            MazeFormat::detect(&write_json(&maze, &MazeMetadata::default())),
+            MazeFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_bench_inputs_are_solvable() {
+        let inputs = [
+            include_str!("../../../benchmarks/inputs/maze1.txt"),
+            include_str!("../../../benchmarks/inputs/maze2.txt"),
+            include_str!("../../../benchmarks/inputs/maze4.txt"),
+            include_str!("../../../benchmarks/inputs/maze5.txt"),
+        ];
+        for input in inputs {
+            let maze = read_box(input).unwrap();
+            assert_eq!(maze.row_len, 97);
+            let distances = distances_from(&maze, maze.entry);
+            assert!(distances[maze.exit.row as usize][maze.exit.column as usize] > 0);
+            assert_eq!(read_box(&write_box(&maze)).unwrap().map, maze.map);
+        }
+
+        assert_eq!(
+            read_box(include_str!("../../../benchmarks/inputs/maze3.txt")).err(),
+            Some(MazeError::MissingMarker('2'))
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(read_text("\n\n").err(), Some(MazeError::Empty));
+        assert_eq!(
+            read_text("###\n#2 3#\n").err(),
+            Some(MazeError::RaggedRow { row: 1, expected: 3, found: 5 })
+        );
+        assert_eq!(
+            read_text("#x#\n#23\n").err(),
+            Some(MazeError::UnknownCharacter { row: 0, column: 1, character: 'x' })
+        );
+        assert_eq!(read_text("###\n#2#\n").err(), Some(MazeError::MissingMarker('3')));
+        assert_eq!(
+            read_box("┌#┐\n│23\n").err(),
+            Some(MazeError::UnknownCharacter { row: 0, column: 1, character: '#' })
+        );
+        assert!(matches!(read_json("{"), Err(MazeError::InvalidJson(_))));
+        assert_eq!(
+            read_json(r#"{"entry": {"row": 0, "column": 0}, "exit": {"row": 5, "column": 0}, "rows": ["  "]}"#)
+                .err(),
+            Some(MazeError::OutOfBounds { row: 5, column: 0 })
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::maze::{Braiding, Cell, GeneratorAlgorithm, ItemPlacement, Maze, Placement};
+use crate::maze_generator::link_levels;
+use crate::messages::Direction;
+use serde::{Deserialize, Serialize};
+
+/// A map position on one of the levels of a [`LevelMaze`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LevelCell {
+    pub level: usize,
+    pub cell: Cell,
+}
+
+/// Mazes stacked on top of each other, walked from the entry of the first level to the
+/// exit of the last one.
+///
+/// The exit of every level but the last is a flight of stairs leading up to the entry of
+/// the next level, at the same map position, see [`link_levels`]. Stairs can be taken
+/// both ways.
+pub struct LevelMaze {
+    /// The levels from the bottom up, there is always at least one.
+    pub levels: Vec<Maze>,
+}
+
+impl LevelMaze {
+    /// Stacks the mazes in this order and links them with stairs.
+    ///
+    /// # Panics
+    ///
+    /// If `levels` is empty.
+    pub fn new(mut levels: Vec<Maze>) -> Self {
+        assert!(!levels.is_empty(), "a maze has at least one level");
+        link_levels(&mut levels);
+        Self { levels }
+    }
+
+    /// Generates `count` levels, at least one, each from its own seed: `seed` plus the
+    /// level. The first level is the same maze as the one [`Maze::generate`] gives for
+    /// `seed`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shared::maze::{Braiding, GeneratorAlgorithm, Placement};
+    /// use shared::maze::levels::LevelMaze;
+    ///
+    /// let maze = LevelMaze::generate(
+    ///     GeneratorAlgorithm::Prim, 10, 10, 3, Braiding::Perfect, Placement::Edges, 42,
+    /// );
+    /// assert_eq!(maze.levels.len(), 3);
+    /// assert_eq!(maze.exit().level, 2);
+    /// ```
+    pub fn generate(
+        algorithm: GeneratorAlgorithm,
+        width: usize,
+        height: usize,
+        count: usize,
+        braiding: Braiding,
+        placement: Placement,
+        seed: u64,
+    ) -> Self {
+        let levels = (0..count.max(1) as u64)
+            .map(|level| {
+                let seed = seed.wrapping_add(level);
+                let mut maze = Maze::generate(algorithm, width, height, seed);
+                maze.braid(braiding, seed);
+                maze.place_entry_exit(placement, seed);
+                maze
+            })
+            .collect();
+        Self::new(levels)
+    }
+
+    /// Where the players start: the entry of the first level.
+    pub fn entry(&self) -> LevelCell {
+        LevelCell { level: 0, cell: self.levels[0].entry }
+    }
+
+    /// Where the players are heading: the exit of the last level.
+    pub fn exit(&self) -> LevelCell {
+        let level = self.levels.len() - 1;
+        LevelCell { level, cell: self.levels[level].exit }
+    }
+
+    /// The level `position` is on, `None` if there is no such level.
+    pub fn level(&self, position: LevelCell) -> Option<&Maze> {
+        self.levels.get(position.level)
+    }
+
+    /// Where taking the stairs at `position` leads, `None` if there are no stairs there.
+    pub fn stairs_at(&self, position: LevelCell) -> Option<LevelCell> {
+        let LevelCell { level, cell } = position;
+        let maze = self.level(position)?;
+        if level + 1 < self.levels.len() && cell == maze.exit {
+            Some(LevelCell { level: level + 1, cell })
+        } else if level > 0 && cell == maze.entry {
+            Some(LevelCell { level: level - 1, cell })
+        } else {
+            None
+        }
+    }
+
+    /// Whether there are stairs at `position`.
+    pub fn is_stairs(&self, position: LevelCell) -> bool {
+        self.stairs_at(position).is_some()
+    }
+
+    /// Cost of stepping onto `position`, `None` for walls and positions outside of the
+    /// maze. Taking the stairs costs as much as stepping onto the position they lead to.
+    pub fn cost(&self, position: LevelCell) -> Option<u32> {
+        self.level(position)?.cost(position.cell)
+    }
+
+    /// Same as [`Maze::step`] on the level of `position`. A player stepping onto stairs
+    /// takes them and ends up on the other level, still looking the same way.
+    pub fn step(
+        &self,
+        position: LevelCell,
+        facing: Cell,
+        direction: &Direction,
+    ) -> Option<(LevelCell, Cell)> {
+        let (cell, facing) = self.level(position)?.step(position.cell, facing, direction)?;
+        let arrived = LevelCell { level: position.level, cell };
+        Some((self.stairs_at(arrived).unwrap_or(arrived), facing))
+    }
+
+    /// Covers a `ratio` of the passages of every level with mud and water, see
+    /// [`Maze::add_terrain`].
+    pub fn add_terrain(&mut self, ratio: f64, seed: u64) {
+        for (level, maze) in self.levels.iter_mut().enumerate() {
+            maze.add_terrain(ratio, seed.wrapping_add(level as u64));
+        }
+    }
+
+    /// Replaces the items of every level, see [`Maze::place_items`]. The stairs are kept
+    /// free like the entry and the exit of a single maze.
+    pub fn place_items(&mut self, placement: &ItemPlacement, seed: u64) {
+        for (level, maze) in self.levels.iter_mut().enumerate() {
+            maze.place_items(placement, seed.wrapping_add(level as u64));
+        }
+    }
+
+    /// Shifts `count` walls on every level, see [`Maze::shift_walls`]. Stairs are never
+    /// moved. Returns the map positions that changed.
+    pub fn shift_walls(&mut self, count: usize, seed: u64) -> Vec<LevelCell> {
+        self.levels
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(level, maze)| {
+                maze.shift_walls(count, seed.wrapping_add(level as u64))
+                    .into_iter()
+                    .map(move |cell| LevelCell { level, cell })
+            })
+            .collect()
+    }
+}
+
+impl From<Maze> for LevelMaze {
+    /// A maze with a single level.
+    fn from(maze: Maze) -> Self {
+        Self { levels: vec![maze] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::analysis::distances_from;
+
+    fn generate(count: usize, placement: Placement, seed: u64) -> LevelMaze {
+        LevelMaze::generate(
+            GeneratorAlgorithm::Backtracker,
+            6,
+            5,
+            count,
+            Braiding::Perfect,
+            placement,
+            seed,
+        )
+    }
+
+    #[test]
+    fn test_levels_are_linked_by_stairs() {
+        for placement in [Placement::Edges, Placement::Random, Placement::Farthest] {
+            for seed in 0..20 {
+                let maze = generate(3, placement, seed);
+                assert_eq!(maze.levels.len(), 3);
+                assert_eq!(maze.entry(), LevelCell { level: 0, cell: maze.levels[0].entry });
+                assert_eq!(maze.exit(), LevelCell { level: 2, cell: maze.levels[2].exit });
+
+                for level in 1..3 {
+                    let below = &maze.levels[level - 1];
+                    let here = &maze.levels[level];
+                    assert_eq!(here.entry, below.exit);
+                    assert_ne!(here.entry, here.exit);
+                    let distances = distances_from(here, here.entry);
+                    assert!(distances[here.exit.row as usize][here.exit.column as usize] > 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_first_level_is_the_single_level_maze() {
+        let maze = generate(3, Placement::Edges, 7);
+        let mut expected = Maze::generate(GeneratorAlgorithm::Backtracker, 6, 5, 7);
+        expected.place_entry_exit(Placement::Edges, 7);
+
+        assert_eq!(maze.levels[0].map, expected.map);
+        assert_eq!(generate(0, Placement::Edges, 7).levels.len(), 1);
+        assert_eq!(LevelMaze::from(expected).exit().level, 0);
+    }
+
+    #[test]
+    fn test_stairs_at() {
+        let maze = generate(3, Placement::Edges, 1);
+        let up = LevelCell { level: 0, cell: maze.levels[0].exit };
+        let down = LevelCell { level: 1, cell: maze.levels[0].exit };
+
+        assert_eq!(maze.stairs_at(up), Some(down));
+        assert_eq!(maze.stairs_at(down), Some(up));
+        assert!(maze.is_stairs(LevelCell { level: 1, cell: maze.levels[1].exit }));
+        // No stairs on the entry of the first level or the exit of the last one
+        assert!(!maze.is_stairs(maze.entry()));
+        assert!(!maze.is_stairs(maze.exit()));
+        assert!(!maze.is_stairs(LevelCell { level: 5, cell: maze.levels[0].exit }));
+    }
+
+    #[test]
+    fn test_step_takes_the_stairs() {
+        use crate::maze::Directions;
+        use crate::maze::io::read_text;
+
+        let maze = LevelMaze::new(vec![
+            read_text("#####\n#2 3#\n#####\n").expect("valid maze"),
+            read_text("#####\n#3 2#\n#####\n").expect("valid maze"),
+        ]);
+        let entry = maze.entry();
+        let stairs = LevelCell { level: 1, cell: Cell { row: 1, column: 3 } };
+
+        assert_eq!(maze.step(entry, Directions::NORTH, &Direction::Front), None);
+        assert_eq!(
+            maze.step(entry, Directions::NORTH, &Direction::Right),
+            Some((stairs, Directions::EAST))
+        );
+        assert_eq!(
+            maze.step(stairs, Directions::EAST, &Direction::Back),
+            Some((maze.exit(), Directions::WEST))
+        );
+        // Walking back onto the stairs leads down again
+        assert_eq!(
+            maze.step(maze.exit(), Directions::WEST, &Direction::Back),
+            Some((LevelCell { level: 0, cell: stairs.cell }, Directions::EAST))
+        );
+    }
+
+    #[test]
+    fn test_shift_walls_keeps_the_stairs() {
+        let mut maze = generate(2, Placement::Random, 3);
+        let stairs = maze.levels[0].exit;
+        let changed = maze.shift_walls(2, 9);
+
+        assert!(changed.iter().any(|position| position.level == 1));
+        assert!(changed.iter().all(|position| position.cell != stairs));
+        assert_eq!(maze.levels[1].entry, stairs);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::maze::{Cell, ItemKind, Maze, PositionType};
+use crate::utils::heat_green;
+use std::fmt::Write;
+
+const WALL_COLOR: &str = "#222222";
+const ENTRY_COLOR: &str = "#2e9d3a";
+const EXIT_COLOR: &str = "#d62d20";
+const PATH_COLOR: &str = "#1f6feb";
+const MUD_COLOR: &str = "#8b5a2b";
+const WATER_COLOR: &str = "#4a90d9";
+const HELP_COLOR: &str = "#f2c230";
+const MONSTER_COLOR: &str = "#7b2cbf";
+const OBJECTIVE_COLOR: &str = "#ff8c00";
+
+/// What to draw on top of the walls, see [`render_svg`].
+#[derive(Debug, Clone, Copy)]
+pub struct SvgOptions<'a> {
+    /// Side of a map position, in pixels.
+    pub scale: u32,
+    /// Positions to join with a line, usually the path returned by a solver.
+    pub path: Option<&'a [Cell]>,
+    /// Step at which each position was visited, -1 if it never was, in the layout of the
+    /// `visited_points` of the solvers. Drawn from yellow for the first steps to red.
+    pub heatmap: Option<&'a [Vec<i32>]>,
+}
+
+impl Default for SvgOptions<'_> {
+    fn default() -> Self {
+        Self { scale: 10, path: None, heatmap: None }
+    }
+}
+
+/// Draws the maze as a standalone SVG document, one square of `options.scale` pixels per
+/// map position, with mud in brown and water in blue. Items are drawn as squares: help in
+/// yellow, monsters in purple and objectives in orange.
+pub fn render_svg(maze: &Maze, options: &SvgOptions) -> String {
+    let scale = options.scale.max(1);
+    let (width, height) = (maze.col_len as u32 * scale, maze.row_len as u32 * scale);
+    let mut svg = String::new();
+
+    // Writing to a `String` never fails
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(svg, r#"<rect width="{width}" height="{height}" fill="white"/>"#);
+
+    for (position, class, color) in
+        [(PositionType::MUD, "mud", MUD_COLOR), (PositionType::WATER, "water", WATER_COLOR)]
+    {
+        if !maze.map.iter().flatten().any(|&other| other == position) {
+            continue;
+        }
+        let _ = writeln!(svg, r#"<g class="{class}" fill="{color}">"#);
+        runs(&mut svg, maze, position, scale);
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if let Some(visited_points) = options.heatmap {
+        let steps = visited_points.iter().flatten().copied().max().unwrap_or(0);
+        let _ = writeln!(svg, r#"<g class="heatmap" fill-opacity="0.6">"#);
+        for (row, points) in visited_points.iter().enumerate() {
+            for (column, &step) in points.iter().enumerate() {
+                if step < 0 {
+                    continue;
+                }
+                let green = heat_green(step, steps);
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{scale}" height="{scale}" fill="rgb(255,{green},0)"/>"#,
+                    column as u32 * scale,
+                    row as u32 * scale,
+                );
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    let _ = writeln!(svg, r#"<g class="walls" fill="{WALL_COLOR}">"#);
+    runs(&mut svg, maze, PositionType::WALL, scale);
+    let _ = writeln!(svg, "</g>");
+
+    if let Some(path) = options.path.filter(|path| !path.is_empty()) {
+        let points: Vec<String> = path
+            .iter()
+            .map(|cell| {
+                let (x, y) = center(*cell, scale);
+                format!("{},{}", x, y)
+            })
+            .collect();
+        let _ = writeln!(
+            svg,
+            r#"<polyline class="path" points="{}" fill="none" stroke="{PATH_COLOR}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round"/>"#,
+            points.join(" "),
+            f64::from(scale) / 3.0,
+        );
+    }
+
+    for item in &maze.items {
+        let (class, color) = match item.kind {
+            ItemKind::Help => ("help", HELP_COLOR),
+            ItemKind::Monster => ("monster", MONSTER_COLOR),
+            ItemKind::Objective => ("objective", OBJECTIVE_COLOR),
+        };
+        let (x, y) = center(item.cell, scale);
+        let half = f64::from(scale) * 0.3;
+        let _ = writeln!(
+            svg,
+            r#"<rect class="{class}" x="{}" y="{}" width="{}" height="{}" fill="{color}"/>"#,
+            x - half,
+            y - half,
+            half * 2.0,
+            half * 2.0,
+        );
+    }
+
+    for (cell, class, color) in
+        [(maze.entry, "entry", ENTRY_COLOR), (maze.exit, "exit", EXIT_COLOR)]
+    {
+        let (x, y) = center(cell, scale);
+        let _ = writeln!(
+            svg,
+            r#"<circle class="{class}" cx="{x}" cy="{y}" r="{}" fill="{color}"/>"#,
+            f64::from(scale) * 0.4,
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draws every `position` of the maze, one rectangle per horizontal run keeps large mazes
+/// small.
+fn runs(svg: &mut String, maze: &Maze, position: u16, scale: u32) {
+    for (row, positions) in maze.map.iter().enumerate() {
+        let mut column = 0;
+        while column < positions.len() {
+            if positions[column] != position {
+                column += 1;
+                continue;
+            }
+            let start = column;
+            while column < positions.len() && positions[column] == position {
+                column += 1;
+            }
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{scale}"/>"#,
+                start as u32 * scale,
+                row as u32 * scale,
+                (column - start) as u32 * scale,
+            );
+        }
+    }
+}
+
+/// Pixel coordinates of the middle of a map position.
+fn center(cell: Cell, scale: u32) -> (f64, f64) {
+    let half = f64::from(scale) / 2.0;
+    (
+        f64::from(cell.column) * f64::from(scale) + half,
+        f64::from(cell.row) * f64::from(scale) + half,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Item;
+    use crate::maze::analysis::distances_from;
+    use crate::maze::io::read_text;
+
+    const TEXT: &str = "#####\n#2  #\n# # #\n# #3#\n#####\n";
+
+    #[test]
+    fn test_walls_only() {
+        let maze = read_text(TEXT).unwrap();
+        let svg = render_svg(&maze, &SvgOptions::default());
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="50" height="50""#)
+        );
+        assert!(svg.ends_with("</svg>\n"));
+        // Full rows are merged into a single rectangle
+        assert!(svg.contains(r#"<rect x="0" y="0" width="50" height="10"/>"#));
+        assert!(svg.contains(r#"<rect x="20" y="20" width="10" height="10"/>"#));
+        assert_eq!(svg.matches("<rect x=").count(), 10);
+        assert!(svg.contains(r#"<circle class="entry" cx="15" cy="15""#));
+        assert!(svg.contains(r#"<circle class="exit" cx="35" cy="35""#));
+        assert!(!svg.contains("heatmap"));
+        assert!(!svg.contains("polyline"));
+        assert!(!svg.contains("mud"));
+    }
+
+    #[test]
+    fn test_items() {
+        let mut maze = read_text(TEXT).unwrap();
+        maze.items = vec![
+            Item { kind: ItemKind::Help, cell: Cell { row: 1, column: 3 } },
+            Item { kind: ItemKind::Monster, cell: Cell { row: 3, column: 1 } },
+        ];
+        let svg = render_svg(&maze, &SvgOptions::default());
+
+        assert!(svg.contains(&format!(
+            r#"<rect class="help" x="32" y="12" width="6" height="6" fill="{HELP_COLOR}"/>"#
+        )));
+        assert!(svg.contains(r#"<rect class="monster" x="12" y="32""#));
+        assert!(!svg.contains("objective"));
+    }
+
+    #[test]
+    fn test_terrain() {
+        let maze = read_text("#####\n#2::#\n# #~#\n# #3#\n#####\n").unwrap();
+        let svg = render_svg(&maze, &SvgOptions::default());
+
+        let mud = &svg[svg.find("class=\"mud\"").unwrap()..svg.find("class=\"water\"").unwrap()];
+        assert!(mud.contains(MUD_COLOR));
+        assert!(mud.contains(r#"<rect x="20" y="10" width="20" height="10"/>"#));
+        let water = &svg[svg.find("class=\"water\"").unwrap()..svg.find("walls").unwrap()];
+        assert!(water.contains(r#"<rect x="30" y="20" width="10" height="10"/>"#));
+    }
+
+    #[test]
+    fn test_path_and_heatmap() {
+        let maze = read_text(TEXT).unwrap();
+        let visited_points = distances_from(&maze, maze.entry);
+        let path = [
+            Cell { row: 1, column: 1 },
+            Cell { row: 1, column: 2 },
+            Cell { row: 1, column: 3 },
+            Cell { row: 2, column: 3 },
+            Cell { row: 3, column: 3 },
+        ];
+        let options = SvgOptions { scale: 4, path: Some(&path), heatmap: Some(&visited_points) };
+        let svg = render_svg(&maze, &options);
+
+        assert!(svg.contains(r#"points="6,6 10,6 14,6 14,10 14,14""#));
+        let heatmap = &svg[svg.find("heatmap").unwrap()..svg.find("walls").unwrap()];
+        // The entry is the first step, the exit the farthest one
+        assert!(
+            heatmap.contains(r#"<rect x="4" y="4" width="4" height="4" fill="rgb(255,255,0)"/>"#)
+        );
+        assert!(
+            heatmap.contains(r#"<rect x="12" y="12" width="4" height="4" fill="rgb(255,0,0)"/>"#)
+        );
+        assert_eq!(heatmap.matches("<rect").count(), 7);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::maze::{Cell, Directions, Maze, PositionType};
+use std::collections::VecDeque;
+use std::fmt;
+
+const DIRECTIONS: [Cell; 4] =
+    [Directions::NORTH, Directions::EAST, Directions::SOUTH, Directions::WEST];
+
+/// A problem found in a maze by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeIssue {
+    /// A row of the map whose length differs from the first one. No other check is run on
+    /// a ragged map.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A walkable position on the border of the map, other than the entry and the exit,
+    /// through which a player could walk out of the maze.
+    OpenBoundary(Cell),
+    MissingEntry,
+    MissingExit,
+    /// Every entry marker of the map, when there is more than one.
+    MultipleEntries(Vec<Cell>),
+    /// Every exit marker of the map, when there is more than one.
+    MultipleExits(Vec<Cell>),
+    /// No path leads from the entry to the exit.
+    UnreachableExit,
+    /// Walkable positions connected to each other but not to the entry, `first` being the
+    /// first of them in reading order.
+    UnreachableRegion {
+        first: Cell,
+        size: usize,
+    },
+    /// Independent loops in a maze expected to be perfect, `first` being where the first
+    /// of them closes. Only reported by [`validate_perfect`].
+    Loops {
+        count: usize,
+        first: Cell,
+    },
+}
+
+impl fmt::Display for MazeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |cell: &Cell| format!("row {}, column {}", cell.row, cell.column);
+        let list = |cells: &[Cell]| cells.iter().map(at).collect::<Vec<_>>().join("; ");
+        match self {
+            MazeIssue::RaggedRow { row, expected, found } => {
+                write!(f, "row {} has {} columns, expected {}", row, found, expected)
+            }
+            MazeIssue::OpenBoundary(cell) => write!(f, "open boundary at {}", at(cell)),
+            MazeIssue::MissingEntry => write!(f, "no entry"),
+            MazeIssue::MissingExit => write!(f, "no exit"),
+            MazeIssue::MultipleEntries(cells) => {
+                write!(f, "{} entries: {}", cells.len(), list(cells))
+            }
+            MazeIssue::MultipleExits(cells) => write!(f, "{} exits: {}", cells.len(), list(cells)),
+            MazeIssue::UnreachableExit => write!(f, "the exit cannot be reached from the entry"),
+            MazeIssue::UnreachableRegion { first, size } => {
+                write!(f, "{} positions unreachable from the entry, from {}", size, at(first))
+            }
+            MazeIssue::Loops { count, first } => {
+                write!(f, "{} loops in a perfect maze, the first closing at {}", count, at(first))
+            }
+        }
+    }
+}
+
+/// Checks that a maze is well formed: a rectangular map closed by its outer wall, with a
+/// single entry and a single exit, and every walkable position reachable from the entry.
+///
+/// The entry and the exit are the markers found in the map, not the `entry` and `exit`
+/// fields. Returns every issue found, none for a valid maze.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::io::read_text;
+/// use shared::maze::validation::{MazeIssue, validate};
+///
+/// let maze = read_text("#####\n#2 3#\n#####\n").unwrap();
+/// assert!(validate(&maze).is_empty());
+/// ```
+pub fn validate(maze: &Maze) -> Vec<MazeIssue> {
+    let expected = maze.map.first().map_or(0, Vec::len);
+    let mut issues: Vec<MazeIssue> = maze
+        .map
+        .iter()
+        .enumerate()
+        .filter(|(_, positions)| positions.len() != expected)
+        .map(|(row, positions)| MazeIssue::RaggedRow { row, expected, found: positions.len() })
+        .collect();
+    if !issues.is_empty() {
+        return issues;
+    }
+
+    let (height, width) = (maze.map.len(), expected);
+    let cells = (0..height).flat_map(|row| {
+        (0..width).map(move |column| Cell { row: row as i16, column: column as i16 })
+    });
+    let markers = |marker: u16| -> Vec<Cell> {
+        cells.clone().filter(|&cell| position(maze, cell) == Some(marker)).collect()
+    };
+    let (entries, exits) = (markers(PositionType::ENTRY), markers(PositionType::EXIT));
+
+    issues.extend(
+        cells
+            .clone()
+            .filter(|&cell| {
+                let on_border = cell.row == 0
+                    || cell.column == 0
+                    || cell.row as usize + 1 == height
+                    || cell.column as usize + 1 == width;
+                on_border
+                    && is_walkable(maze, cell)
+                    && !entries.contains(&cell)
+                    && !exits.contains(&cell)
+            })
+            .map(MazeIssue::OpenBoundary),
+    );
+
+    match entries.len() {
+        0 => issues.push(MazeIssue::MissingEntry),
+        1 => {}
+        _ => issues.push(MazeIssue::MultipleEntries(entries.clone())),
+    }
+    match exits.len() {
+        0 => issues.push(MazeIssue::MissingExit),
+        1 => {}
+        _ => issues.push(MazeIssue::MultipleExits(exits.clone())),
+    }
+
+    let Some(&entry) = entries.first() else {
+        return issues;
+    };
+    let mut region = vec![vec![usize::MAX; width]; height];
+    flood(maze, entry, 0, &mut region);
+    if exits.iter().any(|exit| region[exit.row as usize][exit.column as usize] != 0) {
+        issues.push(MazeIssue::UnreachableExit);
+    }
+    let mut regions = 0;
+    for cell in cells {
+        if is_walkable(maze, cell) && region[cell.row as usize][cell.column as usize] == usize::MAX
+        {
+            regions += 1;
+            let size = flood(maze, cell, regions, &mut region);
+            issues.push(MazeIssue::UnreachableRegion { first: cell, size });
+        }
+    }
+    issues
+}
+
+/// Same as [`validate`] for a maze expected to be perfect, with exactly one path between
+/// two positions, so loops are reported too.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::io::read_text;
+/// use shared::maze::validation::{MazeIssue, validate_perfect};
+/// use shared::maze::Cell;
+///
+/// let maze = read_text("#####\n#2  #\n# # #\n#  3#\n#####\n").unwrap();
+/// let first = Cell { row: 3, column: 3 };
+/// assert_eq!(validate_perfect(&maze), vec![MazeIssue::Loops { count: 1, first }]);
+/// ```
+pub fn validate_perfect(maze: &Maze) -> Vec<MazeIssue> {
+    let mut issues = validate(maze);
+    if issues.iter().any(|issue| matches!(issue, MazeIssue::RaggedRow { .. })) {
+        return issues;
+    }
+
+    // A connected graph is a tree when it has one edge less than nodes, each extra edge
+    // closes an independent loop
+    let (height, width) = (maze.row_len, maze.col_len);
+    let mut parent = vec![vec![None; width]; height];
+    let mut loops = 0;
+    let mut first = None;
+    for row in 0..height {
+        for column in 0..width {
+            let start = Cell { row: row as i16, column: column as i16 };
+            if parent[row][column].is_some() || !is_walkable(maze, start) {
+                continue;
+            }
+            parent[row][column] = Some(start);
+            let mut queue = VecDeque::from([start]);
+            while let Some(cell) = queue.pop_front() {
+                let came_from = parent[cell.row as usize][cell.column as usize];
+                for next in neighbors(maze, cell) {
+                    let slot = &mut parent[next.row as usize][next.column as usize];
+                    if slot.is_none() {
+                        *slot = Some(cell);
+                        queue.push_back(next);
+                    } else if came_from != Some(next)
+                        && (next.row, next.column) > (cell.row, cell.column)
+                    {
+                        // Each extra edge is seen from both ends, it is counted from the one
+                        // coming first in reading order
+                        loops += 1;
+                        first.get_or_insert(next);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(first) = first {
+        issues.push(MazeIssue::Loops { count: loops, first });
+    }
+    issues
+}
+
+/// Marks every position connected to `start` with `region`, returns how many there are.
+fn flood(maze: &Maze, start: Cell, region: usize, regions: &mut [Vec<usize>]) -> usize {
+    regions[start.row as usize][start.column as usize] = region;
+    let mut queue = VecDeque::from([start]);
+    let mut size = 0;
+    while let Some(cell) = queue.pop_front() {
+        size += 1;
+        for next in neighbors(maze, cell) {
+            let slot = &mut regions[next.row as usize][next.column as usize];
+            if *slot == usize::MAX {
+                *slot = region;
+                queue.push_back(next);
+            }
+        }
+    }
+    size
+}
+
+fn neighbors(maze: &Maze, cell: Cell) -> impl Iterator<Item = Cell> + '_ {
+    DIRECTIONS
+        .into_iter()
+        .map(move |direction| cell + direction)
+        .filter(|&next| is_walkable(maze, next))
+}
+
+fn is_walkable(maze: &Maze, cell: Cell) -> bool {
+    position(maze, cell).is_some_and(|position| position != PositionType::WALL)
+}
+
+fn position(maze: &Maze, cell: Cell) -> Option<u16> {
+    let row = usize::try_from(cell.row).ok()?;
+    let column = usize::try_from(cell.column).ok()?;
+    maze.map.get(row)?.get(column).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{Braiding, GeneratorAlgorithm, Placement};
+
+    /// Builds a maze without going through the readers, which reject invalid mazes.
+    fn raw_maze(text: &str) -> Maze {
+        let map = text
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|glyph| match glyph {
+                        '#' => PositionType::WALL,
+                        '2' => PositionType::ENTRY,
+                        '3' => PositionType::EXIT,
+                        _ => PositionType::SPACE,
+                    })
+                    .collect()
+            })
+            .collect();
+        let origin = Cell { row: 0, column: 0 };
+        Maze::new(map, origin, origin)
+    }
+
+    #[test]
+    fn test_generated_mazes_are_perfect() {
+        for algorithm in GeneratorAlgorithm::ALL {
+            for seed in 0..5 {
+                let mut maze = Maze::generate(algorithm, 8, 6, seed);
+                maze.place_entry_exit(Placement::Random, seed);
+                assert_eq!(validate_perfect(&maze), vec![], "{} with seed {}", algorithm, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_braided_mazes_have_loops() {
+        let mut maze = Maze::generate(GeneratorAlgorithm::Backtracker, 8, 6, 3);
+        maze.braid(Braiding::Full, 3);
+        maze.place_entry_exit(Placement::Edges, 3);
+
+        assert!(validate(&maze).is_empty());
+        assert!(
+            matches!(validate_perfect(&maze)[..], [MazeIssue::Loops { count, .. }] if count > 0)
+        );
+    }
+
+    #[test]
+    fn test_markers_and_boundaries() {
+        let maze = raw_maze("## ##\n#2 2#\n#   #\n#####\n");
+        assert_eq!(
+            validate(&maze),
+            vec![
+                MazeIssue::OpenBoundary(Cell { row: 0, column: 2 }),
+                MazeIssue::MultipleEntries(vec![
+                    Cell { row: 1, column: 1 },
+                    Cell { row: 1, column: 3 }
+                ]),
+                MazeIssue::MissingExit,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let maze = raw_maze("###\n##\n");
+        assert_eq!(
+            validate_perfect(&maze),
+            vec![MazeIssue::RaggedRow { row: 1, expected: 3, found: 2 }]
+        );
+    }
+}
@@ -1,5 +1,301 @@
-use crate::maze::{Cell, Maze, PositionType};
-use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedMutRandom};
+use crate::maze::analysis::distances_from;
+use crate::maze::grid::{Direction, MazeGrid, to_map};
+use crate::maze::{
+    Cell, Directions, GeneratorAlgorithm, Item, ItemKind, ItemPlacement, Maze, Placement,
+    PositionType,
+};
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::collections::{HashMap, VecDeque};
+
+/// Coordinates of a cell as (row, column) in cell units, not in map units.
+type GridCell = (usize, usize);
+
+/// A step of a maze generation, see [`generation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenerationEvent {
+    /// An empty grid of `width` x `height` cells with every wall up, always the first event.
+    Started { width: usize, height: usize },
+    /// A cell became part of the maze, in map coordinates.
+    CellCarved(Cell),
+    /// The wall at this map position was removed, joining two carved cells.
+    WallRemoved(Cell),
+    /// The row-based algorithms, Sidewinder and Eller, are done with this row of cells.
+    RowClosed(usize),
+    /// The entry and the exit were placed, always the last event.
+    Finished { entry: Cell, exit: Cell },
+}
+
+/// The events of a maze generation, in the order the algorithm produced them.
+///
+/// Collecting the events rebuilds the generated maze, and a partially built maze can be
+/// updated one event at a time with [`Extend`] to animate the generation.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::{GeneratorAlgorithm, Maze};
+/// use shared::maze_generator::generation;
+///
+/// let maze: Maze = generation(GeneratorAlgorithm::Wilson, 10, 10, 42).collect();
+/// assert_eq!(maze.map, Maze::generate(GeneratorAlgorithm::Wilson, 10, 10, 42).map);
+/// ```
+pub struct Generation {
+    grid: Grid<Maze>,
+    rng: StdRng,
+    /// The carving in progress, `None` once the maze is complete.
+    carver: Option<Carver>,
+    seed: u64,
+    finished: bool,
+}
+
+impl Iterator for Generation {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.grid.events.as_mut().and_then(VecDeque::pop_front) {
+                return Some(event);
+            }
+            match &mut self.carver {
+                Some(carver) => {
+                    if !carver.step(&mut self.grid, &mut self.rng) {
+                        self.carver = None;
+                    }
+                }
+                None if !self.finished => {
+                    self.finished = true;
+                    place_entry_exit(&mut self.grid.target, Placement::Edges, self.seed);
+                    let (entry, exit) = (self.grid.target.entry, self.grid.target.exit);
+                    self.grid.record(|| GenerationEvent::Finished { entry, exit });
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+/// Generates a maze like [`Maze::generate`] and returns every step that built it.
+///
+/// Each call to `next` only carves as far as the next event, so the events never pile
+/// up and dropping the iterator stops the generation. Consuming the iterator at any pace
+/// does not change the result.
+pub fn generation(
+    algorithm: GeneratorAlgorithm,
+    width: usize,
+    height: usize,
+    seed: u64,
+) -> Generation {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut grid = Grid::new(width, height, Some(VecDeque::new()));
+    let carver = (width > 0 && height > 0).then(|| Carver::new(algorithm, &mut grid, &mut rng));
+    Generation { grid, rng, carver, seed, finished: false }
+}
+
+impl Extend<GenerationEvent> for Maze {
+    fn extend<I: IntoIterator<Item = GenerationEvent>>(&mut self, events: I) {
+        for event in events {
+            match event {
+                GenerationEvent::Started { width, height } => {
+                    *self = Maze::with_walls(width, height);
+                }
+                GenerationEvent::CellCarved(cell) | GenerationEvent::WallRemoved(cell) => {
+                    self.set(cell, PositionType::SPACE);
+                }
+                GenerationEvent::RowClosed(_) => {}
+                GenerationEvent::Finished { entry, exit } => {
+                    (self.entry, self.exit) = (entry, exit);
+                    self.set(entry, PositionType::ENTRY);
+                    self.set(exit, PositionType::EXIT);
+                }
+            }
+        }
+    }
+}
+
+impl FromIterator<GenerationEvent> for Maze {
+    fn from_iter<I: IntoIterator<Item = GenerationEvent>>(events: I) -> Self {
+        let mut maze =
+            Maze::new(Vec::new(), Cell { row: 0, column: 0 }, Cell { row: 0, column: 0 });
+        maze.extend(events);
+        maze
+    }
+}
+
+impl Maze {
+    fn set(&mut self, cell: Cell, position: u16) {
+        let slot =
+            self.map.get_mut(cell.row as usize).and_then(|row| row.get_mut(cell.column as usize));
+        if let Some(slot) = slot {
+            *slot = position;
+        }
+    }
+}
+
+/// A maze of `width` x `height` cells being carved into `target`.
+struct Grid<G> {
+    target: G,
+    width: usize,
+    height: usize,
+    /// The steps of the generation not consumed yet, only when they are recorded.
+    events: Option<VecDeque<GenerationEvent>>,
+}
+
+impl<G: MazeGrid> Grid<G> {
+    fn new(width: usize, height: usize, events: Option<VecDeque<GenerationEvent>>) -> Self {
+        let mut grid = Self { target: G::with_walls(width, height), width, height, events };
+        grid.record(|| GenerationEvent::Started { width, height });
+        grid
+    }
+
+    /// Queues `event` for the [`Generation`] iterator, if the events are recorded. Only
+    /// then is the event built, storages larger than a `Maze` have no map coordinates.
+    fn record(&mut self, event: impl FnOnce() -> GenerationEvent) {
+        if let Some(events) = &mut self.events {
+            events.push_back(event());
+        }
+    }
+
+    fn cells(&self) -> impl Iterator<Item = GridCell> + use<G> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+
+    fn open(&mut self, cell: GridCell) {
+        if !self.target.is_cell_open(cell) {
+            self.target.open_cell(cell);
+            self.record(|| GenerationEvent::CellCarved(to_map(cell)));
+        }
+    }
+
+    /// Opens both cells and the wall between them. The cells must be adjacent.
+    fn connect(&mut self, a: GridCell, b: GridCell) {
+        self.open(a);
+        self.open(b);
+        if let Some(direction) = Direction::between(a, b) {
+            self.target.carve(a, direction);
+        }
+        self.record(|| {
+            let (a, b) = (to_map(a), to_map(b));
+            GenerationEvent::WallRemoved(Cell {
+                row: a.row + (b.row - a.row) / 2,
+                column: a.column + (b.column - a.column) / 2,
+            })
+        });
+    }
+
+    fn close_row(&mut self, row: usize) {
+        self.record(|| GenerationEvent::RowClosed(row));
+    }
+
+    fn neighbors(&self, cell: GridCell) -> Vec<GridCell> {
+        neighbors(self.width, self.height, cell)
+    }
+
+    fn random_cell(&self, rng: &mut StdRng) -> GridCell {
+        (rng.random_range(0..self.height), rng.random_range(0..self.width))
+    }
+}
+
+fn neighbors(width: usize, height: usize, (row, column): GridCell) -> Vec<GridCell> {
+    let mut neighbors = Vec::with_capacity(4);
+    if row > 0 {
+        neighbors.push((row - 1, column));
+    }
+    if column + 1 < width {
+        neighbors.push((row, column + 1));
+    }
+    if row + 1 < height {
+        neighbors.push((row + 1, column));
+    }
+    if column > 0 {
+        neighbors.push((row, column - 1));
+    }
+    neighbors
+}
+
+/// An algorithm carving the passages of a perfect maze into an all-wall grid, one step
+/// at a time so that the carving can stop between any two events.
+enum Carver {
+    Sidewinder(SidewinderCarver),
+    Backtracker(BacktrackerCarver),
+    Prim(PrimCarver),
+    Kruskal(KruskalCarver),
+    Wilson(WilsonCarver),
+    Eller(EllerCarver),
+}
+
+impl Carver {
+    /// Starts carving `grid`, which must have at least one cell.
+    fn new<G: MazeGrid>(
+        algorithm: GeneratorAlgorithm,
+        grid: &mut Grid<G>,
+        rng: &mut StdRng,
+    ) -> Self {
+        match algorithm {
+            GeneratorAlgorithm::Sidewinder => Carver::Sidewinder(SidewinderCarver::new()),
+            GeneratorAlgorithm::Backtracker => {
+                Carver::Backtracker(BacktrackerCarver::new(grid, rng))
+            }
+            GeneratorAlgorithm::Prim => Carver::Prim(PrimCarver::new(grid, rng)),
+            GeneratorAlgorithm::Kruskal => Carver::Kruskal(KruskalCarver::new(grid, rng)),
+            GeneratorAlgorithm::Wilson => Carver::Wilson(WilsonCarver::new(grid, rng)),
+            GeneratorAlgorithm::Eller => Carver::Eller(EllerCarver::new(grid)),
+        }
+    }
+
+    /// Carves a little more of the grid, returns `false` once the maze is complete.
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, rng: &mut StdRng) -> bool {
+        match self {
+            Carver::Sidewinder(carver) => carver.step(grid, rng),
+            Carver::Backtracker(carver) => carver.step(grid, rng),
+            Carver::Prim(carver) => carver.step(grid, rng),
+            Carver::Kruskal(carver) => carver.step(grid, rng),
+            Carver::Wilson(carver) => carver.step(grid, rng),
+            Carver::Eller(carver) => carver.step(grid, rng),
+        }
+    }
+}
+
+fn carve_grid<G: MazeGrid>(
+    algorithm: GeneratorAlgorithm,
+    width: usize,
+    height: usize,
+    seed: u64,
+) -> G {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut grid = Grid::new(width, height, None);
+    if width > 0 && height > 0 {
+        let mut carver = Carver::new(algorithm, &mut grid, &mut rng);
+        while carver.step(&mut grid, &mut rng) {}
+    }
+    grid.target
+}
+
+/// Generates a maze into any [`MazeGrid`] storage, with the entry and the exit on the
+/// first and last columns.
+///
+/// For the same arguments every storage holds the same maze. Sidewinder and Eller only
+/// keep a single row of state and scale to mazes of any size, the other algorithms use
+/// memory proportional to the number of cells.
+pub fn generate_grid<G: MazeGrid>(
+    algorithm: GeneratorAlgorithm,
+    width: usize,
+    height: usize,
+    seed: u64,
+) -> G {
+    let mut target = carve_grid::<G>(algorithm, width, height, seed);
+    if width > 0 && height > 0 {
+        let (entry, exit) = edges(width, height, &mut StdRng::seed_from_u64(seed));
+        target.set_entry_exit(entry, exit);
+    }
+    target
+}
+
+/// Entry in the first column and exit in the last one, on random rows.
+fn edges(width: usize, height: usize, rng: &mut StdRng) -> (GridCell, GridCell) {
+    ((rng.random_range(0..height), 0), (rng.random_range(0..height), width - 1))
+}
 
 /// Generates a maze using the Sidewinder algorithm.
 ///
@@ -7,7 +303,6 @@ use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedMutRandom};
 ///
 /// * `width` - The width of the maze.
 /// * `height` - The height of the maze.
-/// * `print` - A boolean indicating whether to print the maze generation process.
 /// * `seed` - A seed for the random number generator.
 ///
 /// # Returns
@@ -19,85 +314,1228 @@ use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedMutRandom};
 /// ```
 /// use shared::maze_generator::sidewinder;
 ///
-/// let maze = sidewinder(10, 10, false, 42);
+/// let maze = sidewinder(10, 10, 42);
+/// ```
+pub fn sidewinder(width: usize, height: usize, seed: u64) -> Maze {
+    generate_grid(GeneratorAlgorithm::Sidewinder, width, height, seed)
+}
+
+/// Sidewinder state: the current cell and the run of cells carved east since the last
+/// passage north.
+struct SidewinderCarver {
+    row: usize,
+    column: usize,
+    run: Vec<GridCell>,
+}
+
+impl SidewinderCarver {
+    fn new() -> Self {
+        Self { row: 0, column: 0, run: Vec::new() }
+    }
+
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, rng: &mut StdRng) -> bool {
+        let (row, column) = (self.row, self.column);
+        if row >= grid.height {
+            return false;
+        }
+
+        grid.open((row, column));
+        self.run.push((row, column));
+
+        if row > 0 && (rng.random_bool(0.5) || column == grid.width - 1) {
+            if let Some(&(run_row, run_column)) = self.run.choose(rng) {
+                grid.connect((run_row, run_column), (run_row - 1, run_column));
+            }
+            self.run.clear();
+        } else if column < grid.width - 1 {
+            grid.connect((row, column), (row, column + 1));
+        }
+
+        self.column += 1;
+        if self.column == grid.width {
+            grid.close_row(row);
+            self.run.clear();
+            self.row += 1;
+            self.column = 0;
+        }
+        true
+    }
+}
+
+/// Generates a maze with a randomized depth-first search.
+///
+/// The recursive backtracker produces long winding corridors with few, long dead ends.
+/// Arguments are the same as [`sidewinder`].
+pub fn backtracker(width: usize, height: usize, seed: u64) -> Maze {
+    generate_grid(GeneratorAlgorithm::Backtracker, width, height, seed)
+}
+
+struct BacktrackerCarver {
+    visited: Vec<Vec<bool>>,
+    /// An explicit stack avoids overflowing the call stack on large mazes.
+    stack: Vec<GridCell>,
+}
+
+impl BacktrackerCarver {
+    fn new<G: MazeGrid>(grid: &mut Grid<G>, rng: &mut StdRng) -> Self {
+        let mut visited = vec![vec![false; grid.width]; grid.height];
+        let start = grid.random_cell(rng);
+        visited[start.0][start.1] = true;
+        grid.open(start);
+        Self { visited, stack: vec![start] }
+    }
+
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, rng: &mut StdRng) -> bool {
+        let Some(&current) = self.stack.last() else {
+            return false;
+        };
+        let unvisited: Vec<GridCell> =
+            grid.neighbors(current).into_iter().filter(|&(r, c)| !self.visited[r][c]).collect();
+
+        match unvisited.choose(rng) {
+            Some(&next) => {
+                self.visited[next.0][next.1] = true;
+                grid.connect(current, next);
+                self.stack.push(next);
+            }
+            None => {
+                self.stack.pop();
+            }
+        }
+        true
+    }
+}
+
+/// Generates a maze with randomized Prim's algorithm.
+///
+/// The maze grows from a random cell by attaching random frontier cells, which gives
+/// many short dead ends. Arguments are the same as [`sidewinder`].
+pub fn prim(width: usize, height: usize, seed: u64) -> Maze {
+    generate_grid(GeneratorAlgorithm::Prim, width, height, seed)
+}
+
+struct PrimCarver {
+    in_maze: Vec<Vec<bool>>,
+    in_frontier: Vec<Vec<bool>>,
+    frontier: Vec<GridCell>,
+}
+
+impl PrimCarver {
+    fn new<G: MazeGrid>(grid: &mut Grid<G>, rng: &mut StdRng) -> Self {
+        let mut in_maze = vec![vec![false; grid.width]; grid.height];
+        let mut in_frontier = vec![vec![false; grid.width]; grid.height];
+        let mut frontier = Vec::new();
+
+        let start = grid.random_cell(rng);
+        in_maze[start.0][start.1] = true;
+        grid.open(start);
+        for (r, c) in grid.neighbors(start) {
+            in_frontier[r][c] = true;
+            frontier.push((r, c));
+        }
+        Self { in_maze, in_frontier, frontier }
+    }
+
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, rng: &mut StdRng) -> bool {
+        if self.frontier.is_empty() {
+            return false;
+        }
+        let cell = self.frontier.swap_remove(rng.random_range(0..self.frontier.len()));
+        self.in_maze[cell.0][cell.1] = true;
+
+        let (carved, outside): (Vec<GridCell>, Vec<GridCell>) =
+            grid.neighbors(cell).into_iter().partition(|&(r, c)| self.in_maze[r][c]);
+        if let Some(&from) = carved.choose(rng) {
+            grid.connect(from, cell);
+        }
+
+        for (r, c) in outside {
+            if !self.in_frontier[r][c] {
+                self.in_frontier[r][c] = true;
+                self.frontier.push((r, c));
+            }
+        }
+        true
+    }
+}
+
+/// Generates a maze with randomized Kruskal's algorithm.
+///
+/// Walls are removed in random order whenever they separate two unconnected regions.
+/// Arguments are the same as [`sidewinder`].
+pub fn kruskal(width: usize, height: usize, seed: u64) -> Maze {
+    generate_grid(GeneratorAlgorithm::Kruskal, width, height, seed)
+}
+
+/// Kruskal state: every cell is opened first, then the shuffled walls are tried one by one.
+struct KruskalCarver {
+    edges: std::vec::IntoIter<(GridCell, GridCell)>,
+    sets: DisjointSet,
+    opened: usize,
+}
+
+impl KruskalCarver {
+    fn new<G: MazeGrid>(grid: &mut Grid<G>, rng: &mut StdRng) -> Self {
+        let (width, height) = (grid.width, grid.height);
+        let mut edges: Vec<(GridCell, GridCell)> = grid
+            .cells()
+            .flat_map(|(r, c)| {
+                let east = (c + 1 < width).then_some(((r, c), (r, c + 1)));
+                let south = (r + 1 < height).then_some(((r, c), (r + 1, c)));
+                east.into_iter().chain(south)
+            })
+            .collect();
+        edges.shuffle(rng);
+        Self { edges: edges.into_iter(), sets: DisjointSet::new(width * height), opened: 0 }
+    }
+
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, _rng: &mut StdRng) -> bool {
+        let width = grid.width;
+        if self.opened < width * grid.height {
+            grid.open((self.opened / width, self.opened % width));
+            self.opened += 1;
+            return true;
+        }
+        let Some((a, b)) = self.edges.next() else {
+            return false;
+        };
+        if self.sets.union(a.0 * width + a.1, b.0 * width + b.1) {
+            grid.connect(a, b);
+        }
+        true
+    }
+}
+
+/// Union-find over cell indices, used by Kruskal's algorithm.
+struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        Self { parents: (0..size).collect() }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    /// Merges the sets of `a` and `b`, returns `false` if they were already the same.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return false;
+        }
+        self.parents[root_a] = root_b;
+        true
+    }
+}
+
+/// Generates a maze with Wilson's algorithm.
+///
+/// Loop-erased random walks make every spanning tree equally likely, so the maze has no
+/// directional bias. Arguments are the same as [`sidewinder`].
+pub fn wilson(width: usize, height: usize, seed: u64) -> Maze {
+    generate_grid(GeneratorAlgorithm::Wilson, width, height, seed)
+}
+
+/// Wilson state: the cells left to walk from and the loop-erased walk being carved.
+struct WilsonCarver {
+    in_maze: Vec<Vec<bool>>,
+    remaining: std::vec::IntoIter<GridCell>,
+    /// The last exit taken from each cell of the walk, which erases its loops.
+    exits: HashMap<GridCell, GridCell>,
+    /// The next cell of the walk to carve, until the walk reaches the maze.
+    path: Option<GridCell>,
+}
+
+impl WilsonCarver {
+    fn new<G: MazeGrid>(grid: &mut Grid<G>, rng: &mut StdRng) -> Self {
+        let mut in_maze = vec![vec![false; grid.width]; grid.height];
+        let root = grid.random_cell(rng);
+        in_maze[root.0][root.1] = true;
+        grid.open(root);
+
+        let mut remaining: Vec<GridCell> = grid.cells().filter(|&cell| cell != root).collect();
+        remaining.shuffle(rng);
+        Self { in_maze, remaining: remaining.into_iter(), exits: HashMap::new(), path: None }
+    }
+
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, rng: &mut StdRng) -> bool {
+        if let Some(current) = self.path {
+            if self.in_maze[current.0][current.1] {
+                self.path = None;
+            } else {
+                self.in_maze[current.0][current.1] = true;
+                let next = self.exits[&current];
+                grid.connect(current, next);
+                self.path = Some(next);
+            }
+            return true;
+        }
+
+        let in_maze = &self.in_maze;
+        let Some(start) = self.remaining.find(|&(r, c)| !in_maze[r][c]) else {
+            return false;
+        };
+
+        // Walk randomly until reaching the maze, the walk is carved by the next steps
+        self.exits.clear();
+        let mut current = start;
+        while !self.in_maze[current.0][current.1] {
+            let neighbors = grid.neighbors(current);
+            let next = neighbors[rng.random_range(0..neighbors.len())];
+            self.exits.insert(current, next);
+            current = next;
+        }
+        self.path = Some(start);
+        true
+    }
+}
+
+/// Generates a maze with Eller's algorithm.
+///
+/// The maze is built one row at a time while tracking which cells of the current row
+/// are already connected, so only a single row of state is kept. Arguments are the same
+/// as [`sidewinder`].
+pub fn eller(width: usize, height: usize, seed: u64) -> Maze {
+    generate_grid(GeneratorAlgorithm::Eller, width, height, seed)
+}
+
+/// Eller state: the set of each cell in the current row and how far the row is built.
+struct EllerCarver {
+    row: usize,
+    sets: Vec<Option<usize>>,
+    next_set: usize,
+    phase: EllerPhase,
+}
+
+enum EllerPhase {
+    /// Opening the cells of the row from this column, new cells get their own set.
+    Open(usize),
+    /// Joining adjacent cells of different sets from this column.
+    Join(usize),
+    /// Extending the remaining sets to the next row, one set per step.
+    Extend {
+        groups: std::vec::IntoIter<(usize, Vec<usize>)>,
+        next_sets: Vec<Option<usize>>,
+    },
+    Done,
+}
+
+impl EllerCarver {
+    fn new<G: MazeGrid>(grid: &Grid<G>) -> Self {
+        Self { row: 0, sets: vec![None; grid.width], next_set: 0, phase: EllerPhase::Open(0) }
+    }
+
+    fn step<G: MazeGrid>(&mut self, grid: &mut Grid<G>, rng: &mut StdRng) -> bool {
+        let row = self.row;
+        let last_row = row + 1 == grid.height;
+
+        self.phase = match std::mem::replace(&mut self.phase, EllerPhase::Done) {
+            EllerPhase::Open(column) if column < grid.width => {
+                let set = &mut self.sets[column];
+                if set.is_none() {
+                    *set = Some(self.next_set);
+                    self.next_set += 1;
+                }
+                grid.open((row, column));
+                EllerPhase::Open(column + 1)
+            }
+            EllerPhase::Open(_) => EllerPhase::Join(0),
+            // Randomly join adjacent cells of different sets, the last row joins them all
+            EllerPhase::Join(column) if column + 1 < grid.width => {
+                let (left, right) = (self.sets[column], self.sets[column + 1]);
+                if left != right && (last_row || rng.random_bool(0.5)) {
+                    grid.connect((row, column), (row, column + 1));
+                    for set in self.sets.iter_mut().filter(|set| **set == right) {
+                        *set = left;
+                    }
+                }
+                EllerPhase::Join(column + 1)
+            }
+            EllerPhase::Join(_) if last_row => {
+                grid.close_row(row);
+                EllerPhase::Done
+            }
+            // Every set extends at least once to the next row
+            EllerPhase::Join(_) => {
+                let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+                for (column, set) in self.sets.iter().enumerate() {
+                    if let Some(set) = set {
+                        members.entry(*set).or_default().push(column);
+                    }
+                }
+                let mut groups: Vec<(usize, Vec<usize>)> = members.into_iter().collect();
+                groups.sort_unstable();
+                EllerPhase::Extend { groups: groups.into_iter(), next_sets: vec![None; grid.width] }
+            }
+            EllerPhase::Extend { mut groups, mut next_sets } => match groups.next() {
+                Some((set, mut columns)) => {
+                    columns.shuffle(rng);
+                    let count = rng.random_range(1..=columns.len());
+                    for &column in &columns[..count] {
+                        grid.connect((row, column), (row + 1, column));
+                        next_sets[column] = Some(set);
+                    }
+                    EllerPhase::Extend { groups, next_sets }
+                }
+                None => {
+                    self.sets = next_sets;
+                    grid.close_row(row);
+                    self.row += 1;
+                    EllerPhase::Open(0)
+                }
+            },
+            EllerPhase::Done => return false,
+        };
+        true
+    }
+}
+
+/// Adds loops to a generated maze by removing dead ends.
+///
+/// Dead ends are visited in a random order and each one is opened with probability
+/// `ratio`, preferring a wall shared with another dead end so that one removal can fix
+/// two of them. A `ratio` of 0 leaves the maze untouched, 1 removes every dead end.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze_generator::{braid, sidewinder};
+///
+/// let mut maze = sidewinder(10, 10, 42);
+/// braid(&mut maze, 0.5, 42);
+/// ```
+pub fn braid(maze: &mut Maze, ratio: f64, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let ratio = ratio.clamp(0.0, 1.0);
+    let height = maze.row_len.saturating_sub(1) / 2;
+    let width = maze.col_len.saturating_sub(1) / 2;
+
+    let wall = |(a, b): (GridCell, GridCell)| (a.0 + b.0 + 1, a.1 + b.1 + 1);
+    let is_dead_end = |map: &[Vec<u16>], cell: GridCell| {
+        let open = neighbors(width, height, cell)
+            .into_iter()
+            .filter(|&other| {
+                let (row, column) = wall((cell, other));
+                map[row][column] != PositionType::WALL
+            })
+            .count();
+        open == 1
+    };
+
+    let mut dead_ends: Vec<GridCell> = (0..height)
+        .flat_map(|row| (0..width).map(move |column| (row, column)))
+        .filter(|&cell| is_dead_end(&maze.map, cell))
+        .collect();
+    dead_ends.shuffle(&mut rng);
+
+    for cell in dead_ends {
+        // An earlier removal may already have opened this one
+        if !rng.random_bool(ratio) || !is_dead_end(&maze.map, cell) {
+            continue;
+        }
+
+        let closed: Vec<GridCell> = neighbors(width, height, cell)
+            .into_iter()
+            .filter(|&other| {
+                let (row, column) = wall((cell, other));
+                maze.map[row][column] == PositionType::WALL
+            })
+            .collect();
+        let paired: Vec<GridCell> =
+            closed.iter().copied().filter(|&other| is_dead_end(&maze.map, other)).collect();
+
+        if let Some(&other) = paired.choose(&mut rng).or_else(|| closed.choose(&mut rng)) {
+            let (row, column) = wall((cell, other));
+            maze.map[row][column] = PositionType::SPACE;
+        }
+    }
+}
+
+/// Moves the entry and the exit of a generated maze according to `placement`.
+///
+/// Both are always placed on cells, never on the walls between them, and the previous
+/// entry and exit become plain passages.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::Placement;
+/// use shared::maze_generator::{place_entry_exit, prim};
+///
+/// let mut maze = prim(10, 10, 42);
+/// place_entry_exit(&mut maze, Placement::Farthest, 42);
 /// ```
-pub fn sidewinder(width: usize, height: usize, print: bool, seed: u64) -> Maze {
+pub fn place_entry_exit(maze: &mut Maze, placement: Placement, seed: u64) {
     let mut rng = StdRng::seed_from_u64(seed);
-    let mut maze = Maze::new(
-        vec![vec![PositionType::WALL; width * 2 + 1]; height * 2 + 1],
-        Cell { row: 0, column: 0 },
-        Cell { row: 0, column: 0 },
-    );
+    let height = maze.row_len.saturating_sub(1) / 2;
+    let width = maze.col_len.saturating_sub(1) / 2;
+    if width == 0 || height == 0 {
+        return;
+    }
 
-    for row in 0..height {
-        let mut current = Vec::new();
+    let cells = width * height;
+    let random_cell = |rng: &mut StdRng| {
+        let index = rng.random_range(0..cells);
+        (index / width, index % width)
+    };
 
-        for col in 0..width {
-            if print {
-                maze.print_visited(&vec![vec![-1; width * 2 + 1]; height * 2 + 1]);
-                std::thread::sleep(std::time::Duration::from_millis(500));
+    let (entry, exit) = match placement {
+        Placement::Edges => edges(width, height, &mut rng),
+        Placement::Random => {
+            let entry = rng.random_range(0..cells);
+            let mut exit = rng.random_range(0..cells.saturating_sub(1).max(1));
+            if cells > 1 && exit >= entry {
+                exit += 1;
+            }
+            ((entry / width, entry % width), (exit / width, exit % width))
+        }
+        Placement::Farthest => farthest_pair(maze, random_cell(&mut rng)),
+        Placement::MinDistance(distance) => {
+            let entry = random_cell(&mut rng);
+            let distances = distances_from(maze, to_map(entry));
+            let candidates: Vec<GridCell> = (0..cells)
+                .map(|index| (index / width, index % width))
+                .filter(|&(row, column)| {
+                    distances[row * 2 + 1][column * 2 + 1] >= distance as i32 * 2
+                })
+                .collect();
+            match candidates.choose(&mut rng) {
+                Some(&exit) => (entry, exit),
+                None => farthest_pair(maze, entry),
             }
-            let cell_row = row * 2 + 1;
-            let cell_col = col * 2 + 1;
+        }
+    };
 
-            maze.map[cell_row][cell_col] = PositionType::SPACE;
+    maze.set_entry_exit(entry, exit);
+}
 
-            current.push(Cell { row: cell_row as i16, column: cell_col as i16 });
+/// Covers about `ratio` of the passages of a maze with patches of mud and water.
+///
+/// Patches of 2 to 6 positions grow from random passages, a third of them being water.
+/// The entry, the exit and the walls are left untouched, so paths do not change but
+/// their cost does, see [`PositionType::cost`].
+///
+/// # Example
+///
+/// ```
+/// use shared::maze_generator::{add_terrain, kruskal};
+///
+/// let mut maze = kruskal(10, 10, 42);
+/// add_terrain(&mut maze, 0.2, 42);
+/// ```
+pub fn add_terrain(maze: &mut Maze, ratio: f64, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut passages: Vec<Cell> = (0..maze.row_len)
+        .flat_map(|row| (0..maze.col_len).map(move |column| (row, column)))
+        .filter(|&(row, column)| maze.map[row][column] == PositionType::SPACE)
+        .map(|(row, column)| Cell { row: row as i16, column: column as i16 })
+        .collect();
+    let mut remaining = (passages.len() as f64 * ratio.clamp(0.0, 1.0)).round() as usize;
+    passages.shuffle(&mut rng);
 
-            if row > 0 && (rng.random_bool(0.5) || col == width - 1) {
-                let random_cell = match current.choose_mut(&mut rng) {
-                    Some(cell) => *cell,
-                    None => Cell { row: 0, column: 0 },
-                };
+    for start in passages {
+        if remaining == 0 {
+            break;
+        }
+        if maze.map[start.row as usize][start.column as usize] != PositionType::SPACE {
+            continue;
+        }
 
-                maze.map[random_cell.row as usize - 1][random_cell.column as usize] =
-                    PositionType::SPACE;
+        let terrain =
+            if rng.random_bool(1.0 / 3.0) { PositionType::WATER } else { PositionType::MUD };
+        let mut size = rng.random_range(2..=6).min(remaining);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            let position = &mut maze.map[cell.row as usize][cell.column as usize];
+            if size == 0 || *position != PositionType::SPACE {
+                continue;
+            }
+            *position = terrain;
+            size -= 1;
+            remaining -= 1;
+            for direction in
+                [Directions::NORTH, Directions::EAST, Directions::SOUTH, Directions::WEST]
+            {
+                let next = cell + direction;
+                if !maze.is_cell_out_of_bound(&next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+}
 
-                current.clear();
-            } else if col < width - 1 {
-                maze.map[cell_row][cell_col + 1] = PositionType::SPACE;
+/// Places help items, monster spawn points and extra objectives on the cells of a maze,
+/// replacing its previous items.
+///
+/// Objectives are placed first, then monsters, then help items, each on a random reachable
+/// cell other than the entry and the exit. Monsters are kept at least
+/// `placement.monster_distance` moves away from the entry and no two items are closer than
+/// `placement.spacing` cells, so fewer items than asked may fit in a small maze. Place the
+/// entry and the exit first.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze::{ItemKind, ItemPlacement};
+/// use shared::maze_generator::{place_items, wilson};
+///
+/// let mut maze = wilson(20, 20, 42);
+/// let placement = ItemPlacement { help: 0.02, monsters: 0.02, objectives: 1, ..Default::default() };
+/// place_items(&mut maze, &placement, 42);
+/// assert!(maze.items.iter().any(|item| item.kind == ItemKind::Monster));
+/// ```
+pub fn place_items(maze: &mut Maze, placement: &ItemPlacement, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let height = maze.row_len.saturating_sub(1) / 2;
+    let width = maze.col_len.saturating_sub(1) / 2;
+    maze.items.clear();
+
+    // Distances are counted in map positions, two per move from a cell to the next
+    let from_entry = distances_from(maze, maze.entry);
+    let mut cells: Vec<Cell> = (0..height)
+        .flat_map(|row| (0..width).map(move |column| to_map((row, column))))
+        .filter(|&cell| cell != maze.entry && cell != maze.exit)
+        .filter(|&cell| from_entry[cell.row as usize][cell.column as usize] >= 0)
+        .collect();
+    cells.shuffle(&mut rng);
+
+    let count = |ratio: f64| (ratio.clamp(0.0, 1.0) * (width * height) as f64).round() as usize;
+    let kinds = [
+        (ItemKind::Objective, placement.objectives, 0),
+        (ItemKind::Monster, count(placement.monsters), placement.monster_distance),
+        (ItemKind::Help, count(placement.help), 0),
+    ];
+
+    for (kind, count, min_distance) in kinds {
+        let mut placed = 0;
+        for &cell in &cells {
+            if placed == count {
+                break;
             }
+            let moves = from_entry[cell.row as usize][cell.column as usize] as usize / 2;
+            let crowded = maze.items.iter().any(|item| {
+                let cells_apart = ((item.cell.row - cell.row).unsigned_abs()
+                    + (item.cell.column - cell.column).unsigned_abs())
+                    / 2;
+                (cells_apart as usize) < placement.spacing.max(1)
+            });
+            if moves < min_distance || crowded {
+                continue;
+            }
+            maze.items.push(Item { kind, cell });
+            placed += 1;
         }
     }
-    (maze.entry, maze.exit) = generate_random_entry_exit(width, height, seed);
-    maze.map[maze.entry.row as usize][maze.entry.column as usize] = PositionType::ENTRY;
-    maze.map[maze.exit.row as usize][maze.exit.column as usize] = PositionType::EXIT;
-    maze
 }
 
-fn generate_random_entry_exit(width: usize, height: usize, seed: u64) -> (Cell, Cell) {
+/// Opens up to `count` walls between two cells of a maze and closes as many passages,
+/// without ever cutting a part of the maze off.
+///
+/// Each opened wall closes a loop, one of the passages along that loop is then closed in
+/// exchange, so every cell that could reach the exit still can. Border walls are never
+/// touched. A closed passage keeps its terrain in `closed_passages` and gets it back when
+/// it opens again. Returns the map positions that changed, opened and closed ones alike.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze_generator::{backtracker, shift_walls};
+///
+/// let mut maze = backtracker(10, 10, 42);
+/// let changed = shift_walls(&mut maze, 3, 7);
+/// assert_eq!(changed.len(), 6);
+/// ```
+pub fn shift_walls(maze: &mut Maze, count: usize, seed: u64) -> Vec<Cell> {
     let mut rng = StdRng::seed_from_u64(seed);
-    let entry = Cell { row: rng.random_range(1..height as i16), column: 1 };
-    let exit = Cell { row: rng.random_range(1..height as i16), column: width as i16 - 1 };
+    let is_wall = |maze: &Maze, cell: Cell| {
+        maze.map[cell.row as usize][cell.column as usize] == PositionType::WALL
+    };
+
+    // Positions between two cells have exactly one odd coordinate
+    let mut closed: Vec<Cell> = (1..maze.row_len.saturating_sub(1))
+        .flat_map(|row| (1..maze.col_len.saturating_sub(1)).map(move |column| (row, column)))
+        .filter(|&(row, column)| (row + column) % 2 == 1)
+        .map(|(row, column)| Cell { row: row as i16, column: column as i16 })
+        .filter(|&cell| is_wall(maze, cell))
+        .collect();
+    closed.shuffle(&mut rng);
 
-    (entry, exit)
+    let mut changed = Vec::new();
+    for wall in closed.into_iter().take(count) {
+        let (a, b) = if wall.row % 2 == 0 {
+            (wall + Directions::NORTH, wall + Directions::SOUTH)
+        } else {
+            (wall + Directions::WEST, wall + Directions::EAST)
+        };
+
+        // Walk back from `b` to `a` along the current shortest path, the loop being that
+        // path and the wall about to be opened
+        let distances = distances_from(maze, a);
+        let mut loop_passages = Vec::new();
+        let mut cell = b;
+        while distances[cell.row as usize][cell.column as usize] > 0 {
+            let distance = distances[cell.row as usize][cell.column as usize];
+            let Some(previous) =
+                [Directions::NORTH, Directions::EAST, Directions::SOUTH, Directions::WEST]
+                    .into_iter()
+                    .map(|direction| cell + direction)
+                    .filter(|next| !maze.is_cell_out_of_bound(next))
+                    .find(|next| {
+                        distances[next.row as usize][next.column as usize] == distance - 1
+                    })
+            else {
+                break;
+            };
+            if (previous.row + previous.column) % 2 == 1 {
+                loop_passages.push(previous);
+            }
+            cell = previous;
+        }
+
+        maze.map[wall.row as usize][wall.column as usize] =
+            maze.closed_passages.remove(&wall).unwrap_or(PositionType::SPACE);
+        changed.push(wall);
+        // Cells out of reach from each other are simply joined
+        if distances[b.row as usize][b.column as usize] > 0 {
+            if let Some(&passage) = loop_passages.choose(&mut rng) {
+                let position = &mut maze.map[passage.row as usize][passage.column as usize];
+                maze.closed_passages.insert(passage, *position);
+                *position = PositionType::WALL;
+                changed.push(passage);
+            }
+        }
+    }
+    changed
+}
+
+/// Links mazes of the same size into the levels of a
+/// [`LevelMaze`](crate::maze::levels::LevelMaze).
+///
+/// The entry of every level but the first is moved onto the exit of the level below, where
+/// the stairs arrive. An exit already there moves to the farthest cell from the new entry,
+/// and an item there is dropped. A level too small to hold the stairs is left as is.
+///
+/// # Example
+///
+/// ```
+/// use shared::maze_generator::{link_levels, prim};
+///
+/// let mut levels = vec![prim(10, 10, 1), prim(10, 10, 2)];
+/// link_levels(&mut levels);
+/// assert_eq!(levels[1].entry, levels[0].exit);
+/// ```
+pub fn link_levels(levels: &mut [Maze]) {
+    for level in 1..levels.len() {
+        let stairs = levels[level - 1].exit();
+        let maze = &mut levels[level];
+        if stairs.0 >= maze.height() || stairs.1 >= maze.width() {
+            continue;
+        }
+
+        let exit = if maze.exit() == stairs { farthest_cell(maze, stairs) } else { maze.exit() };
+        maze.set_entry_exit(stairs, exit);
+        let entry = maze.entry;
+        maze.items.retain(|item| item.cell != entry);
+    }
+}
+
+/// The cell with the longest path from `from`, `from` itself if it is the only one.
+fn farthest_cell(maze: &Maze, from: GridCell) -> GridCell {
+    let distances = distances_from(maze, to_map(from));
+    let mut best = (0, from);
+    for (row, line) in distances.iter().enumerate().skip(1).step_by(2) {
+        for (column, &distance) in line.iter().enumerate().skip(1).step_by(2) {
+            if distance > best.0 {
+                best = (distance, (row / 2, column / 2));
+            }
+        }
+    }
+    best.1
+}
+
+/// The cell farthest from `start`, then the cell farthest from that one. This is the
+/// farthest pair of a perfect maze and a close approximation once it has loops.
+fn farthest_pair(maze: &Maze, start: GridCell) -> (GridCell, GridCell) {
+    let entry = farthest_cell(maze, start);
+    (entry, farthest_cell(maze, entry))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::VecDeque;
+
+    /// Checks that every cell is open and reachable and that there is exactly one path
+    /// between any two cells, i.e. the passages form a spanning tree.
+    fn assert_perfect(maze: &Maze, width: usize, height: usize, name: &str) {
+        let open = |row: usize, column: usize| maze.map[row][column] != PositionType::WALL;
+
+        let mut passages = 0;
+        for (row, line) in maze.map.iter().enumerate() {
+            for column in 0..line.len() {
+                match (row % 2, column % 2) {
+                    (1, 1) => assert!(open(row, column), "{}: closed cell", name),
+                    (0, 0) => assert!(!open(row, column), "{}: open corner", name),
+                    _ if open(row, column) => passages += 1,
+                    _ => {}
+                }
+            }
+        }
+        assert_eq!(passages, width * height - 1, "{}: not a tree", name);
+
+        let mut seen = vec![vec![false; width * 2 + 1]; height * 2 + 1];
+        let mut queue = VecDeque::from([(1, 1)]);
+        seen[1][1] = true;
+        let mut reached = 0;
+        while let Some((row, column)) = queue.pop_front() {
+            reached += (row % 2 == 1 && column % 2 == 1) as usize;
+            for (r, c) in
+                [(row - 1, column), (row + 1, column), (row, column - 1), (row, column + 1)]
+            {
+                if open(r, c) && !seen[r][c] {
+                    seen[r][c] = true;
+                    queue.push_back((r, c));
+                }
+            }
+        }
+        assert_eq!(reached, width * height, "{}: disconnected", name);
+    }
+
+    #[test]
+    fn test_every_generator_is_perfect() {
+        for algorithm in GeneratorAlgorithm::ALL {
+            let name = &algorithm.to_string();
+            for (width, height) in [(1, 1), (1, 6), (6, 1), (2, 2), (10, 10), (13, 7)] {
+                for seed in 0..5 {
+                    let maze = carve_grid::<Maze>(algorithm, width, height, seed);
+                    assert_perfect(&maze, width, height, name);
+                }
+            }
+        }
+    }
+
+    fn dead_ends(maze: &Maze) -> usize {
+        let (height, width) = ((maze.row_len - 1) / 2, (maze.col_len - 1) / 2);
+        (0..height)
+            .flat_map(|row| (0..width).map(move |column| (row * 2 + 1, column * 2 + 1)))
+            .filter(|&(row, column)| {
+                [(row - 1, column), (row + 1, column), (row, column - 1), (row, column + 1)]
+                    .iter()
+                    .filter(|&&(r, c)| maze.map[r][c] != PositionType::WALL)
+                    .count()
+                    == 1
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_braid_ratio() {
+        for generate in [sidewinder, backtracker, prim, kruskal, wilson, eller] {
+            let perfect = generate(15, 12, 3);
+            let initial = dead_ends(&perfect);
+            assert!(initial > 0);
+
+            let mut untouched = generate(15, 12, 3);
+            braid(&mut untouched, 0.0, 3);
+            assert_eq!(untouched.map, perfect.map);
+
+            let mut partial = generate(15, 12, 3);
+            braid(&mut partial, 0.5, 3);
+            let remaining = dead_ends(&partial);
+            assert!(remaining > 0 && remaining < initial, "{} of {}", remaining, initial);
+
+            let mut full = generate(15, 12, 3);
+            braid(&mut full, 1.0, 3);
+            assert_eq!(dead_ends(&full), 0);
+        }
+    }
+
+    #[test]
+    fn test_braid_only_removes_interior_walls() {
+        let perfect = prim(10, 8, 9);
+        let mut braided = prim(10, 8, 9);
+        braid(&mut braided, 1.0, 9);
+
+        for (row, line) in braided.map.iter().enumerate() {
+            for (column, &position) in line.iter().enumerate() {
+                if perfect.map[row][column] != PositionType::WALL {
+                    assert_eq!(position, perfect.map[row][column]);
+                } else if position != PositionType::WALL {
+                    assert!(row > 0 && row < 16 && column > 0 && column < 20);
+                    assert!(row % 2 == 1 || column % 2 == 1, "open corner at {},{}", row, column);
+                }
+            }
+        }
+
+        let mut again = prim(10, 8, 9);
+        braid(&mut again, 1.0, 9);
+        assert_eq!(again.map, braided.map);
+    }
+
+    #[test]
+    fn test_terrain_only_covers_passages() {
+        let plain = kruskal(12, 10, 4);
+        let passages = plain.map.iter().flatten().filter(|&&p| p == PositionType::SPACE).count();
+
+        let mut untouched = kruskal(12, 10, 4);
+        add_terrain(&mut untouched, 0.0, 4);
+        assert_eq!(untouched.map, plain.map);
+
+        let mut covered = kruskal(12, 10, 4);
+        add_terrain(&mut covered, 0.25, 4);
+        let terrain = covered
+            .map
+            .iter()
+            .flatten()
+            .filter(|&&p| p == PositionType::MUD || p == PositionType::WATER)
+            .count();
+        assert_eq!(terrain, (passages as f64 * 0.25).round() as usize);
+
+        for (row, line) in covered.map.iter().enumerate() {
+            for (column, &position) in line.iter().enumerate() {
+                if position != PositionType::MUD && position != PositionType::WATER {
+                    assert_eq!(position, plain.map[row][column]);
+                } else {
+                    assert_eq!(plain.map[row][column], PositionType::SPACE);
+                }
+            }
+        }
+
+        let mut again = kruskal(12, 10, 4);
+        add_terrain(&mut again, 0.25, 4);
+        assert_eq!(again.map, covered.map);
+
+        let mut flooded = kruskal(12, 10, 4);
+        add_terrain(&mut flooded, 1.0, 4);
+        assert!(!flooded.map.iter().flatten().any(|&p| p == PositionType::SPACE));
+        assert_eq!(
+            flooded.map[flooded.entry.row as usize][flooded.entry.column as usize],
+            PositionType::ENTRY
+        );
+    }
+
+    #[test]
+    fn test_item_placement() {
+        let placement = ItemPlacement {
+            help: 0.05,
+            monsters: 0.05,
+            objectives: 2,
+            monster_distance: 5,
+            spacing: 2,
+        };
+        let mut maze = wilson(20, 15, 8);
+        place_items(&mut maze, &placement, 8);
+
+        let count = |kind| maze.items.iter().filter(|item| item.kind == kind).count();
+        assert_eq!(count(ItemKind::Objective), 2);
+        assert_eq!(count(ItemKind::Monster), 15);
+        assert_eq!(count(ItemKind::Help), 15);
+
+        let from_entry = distances_from(&maze, maze.entry);
+        for (index, item) in maze.items.iter().enumerate() {
+            let (row, column) = (item.cell.row as usize, item.cell.column as usize);
+            assert_eq!((row % 2, column % 2), (1, 1), "items are on cells");
+            assert!(item.cell != maze.entry && item.cell != maze.exit);
+            if item.kind == ItemKind::Monster {
+                assert!(from_entry[row][column] >= 5 * 2, "monster too close to the entry");
+            }
+            for other in &maze.items[index + 1..] {
+                let apart = (item.cell.row - other.cell.row).abs()
+                    + (item.cell.column - other.cell.column).abs();
+                assert!(apart >= 2 * 2, "items too close to each other");
+            }
+        }
+
+        let mut again = wilson(20, 15, 8);
+        place_items(&mut again, &placement, 8);
+        assert_eq!(again.items, maze.items);
+        place_items(&mut again, &placement, 9);
+        assert_ne!(again.items, maze.items);
+
+        // Items replace the previous ones and the default places none
+        place_items(&mut again, &ItemPlacement::default(), 9);
+        assert!(again.items.is_empty());
+        assert!(ItemPlacement::default().is_empty());
+    }
+
+    #[test]
+    fn test_shift_walls_keeps_the_maze_connected() {
+        for algorithm in GeneratorAlgorithm::ALL {
+            let name = &algorithm.to_string();
+            let mut maze = Maze::generate(algorithm, 12, 9, 5);
+            let passages = |maze: &Maze| {
+                maze.map.iter().flatten().filter(|&&p| p != PositionType::WALL).count()
+            };
+            let before = passages(&maze);
+
+            for seed in 0..5 {
+                let changed = shift_walls(&mut maze, 4, seed);
+                assert_eq!(changed.len(), 8, "{}: one passage closed per wall opened", name);
+                assert_eq!(passages(&maze), before, "{}", name);
+                // Still a perfect maze, hence every cell is reachable
+                assert_perfect(&maze, 12, 9, name);
+            }
+        }
+
+        let mut maze = wilson(8, 8, 2);
+        let mut again = wilson(8, 8, 2);
+        assert_eq!(shift_walls(&mut maze, 3, 1), shift_walls(&mut again, 3, 1));
+        assert_eq!(maze.map, again.map);
+        assert!(shift_walls(&mut maze, 0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_shift_walls_restores_terrain() {
+        let mut maze = kruskal(8, 6, 3);
+        add_terrain(&mut maze, 1.0, 3);
+        let original = maze.map.clone();
+
+        let mut reopened = 0;
+        for seed in 0..40 {
+            for position in shift_walls(&mut maze, 3, seed) {
+                let (row, column) = (position.row as usize, position.column as usize);
+                let was_open = original[row][column] != PositionType::WALL;
+                if was_open && maze.map[row][column] != PositionType::WALL {
+                    assert_eq!(maze.map[row][column], original[row][column], "{:?}", position);
+                    reopened += 1;
+                }
+            }
+        }
+        assert!(reopened > 0);
+    }
+
+    #[test]
+    fn test_item_placement_in_small_maze() {
+        // No cell of a 3x2 maze is 6 moves away from another
+        let mut maze = eller(3, 2, 1);
+        let placement =
+            ItemPlacement { monsters: 1.0, monster_distance: 6, ..ItemPlacement::default() };
+        place_items(&mut maze, &placement, 1);
+        assert!(maze.items.is_empty());
+
+        let placement = ItemPlacement { help: 1.0, ..ItemPlacement::default() };
+        place_items(&mut maze, &placement, 1);
+        assert_eq!(maze.items.len(), 3 * 2 - 2);
+    }
+
+    #[test]
+    fn test_generators_are_seeded() {
+        for generate in [sidewinder, backtracker, prim, kruskal, wilson, eller] {
+            let maze = generate(12, 9, 42);
+            assert_eq!(maze.map.len(), 19);
+            assert_eq!(maze.map, generate(12, 9, 42).map);
+            assert_ne!(maze.map, generate(12, 9, 43).map);
+        }
+    }
 
     #[test]
     fn test_sidewinder() {
         let seed = 42;
-        let maze = sidewinder(10, 10, false, seed);
+        let maze = sidewinder(10, 10, seed);
         assert_eq!(maze.map.len(), 21);
 
-        let maze2 = sidewinder(10, 10, false, seed);
+        let maze2 = sidewinder(10, 10, seed);
         assert_eq!(maze.map, maze2.map);
     }
 
     #[test]
-    fn test_generate_random_entry_exit() {
-        let seed = 42;
-        let (entry, exit) = generate_random_entry_exit(10, 10, seed);
-        assert!(entry.row > 0 && entry.row < 10);
-        assert_eq!(entry.column, 1);
-        assert!(exit.row > 0 && exit.row < 10);
-        assert_eq!(exit.column, 9);
-
-        let (entry2, exit2) = generate_random_entry_exit(10, 10, seed);
-        assert_eq!(entry.row, entry2.row);
-        assert_eq!(exit.row, exit2.row);
+    fn test_sidewinder_top_row_is_open() {
+        let maze = sidewinder(8, 5, 7);
+        assert!(maze.map[1][1..16].iter().all(|&position| position != PositionType::WALL));
+    }
+
+    #[test]
+    fn test_edges_placement() {
+        for seed in 0..20 {
+            let maze = sidewinder(10, 7, seed);
+            assert_eq!(maze.entry.column, 1);
+            assert_eq!(maze.exit.column, 19);
+            assert_eq!(maze.entry.row % 2, 1);
+            assert_eq!(maze.exit.row % 2, 1);
+            assert!(maze.entry.row < 14 && maze.exit.row < 14);
+        }
+    }
+
+    fn assert_reachable(maze: &Maze) {
+        for marker in [maze.entry, maze.exit] {
+            assert_eq!((marker.row % 2, marker.column % 2), (1, 1), "{:?} is not a cell", marker);
+        }
+        assert_eq!(
+            maze.map[maze.entry.row as usize][maze.entry.column as usize],
+            PositionType::ENTRY
+        );
+        assert_eq!(maze.map[maze.exit.row as usize][maze.exit.column as usize], PositionType::EXIT);
+        let markers = maze
+            .map
+            .iter()
+            .flatten()
+            .filter(|&&p| p == PositionType::ENTRY || p == PositionType::EXIT);
+        assert_eq!(markers.count(), 2);
+
+        let distances = distances_from(maze, maze.entry);
+        assert!(distances[maze.exit.row as usize][maze.exit.column as usize] > 0);
+    }
+
+    #[test]
+    fn test_every_placement_is_reachable() {
+        let placements =
+            [Placement::Edges, Placement::Random, Placement::Farthest, Placement::MinDistance(8)];
+        for generate in [sidewinder, backtracker, prim, kruskal, wilson, eller] {
+            for placement in placements {
+                for seed in 0..5 {
+                    let mut maze = generate(9, 6, seed);
+                    place_entry_exit(&mut maze, placement, seed);
+                    assert_reachable(&maze);
+
+                    braid(&mut maze, 0.5, seed);
+                    place_entry_exit(&mut maze, placement, seed + 1);
+                    assert_reachable(&maze);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_farthest_placement() {
+        let mut maze = wilson(12, 12, 5);
+        place_entry_exit(&mut maze, Placement::Farthest, 5);
+
+        let distances = distances_from(&maze, maze.entry);
+        let longest = distances.iter().flatten().max().copied();
+        assert_eq!(Some(distances[maze.exit.row as usize][maze.exit.column as usize]), longest);
+    }
+
+    #[test]
+    fn test_min_distance_placement() {
+        for seed in 0..10 {
+            let mut maze = kruskal(15, 15, seed);
+            place_entry_exit(&mut maze, Placement::MinDistance(20), seed);
+            let distances = distances_from(&maze, maze.entry);
+            assert!(distances[maze.exit.row as usize][maze.exit.column as usize] >= 40);
+        }
+
+        // Too far for a 3x3 maze, falls back on the farthest pair
+        let mut maze = backtracker(3, 3, 1);
+        place_entry_exit(&mut maze, Placement::MinDistance(100), 1);
+        assert_reachable(&maze);
+    }
+
+    #[test]
+    fn test_placement_on_tiny_mazes() {
+        for placement in [Placement::Edges, Placement::Random, Placement::Farthest] {
+            let mut maze = prim(1, 1, 0);
+            place_entry_exit(&mut maze, placement, 0);
+            assert_eq!(
+                (maze.entry, maze.exit),
+                (Cell { row: 1, column: 1 }, Cell { row: 1, column: 1 })
+            );
+
+            let mut maze = eller(2, 1, 0);
+            place_entry_exit(&mut maze, placement, 0);
+            assert_ne!(maze.entry, maze.exit);
+        }
+    }
+
+    #[test]
+    fn test_generation_collects_into_generated_maze() {
+        for algorithm in GeneratorAlgorithm::ALL {
+            let maze: Maze = generation(algorithm, 11, 7, 3).collect();
+            let expected = Maze::generate(algorithm, 11, 7, 3);
+            assert_eq!(maze.map, expected.map, "{}", algorithm);
+            assert_eq!((maze.entry, maze.exit), (expected.entry, expected.exit));
+            assert_eq!((maze.row_len, maze.col_len), (15, 23));
+        }
+    }
+
+    #[test]
+    fn test_generation_is_lazy() {
+        // Only the carving needed for the events taken so far is done
+        let mut events = generation(GeneratorAlgorithm::Backtracker, 1000, 1000, 2);
+        assert_eq!(events.next(), Some(GenerationEvent::Started { width: 1000, height: 1000 }));
+        assert_eq!(events.by_ref().take(10).count(), 10);
+
+        let pending = events.grid.events.as_ref().map_or(0, VecDeque::len);
+        assert!(pending <= 2, "{} pending events", pending);
+        let carved = events.grid.target.map.iter().flatten();
+        let carved = carved.filter(|&&position| position == PositionType::SPACE).count();
+        assert_eq!(carved, 10 + pending);
+    }
+
+    #[test]
+    fn test_generation_event_order() {
+        for algorithm in GeneratorAlgorithm::ALL {
+            let events: Vec<GenerationEvent> = generation(algorithm, 6, 5, 8).collect();
+            assert_eq!(events[0], GenerationEvent::Started { width: 6, height: 5 });
+            assert!(matches!(events.last(), Some(GenerationEvent::Finished { .. })));
+
+            let mut maze = Maze::with_walls(6, 5);
+            let (mut carved, mut removed) = (0, 0);
+            for event in &events {
+                if let GenerationEvent::WallRemoved(wall) = *event {
+                    // Both cells on each side of a wall are carved before it is removed
+                    let (row, column) = (wall.row as usize, wall.column as usize);
+                    let sides = if row % 2 == 0 {
+                        [(row - 1, column), (row + 1, column)]
+                    } else {
+                        [(row, column - 1), (row, column + 1)]
+                    };
+                    for (r, c) in sides {
+                        assert_eq!(maze.map[r][c], PositionType::SPACE, "{}", algorithm);
+                    }
+                    removed += 1;
+                }
+                if let GenerationEvent::CellCarved(cell) = *event {
+                    assert_eq!(
+                        maze.map[cell.row as usize][cell.column as usize],
+                        PositionType::WALL
+                    );
+                    carved += 1;
+                }
+                maze.extend([*event]);
+            }
+            assert_eq!((carved, removed), (30, 29), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_row_based_generation_closes_rows_in_order() {
+        for algorithm in [GeneratorAlgorithm::Sidewinder, GeneratorAlgorithm::Eller] {
+            let mut closed = Vec::new();
+            for event in generation(algorithm, 7, 6, 1) {
+                match event {
+                    GenerationEvent::RowClosed(row) => closed.push(row),
+                    // No cell of a closed row is carved afterwards
+                    GenerationEvent::CellCarved(cell) => {
+                        assert!(cell.row as usize / 2 >= closed.len(), "{}", algorithm)
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(closed, (0..6).collect::<Vec<_>>());
+        }
+
+        let rows = generation(GeneratorAlgorithm::Prim, 7, 6, 1)
+            .filter(|event| matches!(event, GenerationEvent::RowClosed(_)));
+        assert_eq!(rows.count(), 0);
+    }
+
+    #[test]
+    fn test_compact_grid_holds_the_same_maze() {
+        use crate::maze::grid::CompactMaze;
+
+        for algorithm in GeneratorAlgorithm::ALL {
+            let compact: CompactMaze = generate_grid(algorithm, 13, 9, 6);
+            let maze = Maze::generate(algorithm, 13, 9, 6);
+            assert_eq!(Maze::from(&compact).map, maze.map, "{}", algorithm);
+            assert_eq!((compact.entry(), compact.exit()), (maze.entry(), maze.exit()));
+        }
     }
 }
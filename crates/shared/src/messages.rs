@@ -1,15 +1,22 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{
     io::{self, Read, Write},
     net::{SocketAddr, TcpStream},
+    time::Duration,
 };
 
 use crate::{
     errors::{GameError, GameResult},
     logger::Logger,
+    maze::Cell,
 };
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Every message exchanged between clients and the server.
+///
+/// On the wire each message is its JSON encoding prefixed by its byte length as a
+/// little-endian `u32`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum Message {
     RegisterTeam(RegisterTeam),
     RegisterTeamResult(RegisterTeamResult),
@@ -21,14 +28,17 @@ pub enum Message {
     MessageError(MessageError),
     Hint(Hint),
     Challenge(Challenge),
+    Ping(Heartbeat),
+    Pong(Heartbeat),
+    TeamMessage(TeamMessage),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct RegisterTeam {
     pub name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum RegistrationError {
     InvalidName,
     TeamAlreadyRegistered,
@@ -38,33 +48,41 @@ pub enum RegistrationError {
     ServerError,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum RegisterTeamResult {
     Ok { expected_players: u8, registration_token: String },
     Err(RegistrationError),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct MessageError {
     pub message: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub struct SubscribePlayer {
     pub name: String,
     pub registration_token: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum SubscribePlayerResult {
     Ok,
     Err(RegistrationError),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// The 3x3 surroundings of the player, facing up, encoded with the game's base64 alphabet
+/// (`a-z`, `A-Z`, `0-9`, `+`, `/`, no padding).
+///
+/// The 11 decoded bytes hold 12 horizontal passages (3 bytes, little-endian), then
+/// 12 vertical passages (3 bytes, little-endian), both as 2-bit codes
+/// (0 undefined, 1 open, 2 wall), then 9 cells as 4-bit codes in reading order
+/// (0 nothing, 1 ally, 2 enemy, 3 monster, 4 help, 5 stairs, 8 objective, 15 invalid), high
+/// nibble first.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct RadarView(pub String);
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub enum Direction {
     Right,
     Left,
@@ -72,13 +90,13 @@ pub enum Direction {
     Back,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub enum Action {
     MoveTo(Direction),
     SolveChallenge { answer: String },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum ActionError {
     InvalidMove,
     OutOfMap,
@@ -90,7 +108,7 @@ pub enum ActionError {
     NoRunningChallenge,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum Hint {
     RelativeCompass { angle: f32 },
     GridSize { columns: u32, rows: u32 },
@@ -98,11 +116,36 @@ pub enum Hint {
     SOS,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 pub enum Challenge {
     SecretSumModulo(u128),
 }
 
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub sequence: u32,
+}
+
+/// A message relayed by the server to the sender's teammates only.
+///
+/// The server overwrites `sender` with the name the connection subscribed with.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct TeamMessage {
+    pub sender: String,
+    pub info: TeamInfo,
+}
+
+/// Knowledge an agent shares with its team.
+///
+/// Positions are expressed in the sender's own frame: the origin is its spawn cell and
+/// the axes follow its initial direction, so teammates spawning together share it.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub enum TeamInfo {
+    DeadEnd(Cell),
+    Exit(Cell),
+    ClaimCorridor { from: Cell, to: Cell },
+}
+
 #[derive(Debug, Clone)]
 pub struct Client {
     pub player_name: String,
@@ -120,7 +163,8 @@ pub struct Teams {
     pub max_players: u8,
 }
 
-pub fn receive_message(stream: &mut TcpStream) -> GameResult<Message> {
+/// Reads one length-prefixed frame and returns its raw JSON payload.
+pub fn read_frame<R: Read>(stream: &mut R) -> GameResult<Vec<u8>> {
     let mut buf_len = [0u8; 4];
     stream.read_exact(&mut buf_len).map_err(|e| match e.kind() {
         io::ErrorKind::UnexpectedEof => GameError::MessageError("Incomplete message length".into()),
@@ -135,16 +179,56 @@ pub fn receive_message(stream: &mut TcpStream) -> GameResult<Message> {
 
     let mut buf = vec![0u8; len];
     stream.read_exact(&mut buf).map_err(GameError::ConnectionError)?;
+    Ok(buf)
+}
+
+/// Writes a raw JSON payload as one length-prefixed frame.
+pub fn write_frame<W: Write>(stream: &mut W, payload: &[u8]) -> GameResult<()> {
+    let mut buffer = Vec::with_capacity(4 + payload.len());
+    buffer.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(payload);
+
+    stream.write_all(&buffer).and_then(|_| stream.flush()).map_err(GameError::ConnectionError)
+}
 
-    let str = match String::from_utf8(buf) {
+/// Parses the JSON payload of a frame into a `Message`.
+pub fn decode_frame(payload: &[u8]) -> GameResult<Message> {
+    let str = match std::str::from_utf8(payload) {
         Ok(s) => s,
         Err(e) => return Err(GameError::MessageError(format!("Invalid UTF-8: {}", e))),
     };
 
-    serde_json::from_str(&str).map_err(|e| GameError::MessageError(format!("Invalid JSON: {}", e)))
+    serde_json::from_str(str).map_err(|e| GameError::MessageError(format!("Invalid JSON: {}", e)))
 }
 
-pub fn send_message(stream: &mut TcpStream, msg: &Message) -> GameResult<()> {
+pub fn receive_message<R: Read>(stream: &mut R) -> GameResult<Message> {
+    decode_frame(&read_frame(stream)?)
+}
+
+/// Waits up to `timeout` for the next message on the stream.
+///
+/// Returns `Ok(None)` when nothing arrived in time, which lets callers tell an idle
+/// peer apart from a closed connection. Once a frame has started, each read of the rest
+/// of it waits up to `timeout` too: a peer stalling in the middle of a frame is reported
+/// as a connection error rather than blocking the reader forever.
+pub fn poll_message(stream: &mut TcpStream, timeout: Duration) -> GameResult<Option<Message>> {
+    stream.set_read_timeout(Some(timeout)).map_err(GameError::ConnectionError)?;
+
+    let mut probe = [0u8; 1];
+    let received = match stream.peek(&mut probe) {
+        Ok(0) => Err(GameError::MessageError("Connection closed".into())),
+        Ok(_) => receive_message(stream).map(Some),
+        Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+            Ok(None)
+        }
+        Err(e) => Err(GameError::ConnectionError(e)),
+    };
+
+    stream.set_read_timeout(None).map_err(GameError::ConnectionError)?;
+    received
+}
+
+pub fn send_message<W: Write>(stream: &mut W, msg: &Message) -> GameResult<()> {
     let json =
         serde_json::to_string(msg).map_err(|e| GameError::SerializationError(e.to_string()))?;
 
@@ -153,12 +237,7 @@ pub fn send_message(stream: &mut TcpStream, msg: &Message) -> GameResult<()> {
             .debug(&format!("Sending message: Action::SolveChallenge {{ answer: {} }}", answer));
     }
 
-    let len = json.len();
-    let mut buffer = Vec::with_capacity(4 + len);
-    buffer.extend_from_slice(&(len as u32).to_le_bytes());
-    buffer.extend_from_slice(json.as_bytes());
-
-    stream.write_all(&buffer).and_then(|_| stream.flush()).map_err(GameError::ConnectionError)
+    write_frame(stream, json.as_bytes())
 }
 
 #[cfg(test)]
@@ -182,6 +261,15 @@ mod tests {
             Message::Action(Action::MoveTo(Direction::Right)),
             Message::Action(Action::SolveChallenge { answer: "answer".to_string() }),
             Message::MessageError(MessageError { message: "error".to_string() }),
+            Message::Ping(Heartbeat { sequence: 1 }),
+            Message::Pong(Heartbeat { sequence: 1 }),
+            Message::TeamMessage(TeamMessage {
+                sender: "player1".to_string(),
+                info: TeamInfo::ClaimCorridor {
+                    from: Cell { row: 0, column: 0 },
+                    to: Cell { row: 0, column: -1 },
+                },
+            }),
         ];
 
         for msg in messages {
@@ -195,4 +283,72 @@ mod tests {
                 | Message::MessageError(_));
         }
     }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, br#"{"Hint":{"Secret":42}}"#).unwrap();
+        assert_eq!(&buffer[..4], &22u32.to_le_bytes());
+
+        let payload = read_frame(&mut buffer.as_slice()).unwrap();
+        assert!(matches!(decode_frame(&payload).unwrap(), Message::Hint(Hint::Secret(42))));
+    }
+
+    #[test]
+    fn test_read_frame_rejects_truncated_input() {
+        let mut input: &[u8] = &[10, 0, 0, 0, b'{'];
+        assert!(read_frame(&mut input).is_err());
+
+        let mut input: &[u8] = &[1, 0];
+        assert!(matches!(read_frame(&mut input), Err(GameError::MessageError(_))));
+    }
+
+    #[test]
+    fn test_message_round_trip_over_buffer() {
+        let mut buffer = Vec::new();
+        send_message(&mut buffer, &Message::Ping(Heartbeat { sequence: 3 })).unwrap();
+
+        let message = receive_message(&mut buffer.as_slice()).unwrap();
+        assert!(matches!(message, Message::Ping(Heartbeat { sequence: 3 })));
+        assert!(receive_message(&mut &b"\x02\0\0\0{}"[..]).is_err());
+    }
+
+    #[test]
+    fn test_poll_message_times_out_on_partial_frame() {
+        use std::net::TcpListener;
+        use std::time::Instant;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        let timeout = Duration::from_millis(50);
+        assert!(poll_message(&mut client, timeout).unwrap().is_none());
+
+        // The length announces ten bytes but only one is ever sent
+        server.write_all(&[10, 0, 0, 0, b'{']).unwrap();
+        let start = Instant::now();
+        assert!(matches!(poll_message(&mut client, timeout), Err(GameError::ConnectionError(_))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(server);
+    }
+
+    #[test]
+    fn test_team_message_round_trip() {
+        let message = TeamMessage {
+            sender: "player1".to_string(),
+            info: TeamInfo::Exit(Cell { row: 3, column: -2 }),
+        };
+
+        let serialized = serde_json::to_string(&Message::TeamMessage(message.clone())).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"TeamMessage":{"sender":"player1","info":{"Exit":{"row":3,"column":-2}}}}"#
+        );
+
+        match serde_json::from_str(&serialized).unwrap() {
+            Message::TeamMessage(deserialized) => assert_eq!(deserialized, message),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
 }
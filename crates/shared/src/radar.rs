@@ -1,9 +1,12 @@
+use crate::base64::Base64;
+use crate::errors::{Base64Error, RadarError};
+use crate::maze::levels::{LevelCell, LevelMaze};
+use crate::maze::{Cell, ItemKind, Maze, PositionType};
+use crate::messages::Direction;
 use crate::to_binary::ToBinary;
 use core::str;
 use std::char;
-use std::fmt::Write;
-
-const BASE64_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789+/";
+use std::fmt;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Passages {
@@ -19,19 +22,145 @@ pub enum CellType {
     ENEMY = 2,
     MONSTER = 3,
     HELP = 4,
+    /// Stairs to another level of the maze, see [`level_radar`].
+    STAIRS = 5,
     OBJECTIVE = 8,
     ObjectiveAlly = 9,
     ObjectiveMonster = 11,
     INVALID = 15,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Radar {
     pub horizontal: Vec<Passages>,
     pub vertical: Vec<Passages>,
     pub cells: Vec<CellType>,
 }
 
+/// Box-drawing junctions indexed by the walls around them: up = 1, right = 2, down = 4, left = 8.
+pub(crate) const JUNCTIONS: [char; 16] =
+    [' ', '╵', '╶', '└', '╷', '│', '┌', '├', '╴', '┘', '─', '┴', '┐', '┤', '┬', '┼'];
+
+impl CellType {
+    /// Three characters wide glyph used when drawing a radar.
+    fn glyph(&self) -> &'static str {
+        match self {
+            CellType::NOTHING => "   ",
+            CellType::ALLY => " A ",
+            CellType::ENEMY => " E ",
+            CellType::MONSTER => " M ",
+            CellType::HELP => " H ",
+            CellType::STAIRS => " S ",
+            CellType::OBJECTIVE => " X ",
+            CellType::ObjectiveAlly => "A X",
+            CellType::ObjectiveMonster => "M X",
+            CellType::INVALID => "░░░",
+        }
+    }
+}
+
+impl From<ItemKind> for CellType {
+    fn from(kind: ItemKind) -> Self {
+        match kind {
+            ItemKind::Help => CellType::HELP,
+            ItemKind::Monster => CellType::MONSTER,
+            ItemKind::Objective => CellType::OBJECTIVE,
+        }
+    }
+}
+
+impl Radar {
+    /// Marks the passage on the `direction` side of the player as a wall, for a radar view
+    /// found out of date when a move through that passage is refused.
+    pub fn close_passage(&mut self, direction: &Direction) {
+        let passage = match direction {
+            Direction::Front => self.horizontal.get_mut(4),
+            Direction::Back => self.horizontal.get_mut(7),
+            Direction::Left => self.vertical.get_mut(5),
+            Direction::Right => self.vertical.get_mut(6),
+        };
+        if let Some(passage) = passage {
+            *passage = Passages::WALL;
+        }
+    }
+
+    fn horizontal_at(&self, row: usize, column: usize) -> &Passages {
+        self.horizontal.get(row * 3 + column).unwrap_or(&Passages::UNDEFINED)
+    }
+
+    fn vertical_at(&self, row: usize, column: usize) -> &Passages {
+        self.vertical.get(row * 4 + column).unwrap_or(&Passages::UNDEFINED)
+    }
+
+    fn junction(&self, row: usize, column: usize) -> char {
+        let walls = [
+            row > 0 && *self.vertical_at(row - 1, column) == Passages::WALL,
+            column < 3 && *self.horizontal_at(row, column) == Passages::WALL,
+            row < 3 && *self.vertical_at(row, column) == Passages::WALL,
+            column > 0 && *self.horizontal_at(row, column - 1) == Passages::WALL,
+        ];
+        let index =
+            walls.iter().enumerate().filter(|(_, wall)| **wall).map(|(i, _)| 1 << i).sum::<usize>();
+        JUNCTIONS[index]
+    }
+}
+
+/// Draws the 3x3 radar seen from the player, who stands on the center cell marked `@`.
+///
+/// Walls are drawn with box-drawing characters, open passages are left blank and
+/// undefined passages are dotted. Cells show `A` ally, `E` enemy, `M` monster, `H` help,
+/// `X` objective and `░░░` outside of the map.
+///
+/// ```text
+/// ┌───────┐ ·
+/// │       │░░░·
+/// ╵ · ╷   │ ·
+/// ·░░░│ @ │░░░·
+///   · │   ╵ ·
+/// ·░░░│
+///   · ╵   ╶───╴
+/// ```
+impl fmt::Display for Radar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..=3 {
+            for column in 0..=3 {
+                write!(f, "{}", self.junction(row, column))?;
+                if column < 3 {
+                    let edge = match self.horizontal_at(row, column) {
+                        Passages::WALL => "───",
+                        Passages::OPEN => "   ",
+                        Passages::UNDEFINED => " · ",
+                    };
+                    write!(f, "{}", edge)?;
+                }
+            }
+            if row == 3 {
+                break;
+            }
+            writeln!(f)?;
+
+            for column in 0..=3 {
+                let edge = match self.vertical_at(row, column) {
+                    Passages::WALL => '│',
+                    Passages::OPEN => ' ',
+                    Passages::UNDEFINED => '·',
+                };
+                write!(f, "{}", edge)?;
+                if column < 3 {
+                    let cell = self.cells.get(row * 3 + column).unwrap_or(&CellType::INVALID);
+                    let glyph = match cell {
+                        CellType::NOTHING if row == 1 && column == 1 => " @ ",
+                        _ => cell.glyph(),
+                    };
+                    write!(f, "{}", glyph)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 /// Splits a string into chunks of specified size, padding the last chunk if necessary.
 ///
 /// # Arguments
@@ -56,6 +185,24 @@ pub fn split_into_chunks(text: &str, chunk_size: usize) -> Vec<String> {
         .collect::<Vec<String>>()
 }
 
+const NUM_PASSAGES: usize = 12;
+const NUM_CELLS: usize = 9;
+const RADAR_BYTES: usize = 11;
+
+/// Encodes raw bytes to Base64 using the custom Base64 alphabet, without padding.
+///
+/// See [`crate::base64`] for the other alphabets.
+///
+/// # Examples
+/// ```
+/// use shared::radar::encode_base64_bytes;
+/// assert_eq!(encode_base64_bytes(&[0]), "aa");
+/// assert_eq!(encode_base64_bytes(b"Hello"), "sgvSBg8");
+/// ```
+pub fn encode_base64_bytes(bytes: &[u8]) -> String {
+    Base64::GAME.encode(bytes)
+}
+
 /// Encodes data to Base64 representation using the custom Base64 alphabet.
 ///
 /// # Arguments
@@ -66,34 +213,57 @@ pub fn split_into_chunks(text: &str, chunk_size: usize) -> Vec<String> {
 ///
 /// A Base64-encoded string representation of the input data
 pub fn encode_base64<T: ToBinary>(input: T) -> String {
-    let binary_text = match input.to_binary() {
-        Ok(b) => b,
-        Err(_) => return String::new(),
-    };
-    let subs = split_into_chunks(&binary_text, 6);
-    let mut encoded = String::with_capacity(subs.len());
-
-    for sub in subs {
-        let decimal = match u8::from_str_radix(&sub, 2) {
-            Ok(d) => d,
-            Err(_) => continue,
-        };
-
-        if let Some(c) = BASE64_CHARS.chars().nth(decimal as usize) {
-            encoded.push(c);
-        }
-    }
-
-    encoded
+    encode_base64_bytes(&input.to_bytes())
 }
 
-/// Decodes a base64-encoded string into its original form.
+/// Decodes a base64-encoded string into raw bytes.
 ///
 /// # Algorithm
 /// The function processes input character by character:
 /// 1. Each base64 character represents 6 bits of data
 /// 2. Bits are accumulated in a buffer until there are enough (8 bits) to form a byte
-/// 3. Bytes are extracted and converted to characters
+/// 3. Bytes are extracted from the top of the buffer
+///
+/// Characters outside of the alphabet fail like in [`decode_base64_strict`]. Unlike it, a
+/// lone last character, too short to hold a byte, is ignored.
+///
+/// # Bit Processing
+/// ```text
+/// Input:    |  S     |  G     |  V     |  s     |
+/// Base64:   |010010  |010000  |010111  |100011  |
+/// Output:   |01001001|00000101|11100011|
+/// ```
+pub fn decode_base64_bytes(input: &str) -> Result<Vec<u8>, RadarError> {
+    match Base64::GAME.decode(input) {
+        // Only reported once every character is in the alphabet, so the input is ASCII
+        Err(Base64Error::TruncatedInput { .. }) => {
+            Ok(Base64::GAME.decode(&input[..input.len() - 1])?)
+        }
+        decoded => Ok(decoded?),
+    }
+}
+
+/// Decodes a base64-encoded string into raw bytes, failing on the first character
+/// outside of the game alphabet instead of skipping it.
+///
+/// # Examples
+/// ```
+/// use shared::errors::RadarError;
+/// use shared::radar::decode_base64_strict;
+/// assert_eq!(decode_base64_strict("gq"), Ok(vec![25]));
+/// assert_eq!(
+///     decode_base64_strict("gq=="),
+///     Err(RadarError::BadAlphabet { character: '=', position: 2 })
+/// );
+/// ```
+pub fn decode_base64_strict(input: &str) -> Result<Vec<u8>, RadarError> {
+    Ok(Base64::GAME.decode(input)?)
+}
+
+/// Decodes a base64-encoded string into a string holding one character per decoded byte.
+///
+/// Every byte is mapped to the character with the same code point, so bytes above 127
+/// survive a round trip through `ToBinary`. Prefer [`decode_base64_bytes`] for binary data.
 ///
 /// # Parameters
 /// * `input` - A string slice containing base64-encoded data using the alphabet:
@@ -102,54 +272,90 @@ pub fn encode_base64<T: ToBinary>(input: T) -> String {
 ///   - 0-9 (52-61)
 ///   - +/ (62-63)
 ///
-/// # Returns
-/// A `String` containing the decoded data.
-///
 /// # Examples
 /// ```
 /// use shared::radar::{encode_base64, decode_base64};
 /// let encoded = encode_base64("Hello");
-/// let decoded = decode_base64(&encoded);
+/// let decoded = decode_base64(&encoded).unwrap();
 /// assert_eq!(decoded, "Hello");
 ///
 /// // Single byte values
-/// assert_eq!(decode_base64("aa"), "\0");
-/// assert_eq!(decode_base64("gq"), "\x19");
+/// assert_eq!(decode_base64("aa").unwrap(), "\0");
+/// assert_eq!(decode_base64("gq").unwrap(), "\x19");
+/// assert!(decode_base64("gq==").is_err());
 /// ```
-///
-/// # Bit Processing
-/// ```text
-/// Input:    |  S     |  G     |  V     |  s     |
-/// Base64:   |010010  |010000  |010111  |100011  |
-/// Output:   |01001001|00000101|11100011|
-/// Chars:    |   I    |   E    |   š    |
-/// ```
-pub fn decode_base64(input: &str) -> String {
-    let mut decoded = String::new();
-    let mut buffer: u32 = 0;
-    let mut bits: u32 = 0;
-
-    for &byte in input.as_bytes() {
-        let value = match byte {
-            b'a'..=b'z' => byte - b'a',      // 0-25
-            b'A'..=b'Z' => byte - b'A' + 26, // 26-51
-            b'0'..=b'9' => byte - b'0' + 52, // 52-61
-            b'+' => 62,
-            b'/' => 63,
-            _ => continue,
-        };
+pub fn decode_base64(input: &str) -> Result<String, RadarError> {
+    Ok(decode_base64_bytes(input)?.into_iter().map(char::from).collect())
+}
 
-        buffer = (buffer << 6) | (value as u32); // Shift buffer 6 bits to the left and add the value
-        bits += 6;
+fn cell_from_code(code: u8) -> Option<CellType> {
+    match code {
+        0 => Some(CellType::NOTHING),
+        1 => Some(CellType::ALLY),
+        2 => Some(CellType::ENEMY),
+        3 => Some(CellType::MONSTER),
+        4 => Some(CellType::HELP),
+        5 => Some(CellType::STAIRS),
+        8 => Some(CellType::OBJECTIVE),
+        9 => Some(CellType::ObjectiveAlly),
+        11 => Some(CellType::ObjectiveMonster),
+        15 => Some(CellType::INVALID),
+        _ => None,
+    }
+}
 
-        while bits >= 8 {
-            bits -= 8;
-            let byte = ((buffer >> bits) & 0xFF) as u8; // Shift buffer to the right by bits and pad with 0s
-            decoded.push(byte as char);
-        }
+fn passage_from_code(code: u8) -> Option<Passages> {
+    match code {
+        0 => Some(Passages::UNDEFINED),
+        1 => Some(Passages::OPEN),
+        2 => Some(Passages::WALL),
+        _ => None,
     }
+}
 
-    decoded
+/// Reads the 12 passages packed 2 bits each in a 24-bit value, most significant first.
+/// Errors report the index of the passage plus `offset`.
+fn passages_from_bits(bits: u32, offset: usize) -> Result<Vec<Passages>, RadarError> {
+    (0..NUM_PASSAGES)
+        .map(|i| {
+            let code = ((bits >> (22 - 2 * i)) & 0b11) as u8;
+            passage_from_code(code).ok_or(RadarError::UnknownPassage { index: offset + i, code })
+        })
+        .collect()
+}
+
+/// Reads the 9 cells packed 4 bits each in a 40-bit value, most significant first.
+/// The last 4 bits are padding.
+fn cells_from_bits(bits: u64) -> Result<Vec<CellType>, RadarError> {
+    (0..NUM_CELLS)
+        .map(|index| {
+            let code = ((bits >> (36 - 4 * index)) & 0xF) as u8;
+            cell_from_code(code).ok_or(RadarError::UnknownCell { index, code })
+        })
+        .collect()
+}
+
+/// Returns the first `len` digits of a binary string as an integer.
+///
+/// Fails if the string is shorter than `len` or if one of those digits is not '0' or '1'.
+fn binary_digits(binary: &str, len: usize) -> Result<u64, RadarError> {
+    let mut value = 0;
+    let mut count = 0;
+
+    for c in binary.chars().take(len) {
+        let bit = match c {
+            '0' => 0,
+            '1' => 1,
+            _ => return Err(RadarError::NotBinary(c)),
+        };
+        value = (value << 1) | bit;
+        count += 1;
+    }
+
+    if count < len {
+        return Err(RadarError::WrongLength { expected: len, actual: binary.len() });
+    }
+    Ok(value)
 }
 
 /// Parses cell data from a binary string and converts it to a vector of CellType enums.
@@ -160,34 +366,12 @@ pub fn decode_base64(input: &str) -> String {
 ///
 /// # Returns
 ///
-/// A vector of CellType enums representing the cell types in the maze
-pub fn retrieve_cell(octet: &str) -> Vec<CellType> {
-    const NUM_CELLS: usize = 9; // 4 bits per cell, remove the last 4 bits (padding)
-    let mut data = Vec::with_capacity(NUM_CELLS);
-
-    for i in (0..NUM_CELLS).map(|x| x * 4) {
-        let bits = &octet[i..i + 4];
-        let value = match u8::from_str_radix(bits, 2) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-
-        let cell = match value {
-            0 => CellType::NOTHING,
-            1 => CellType::ALLY,
-            2 => CellType::ENEMY,
-            3 => CellType::MONSTER,
-            4 => CellType::HELP,
-            8 => CellType::OBJECTIVE,
-            9 => CellType::ObjectiveAlly,
-            11 => CellType::ObjectiveMonster,
-            15 => CellType::INVALID,
-            _ => continue,
-        };
-        data.push(cell);
-    }
-
-    data
+/// A vector of CellType enums representing the cell types in the maze, or an error if
+/// `octet` holds fewer than 36 binary digits or an unknown cell code
+pub fn retrieve_cell(octet: &str) -> Result<Vec<CellType>, RadarError> {
+    // 4 bits per cell, the last 4 bits of the radar are padding
+    let bits = binary_digits(octet, NUM_CELLS * 4)?;
+    cells_from_bits(bits << 4)
 }
 
 /// Parses horizontal and vertical passage data from binary strings.
@@ -199,47 +383,196 @@ pub fn retrieve_cell(octet: &str) -> Vec<CellType> {
 ///
 /// # Returns
 ///
-/// A tuple containing two vectors: (horizontal passages, vertical passages)
-pub fn retrieve_passage(horizontal: &str, vertical: &str) -> (Vec<Passages>, Vec<Passages>) {
-    const NUM_HORIZONTAL: usize = 12;
-    const NUM_VERTICAL: usize = 12;
+/// A tuple containing two vectors: (horizontal passages, vertical passages), or an error
+/// if either string holds fewer than 24 binary digits or an unknown passage code
+pub fn retrieve_passage(
+    horizontal: &str,
+    vertical: &str,
+) -> Result<(Vec<Passages>, Vec<Passages>), RadarError> {
+    let horizontal = binary_digits(horizontal, NUM_PASSAGES * 2)?;
+    let vertical = binary_digits(vertical, NUM_PASSAGES * 2)?;
 
-    let mut horizontal_data = Vec::with_capacity(NUM_HORIZONTAL);
-    let mut vertical_data = Vec::with_capacity(NUM_VERTICAL);
+    Ok((
+        passages_from_bits(horizontal as u32, 0)?,
+        passages_from_bits(vertical as u32, NUM_PASSAGES)?,
+    ))
+}
 
-    for i in (0..NUM_HORIZONTAL).map(|x| x * 2) {
-        let bits = &horizontal[i..i + 2];
-        let value = match u8::from_str_radix(bits, 2) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
+/// Extracts radar data from the raw bytes of a radar view.
+///
+/// # Details
+///
+/// The bytes are expected to be structured as follows:
+/// - First 3 bytes: Horizontal passage data (in little-endian)
+/// - Next 3 bytes: Vertical passage data (in little-endian)
+/// - Last 5 bytes: Cell type data
+///
+/// Input shorter than 11 bytes or holding an unknown cell or passage code is an error,
+/// bytes past the first 11 are ignored.
+///
+/// # Examples
+/// ```
+/// use shared::radar::{decode_base64_bytes, extract_radar};
+/// let radar = extract_radar(&decode_base64_bytes("jivbQjIad/apapa").unwrap()).unwrap();
+/// assert_eq!(radar.cells.len(), 9);
+/// assert!(extract_radar(&[0; 10]).is_err());
+/// ```
+pub fn extract_radar(bytes: &[u8]) -> Result<Radar, RadarError> {
+    if bytes.len() < RADAR_BYTES {
+        return Err(RadarError::WrongLength { expected: RADAR_BYTES, actual: bytes.len() });
+    }
 
-        let passage = match value {
-            0 => Passages::UNDEFINED,
-            1 => Passages::OPEN,
-            2 => Passages::WALL,
-            _ => continue,
-        };
-        horizontal_data.push(passage);
+    let little_endian = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], 0]);
+    let cells = bytes[6..RADAR_BYTES].iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+
+    Ok(Radar {
+        horizontal: passages_from_bits(little_endian(&bytes[0..3]), 0)?,
+        vertical: passages_from_bits(little_endian(&bytes[3..6]), NUM_PASSAGES)?,
+        cells: cells_from_bits(cells)?,
+    })
+}
+
+/// Extracts radar data from the raw bytes of a radar view, rejecting anything that is not
+/// exactly 11 bytes.
+///
+/// Unlike [`extract_radar`], trailing bytes are an error too.
+pub fn extract_radar_strict(bytes: &[u8]) -> Result<Radar, RadarError> {
+    if bytes.len() != RADAR_BYTES {
+        return Err(RadarError::WrongLength { expected: RADAR_BYTES, actual: bytes.len() });
     }
+    extract_radar(bytes)
+}
 
-    for i in (0..NUM_VERTICAL).map(|x| x * 2) {
-        let bits = &vertical[i..i + 2];
-        let value = match u8::from_str_radix(bits, 2) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
+/// Decodes a radar view as sent by the server, failing on any malformed content.
+///
+/// # Examples
+/// ```
+/// use shared::radar::decode_radar;
+/// let radar = decode_radar("jivbQjIad/apapa").unwrap();
+/// assert_eq!(radar.horizontal.len(), 12);
+/// assert!(decode_radar("jivbQjIad").is_err());
+/// ```
+pub fn decode_radar(view: &str) -> Result<Radar, RadarError> {
+    extract_radar_strict(&decode_base64_strict(view)?)
+}
 
-        let passage = match value {
-            0 => Passages::UNDEFINED,
-            1 => Passages::OPEN,
-            2 => Passages::WALL,
-            _ => continue,
+/// Encodes a radar the way the server sends it, the inverse of [`decode_radar`].
+///
+/// Missing passages are encoded as undefined and missing cells as outside of the map.
+///
+/// # Examples
+/// ```
+/// use shared::radar::{decode_radar, encode_radar};
+/// let radar = decode_radar("bKgGjsIyap8p8aa").unwrap();
+/// assert_eq!(decode_radar(&encode_radar(&radar)).unwrap().cells, radar.cells);
+/// ```
+pub fn encode_radar(radar: &Radar) -> String {
+    let passages = |passages: &[Passages]| {
+        let bits = (0..NUM_PASSAGES).fold(0u32, |acc, i| {
+            let code = passages.get(i).cloned().unwrap_or(Passages::UNDEFINED) as u32;
+            acc | (code << (22 - 2 * i))
+        });
+        let [low, middle, high, _] = bits.to_le_bytes();
+        [low, middle, high]
+    };
+    let cells = (0..NUM_CELLS).fold(0u64, |acc, i| {
+        let code = radar.cells.get(i).cloned().unwrap_or(CellType::INVALID) as u64;
+        acc | (code << (36 - 4 * i))
+    });
+
+    let mut bytes = Vec::with_capacity(RADAR_BYTES);
+    bytes.extend(passages(&radar.horizontal));
+    bytes.extend(passages(&radar.vertical));
+    bytes.extend(&cells.to_be_bytes()[3..]);
+    encode_base64_bytes(&bytes)
+}
+
+/// The 3x3 radar of a player standing on the map position `position`, looking toward
+/// `facing`, one of the [`Directions`](crate::maze::Directions).
+///
+/// Cells and walls outside of the map are reported as outside and undefined, the exit and
+/// the items of the maze as their cell types.
+pub fn maze_radar(maze: &Maze, position: Cell, facing: Cell) -> Radar {
+    radar_with(maze, position, facing, |cell| {
+        if cell == maze.exit { CellType::OBJECTIVE } else { CellType::NOTHING }
+    })
+}
+
+/// The radar of a player standing on `position` of a maze with several levels, like
+/// [`maze_radar`] on that level with stairs shown as [`CellType::STAIRS`].
+///
+/// Only the exit of the last level is an objective. Radars of positions on a level that
+/// does not exist are entirely outside of the map.
+pub fn level_radar(maze: &LevelMaze, position: LevelCell, facing: Cell) -> Radar {
+    let Some(level) = maze.level(position) else {
+        return Radar {
+            horizontal: vec![Passages::UNDEFINED; NUM_PASSAGES],
+            vertical: vec![Passages::UNDEFINED; NUM_PASSAGES],
+            cells: vec![CellType::INVALID; NUM_CELLS],
         };
-        vertical_data.push(passage);
-    }
+    };
+    let exit = maze.exit();
+    radar_with(level, position.cell, facing, |cell| {
+        let position = LevelCell { level: position.level, cell };
+        if position == exit {
+            CellType::OBJECTIVE
+        } else if maze.is_stairs(position) {
+            CellType::STAIRS
+        } else {
+            CellType::NOTHING
+        }
+    })
+}
+
+/// The radar around `position`, `cell_type` telling what the open cells without an item
+/// hold.
+fn radar_with(
+    maze: &Maze,
+    position: Cell,
+    facing: Cell,
+    cell_type: impl Fn(Cell) -> CellType,
+) -> Radar {
+    // Offsets are counted in map positions, two per cell, down and right of the radar
+    let down = Cell { row: -facing.row, column: -facing.column };
+    let right = Cell { row: facing.column, column: -facing.row };
+    let at = |y: i16, x: i16| Cell {
+        row: position.row + y * down.row + x * right.row,
+        column: position.column + y * down.column + x * right.column,
+    };
+    let is_wall =
+        |cell: Cell| maze.map[cell.row as usize][cell.column as usize] == PositionType::WALL;
+    let passage = |cell: Cell| {
+        if maze.is_cell_out_of_bound(&cell) {
+            Passages::UNDEFINED
+        } else if is_wall(cell) {
+            Passages::WALL
+        } else {
+            Passages::OPEN
+        }
+    };
+
+    let horizontal = (0..4)
+        .flat_map(|row| (0..3).map(move |column| (row, column)))
+        .map(|(row, column)| passage(at(2 * row - 3, 2 * column - 2)))
+        .collect();
+    let vertical = (0..3)
+        .flat_map(|row| (0..4).map(move |column| (row, column)))
+        .map(|(row, column)| passage(at(2 * row - 2, 2 * column - 3)))
+        .collect();
+    let cells = (0..3)
+        .flat_map(|row| (0..3).map(move |column| at(2 * row - 2, 2 * column - 2)))
+        .map(|cell| {
+            if maze.is_cell_out_of_bound(&cell) || is_wall(cell) {
+                CellType::INVALID
+            } else if let Some(kind) = maze.item_at(cell) {
+                CellType::from(kind)
+            } else {
+                cell_type(cell)
+            }
+        })
+        .collect();
 
-    (horizontal_data, vertical_data)
+    Radar { horizontal, vertical, cells }
 }
 
 /// Extracts radar data from encoded input.
@@ -251,40 +584,11 @@ pub fn retrieve_passage(horizontal: &str, vertical: &str) -> (Vec<Passages>, Vec
 /// # Returns
 ///
 /// A Result containing a Radar struct with horizontal passages, vertical passages,
-/// and cell types, or a formatting error
+/// and cell types, or a decoding error
 ///
-/// # Details
-///
-/// The binary data is expected to be structured as follows:
-/// - First 24 bits (3 bytes): Horizontal passage data (in little-endian)
-/// - Next 24 bits (3 bytes): Vertical passage data (in little-endian)
-/// - Last 40 bits (5 bytes): Cell type data
-pub fn extract_data<T: ToBinary>(input: T) -> Result<Radar, std::fmt::Error> {
-    let binary = match input.to_binary() {
-        Ok(b) => b,
-        Err(_) => {
-            return Ok(Radar { horizontal: Vec::new(), vertical: Vec::new(), cells: Vec::new() });
-        }
-    };
-
-    if binary.len() < 88 {
-        return Ok(Radar { horizontal: Vec::new(), vertical: Vec::new(), cells: Vec::new() });
-    }
-
-    // 3 first octets are for horizontal, 3 next for vertical, and the last 5 for cells
-    // Horizontal and vertical are in little-endian order so we need to reverse them
-    let mut horizontal_bits = String::with_capacity(24);
-    write!(horizontal_bits, "{}{}{}", &binary[16..24], &binary[8..16], &binary[0..8])?;
-
-    let mut vertical_bits = String::with_capacity(24);
-    write!(vertical_bits, "{}{}{}", &binary[40..48], &binary[32..40], &binary[24..32])?;
-
-    let cell_bits = &binary[48..88];
-
-    let (horizontal, vertical) = retrieve_passage(&horizontal_bits, &vertical_bits);
-    let cells = retrieve_cell(cell_bits);
-
-    Ok(Radar { horizontal, vertical, cells })
+/// See [`extract_radar`] for the expected layout.
+pub fn extract_data<T: ToBinary>(input: T) -> Result<Radar, RadarError> {
+    extract_radar(&input.to_bytes())
 }
 
 #[cfg(test)]
@@ -355,19 +659,19 @@ mod tests {
     #[test]
     fn test_decode_prof() {
         let test1 = "Hello, World!";
-        assert_eq!(decode_base64(&encode_base64(test1)), test1);
+        assert_eq!(decode_base64(&encode_base64(test1)).unwrap(), test1);
         let test2 = "#123";
-        assert_eq!(decode_base64(&encode_base64(test2)), test2);
+        assert_eq!(decode_base64(&encode_base64(test2)).unwrap(), test2);
         let test3 = "Hier, je suis rentré chez moi vers 18h, j'ai manger du poulet avec du riz et ainsi que fait mon exercice de Schooding.";
-        assert_eq!(decode_base64(&encode_base64(test3)), test3);
+        assert_eq!(decode_base64(&encode_base64(test3)).unwrap(), test3);
         let test4 = "qwekasdjladfljadljk";
-        assert_eq!(decode_base64(&encode_base64(test4)), test4);
+        assert_eq!(decode_base64(&encode_base64(test4)).unwrap(), test4);
     }
 
     #[test]
     fn test_retrieve_cell() {
         assert_eq!(
-            retrieve_cell("1111111111111111000011111111000000000000"),
+            retrieve_cell("1111111111111111000011111111000000000000").unwrap(),
             vec![
                 CellType::INVALID,
                 CellType::INVALID,
@@ -382,7 +686,7 @@ mod tests {
         );
 
         assert_eq!(
-            retrieve_cell("1111111111110000000011110000000011110000"),
+            retrieve_cell("1111111111110000000011110000000011110000").unwrap(),
             vec![
                 CellType::INVALID,
                 CellType::INVALID,
@@ -397,7 +701,7 @@ mod tests {
         );
 
         assert_eq!(
-            retrieve_cell("1111111111110000000011110000000011110000"),
+            retrieve_cell("1111111111110000000011110000000011110000").unwrap(),
             vec![
                 CellType::INVALID,
                 CellType::INVALID,
@@ -415,7 +719,7 @@ mod tests {
     #[test]
     fn test_retrieve_passage() {
         let (horizontal, vertical) =
-            retrieve_passage("000000001010000101100110", "000000000010011010011010");
+            retrieve_passage("000000001010000101100110", "000000000010011010011010").unwrap();
         assert_eq!(
             horizontal,
             vec![
@@ -452,7 +756,7 @@ mod tests {
         );
 
         let (horizontal, vertical) =
-            retrieve_passage("000000101000010100011000", "000000001001100001101000");
+            retrieve_passage("000000101000010100011000", "000000001001100001101000").unwrap();
         assert_eq!(
             horizontal,
             vec![
@@ -489,9 +793,173 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_retrieve_short_input() {
+        assert_eq!(retrieve_cell("1111"), Err(RadarError::WrongLength { expected: 36, actual: 4 }));
+        assert_eq!(
+            retrieve_passage("000000001010000101100110", "00"),
+            Err(RadarError::WrongLength { expected: 24, actual: 2 })
+        );
+        assert_eq!(
+            retrieve_passage("", "000000001010000101100110").map(|_| ()),
+            Err(RadarError::WrongLength { expected: 24, actual: 0 })
+        );
+    }
+
+    #[test]
+    fn test_retrieve_non_binary_input() {
+        assert_eq!(
+            retrieve_cell("11é1111111111111111111111111111111111"),
+            Err(RadarError::NotBinary('é'))
+        );
+        assert_eq!(
+            retrieve_passage("0000000010100001011001x0", "000000001010000101100110"),
+            Err(RadarError::NotBinary('x'))
+        );
+    }
+
+    #[test]
+    fn test_retrieve_unknown_codes() {
+        assert_eq!(
+            retrieve_cell("111101101111111111111111111111111111"),
+            Err(RadarError::UnknownCell { index: 1, code: 6 })
+        );
+        assert_eq!(
+            retrieve_passage("000000001010000101100110", "000000000000000000000011"),
+            Err(RadarError::UnknownPassage { index: 23, code: 3 })
+        );
+    }
+
+    #[test]
+    fn test_byte_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let encoded = encode_base64_bytes(&bytes);
+        assert_eq!(decode_base64_bytes(&encoded).unwrap(), bytes);
+
+        // Bytes above 127 survive the string wrappers as well
+        assert_eq!(encode_base64(&decode_base64(&encoded).unwrap()), encoded);
+        assert_eq!(decode_base64_bytes("gq").unwrap(), vec![25]);
+    }
+
+    #[test]
+    fn test_decode_base64_bytes_only_differs_on_length() {
+        assert_eq!(decode_base64_bytes("gqa").unwrap(), vec![25, 0]);
+        assert_eq!(decode_base64_bytes("gqaaa").unwrap(), decode_base64_strict("gqaa").unwrap());
+        assert!(decode_base64_strict("gqaaa").is_err());
+
+        for input in ["jivb Qj", "ééa", "gq=="] {
+            assert_eq!(decode_base64_bytes(input), decode_base64_strict(input));
+        }
+    }
+
+    #[test]
+    fn test_extract_radar_matches_string_pipeline() {
+        for view in ["jivbQjIad/apapa", "Hjeikcyc/W8a8pa", "kOuczzGa//apaaa", "bKgGjsIyap8p8aa"] {
+            let from_bytes = extract_radar(&decode_base64_bytes(view).unwrap()).unwrap();
+            let from_string = extract_data(&decode_base64(view).unwrap()).unwrap();
+            assert_eq!(from_bytes.horizontal, from_string.horizontal);
+            assert_eq!(from_bytes.vertical, from_string.vertical);
+            assert_eq!(from_bytes.cells, from_string.cells);
+        }
+
+        assert_eq!(
+            extract_radar(&[0; 10]).err(),
+            Some(RadarError::WrongLength { expected: 11, actual: 10 })
+        );
+    }
+
+    #[test]
+    fn test_decode_radar_known_views() {
+        for view in [
+            "jivbQjIad/apapa",
+            "Hjeikcyc/W8a8pa",
+            "kOuczzGa//apaaa",
+            "bKgGjsIyap8p8aa",
+            "bieakcGa//+F8pa",
+        ] {
+            let strict = decode_radar(view).unwrap();
+            let lenient = extract_radar(&decode_base64_bytes(view).unwrap()).unwrap();
+            assert_eq!(strict.horizontal, lenient.horizontal);
+            assert_eq!(strict.vertical, lenient.vertical);
+            assert_eq!(strict.cells, lenient.cells);
+        }
+    }
+
+    #[test]
+    fn test_decode_base64_strict_rejects_bad_alphabet() {
+        assert_eq!(
+            decode_base64_strict("jivb Qj"),
+            Err(RadarError::BadAlphabet { character: ' ', position: 4 })
+        );
+        assert_eq!(
+            decode_base64_strict("ééa"),
+            Err(RadarError::BadAlphabet { character: 'é', position: 0 })
+        );
+    }
+
+    #[test]
+    fn test_extract_radar_strict_rejects_wrong_length() {
+        assert_eq!(
+            extract_radar_strict(&[0; 10]).err(),
+            Some(RadarError::WrongLength { expected: 11, actual: 10 })
+        );
+        assert_eq!(
+            decode_radar("jivbQjIad/apapaa").err(),
+            Some(RadarError::WrongLength { expected: 11, actual: 12 })
+        );
+    }
+
+    #[test]
+    fn test_extract_radar_strict_rejects_unknown_codes() {
+        // Cell 1 holds 6
+        let mut bytes = [0u8; 11];
+        bytes[6] = 0x06;
+        assert_eq!(
+            extract_radar_strict(&bytes).err(),
+            Some(RadarError::UnknownCell { index: 1, code: 6 })
+        );
+        assert_eq!(
+            extract_radar(&bytes).err(),
+            Some(RadarError::UnknownCell { index: 1, code: 6 })
+        );
+
+        // The last vertical passage holds 3
+        let mut bytes = [0u8; 11];
+        bytes[3] = 0b11;
+        assert_eq!(
+            extract_radar_strict(&bytes).err(),
+            Some(RadarError::UnknownPassage { index: 23, code: 3 })
+        );
+    }
+
+    #[test]
+    fn test_display_radar() {
+        let radar = decode_radar("bKgGjsIyap8p8aa").unwrap();
+        let expected = [
+            "┌───────┐ ·  ",
+            "│       │░░░·",
+            "╵ · ╷   │ ·  ",
+            "·░░░│ @ │░░░·",
+            "  · │   ╵ ·  ",
+            "·░░░│        ",
+            "  · ╵   ╶───╴",
+        ];
+        assert_eq!(radar.to_string(), expected.join("\n"));
+
+        let radar = decode_radar("bieakcGa//+F8pa").unwrap();
+        assert!(radar.to_string().contains("│A X│"));
+    }
+
+    #[test]
+    fn test_display_incomplete_radar() {
+        let radar = Radar { horizontal: Vec::new(), vertical: Vec::new(), cells: Vec::new() };
+        assert_eq!(radar.to_string().lines().count(), 7);
+        assert!(radar.to_string().lines().all(|line| line.chars().count() == 13));
+    }
+
     #[test]
     fn test_extract_data() {
-        let input = decode_base64("jivbQjIad/apapa");
+        let input = decode_base64("jivbQjIad/apapa").unwrap();
         let radar_view = extract_data(&input).unwrap();
         assert_eq!(radar_view.horizontal.len(), 12);
         assert_eq!(radar_view.vertical.len(), 12);
@@ -557,4 +1025,119 @@ mod tests {
 
         assert_eq!(split_into_chunks("0011000", 2), vec!["00", "11", "00", "00"]);
     }
+
+    #[test]
+    fn test_encode_radar() {
+        for view in [
+            "jivbQjIad/apapa",
+            "Hjeikcyc/W8a8pa",
+            "kOuczzGa//apaaa",
+            "bKgGjsIyap8p8aa",
+            "bieakcGa//+F8pa",
+        ] {
+            let radar = decode_radar(view).unwrap();
+            let encoded = encode_radar(&radar);
+            let decoded = decode_radar(&encoded).unwrap();
+            assert_eq!(decoded.horizontal, radar.horizontal);
+            assert_eq!(decoded.vertical, radar.vertical);
+            assert_eq!(decoded.cells, radar.cells);
+        }
+
+        let empty = Radar { horizontal: Vec::new(), vertical: Vec::new(), cells: Vec::new() };
+        let decoded = decode_radar(&encode_radar(&empty)).unwrap();
+        assert_eq!(decoded.horizontal, vec![Passages::UNDEFINED; 12]);
+        assert_eq!(decoded.cells, vec![CellType::INVALID; 9]);
+    }
+
+    #[test]
+    fn test_maze_radar() {
+        use crate::maze::io::read_text;
+        use crate::maze::{Directions, Item};
+
+        let mut maze = read_text("#######\n#2    #\n# ### #\n#    3#\n#######\n").unwrap();
+        maze.items.push(Item { kind: ItemKind::Help, cell: Cell { row: 3, column: 1 } });
+        let entry = maze.entry;
+
+        let radar = maze_radar(&maze, entry, Directions::NORTH);
+        let expected = [
+            "  ·   ·   ·  ",
+            "·░░░·░░░·░░░·",
+            "  · ┌───────╴",
+            "·░░░│ @      ",
+            "  · │   ╶───╴",
+            "·░░░│ H      ",
+            "  · └───────╴",
+        ];
+        assert_eq!(radar.to_string(), expected.join("\n"));
+
+        // Looking east, the passage ahead is at the top of the radar
+        let radar = maze_radar(&maze, entry, Directions::EAST);
+        assert_eq!(radar.cells[1], CellType::NOTHING);
+        assert_eq!(radar.horizontal[4], Passages::OPEN);
+        assert_eq!(radar.cells[3], CellType::INVALID);
+        assert_eq!(radar.cells[5], CellType::HELP);
+
+        let radar = maze_radar(&maze, Cell { row: 3, column: 3 }, Directions::NORTH);
+        assert_eq!(radar.cells[5], CellType::OBJECTIVE);
+    }
+
+    #[test]
+    fn test_close_passage() {
+        use crate::maze::Directions;
+        use crate::maze::io::read_text;
+
+        let mut maze = read_text("#######\n#2    #\n# ### #\n#    3#\n#######\n").unwrap();
+        let mut radar = maze_radar(&maze, maze.entry, Directions::NORTH);
+        radar.close_passage(&Direction::Right);
+        radar.close_passage(&Direction::Front);
+
+        maze.map[1][2] = PositionType::WALL;
+        let closed = maze_radar(&maze, maze.entry, Directions::NORTH);
+        assert_eq!(radar.to_string(), closed.to_string());
+        assert_eq!(radar.vertical[6], Passages::WALL);
+        assert_eq!(radar.horizontal[7], Passages::OPEN);
+    }
+
+    #[test]
+    fn test_level_radar() {
+        use crate::maze::Directions;
+        use crate::maze::io::read_text;
+
+        let text = "#######\n#2    #\n# ### #\n#    3#\n#######\n";
+        let maze = LevelMaze::new(vec![read_text(text).unwrap(), read_text(text).unwrap()]);
+        let at = |level, row, column| LevelCell { level, cell: Cell { row, column } };
+
+        // The stairs go up from the exit of the first level and down from the entry of the
+        // second one
+        let radar = level_radar(&maze, at(0, 3, 3), Directions::NORTH);
+        assert_eq!(radar.cells[5], CellType::STAIRS);
+        let radar = level_radar(&maze, at(1, 3, 3), Directions::NORTH);
+        assert_eq!(radar.cells[5], CellType::STAIRS);
+        assert_eq!(radar.cells[3], CellType::NOTHING);
+        let radar = level_radar(&maze, at(1, 1, 3), Directions::NORTH);
+        assert_eq!(radar.cells[3], CellType::OBJECTIVE);
+        assert_eq!(decode_radar(&encode_radar(&radar)).unwrap().cells, radar.cells);
+
+        let radar = level_radar(&maze, at(2, 3, 3), Directions::NORTH);
+        assert!(radar.cells.iter().all(|cell| *cell == CellType::INVALID));
+        assert!(radar.horizontal.iter().all(|passage| *passage == Passages::UNDEFINED));
+    }
+
+    #[test]
+    fn test_stairs_code() {
+        let radar = Radar {
+            horizontal: vec![Passages::OPEN; 12],
+            vertical: vec![Passages::OPEN; 12],
+            cells: vec![CellType::STAIRS; 9],
+        };
+        assert_eq!(decode_radar(&encode_radar(&radar)).unwrap().cells, radar.cells);
+        assert_eq!(CellType::STAIRS.glyph(), " S ");
+    }
+
+    #[test]
+    fn test_item_cell_types() {
+        assert_eq!(CellType::from(ItemKind::Help), CellType::HELP);
+        assert_eq!(CellType::from(ItemKind::Monster), CellType::MONSTER);
+        assert_eq!(CellType::from(ItemKind::Objective), CellType::OBJECTIVE);
+    }
 }
@@ -0,0 +1,136 @@
+use crate::maze::Cell;
+use crate::messages::{
+    Action, ActionError, Challenge, Direction, Heartbeat, Hint, Message, MessageError, RadarView,
+    RegisterTeam, RegisterTeamResult, RegistrationError, SubscribePlayer, SubscribePlayerResult,
+    TeamInfo, TeamMessage,
+};
+use serde_json::Value;
+
+/// Builds the JSON Schema of the wire protocol from the `Message` type.
+///
+/// The schema embeds one example payload per `Message` variant under `examples`.
+/// The generated document is checked in at `docs/protocol.schema.json`, regenerate it
+/// with `just schema` after changing any message type.
+pub fn protocol_schema() -> Value {
+    let mut schema = schemars::schema_for!(Message).to_value();
+    let examples = example_messages()
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("protocol messages serialize to JSON");
+
+    if let Some(object) = schema.as_object_mut() {
+        object.insert("examples".to_string(), Value::Array(examples));
+    }
+    schema
+}
+
+/// Returns one example for each `Message` variant, in declaration order.
+pub fn example_messages() -> Vec<Message> {
+    vec![
+        Message::RegisterTeam(RegisterTeam { name: "curious_broccoli".to_string() }),
+        Message::RegisterTeamResult(RegisterTeamResult::Ok {
+            expected_players: 3,
+            registration_token: "SECRET".to_string(),
+        }),
+        Message::SubscribePlayer(SubscribePlayer {
+            name: "Player1".to_string(),
+            registration_token: "SECRET".to_string(),
+        }),
+        Message::SubscribePlayerResult(SubscribePlayerResult::Err(
+            RegistrationError::InvalidRegistrationToken,
+        )),
+        Message::RadarView(RadarView("bKgGjsIyap8p8aa".to_string())),
+        Message::Action(Action::MoveTo(Direction::Front)),
+        Message::ActionError(ActionError::CannotPassThroughWall),
+        Message::MessageError(MessageError { message: "Invalid message type".to_string() }),
+        Message::Hint(Hint::RelativeCompass { angle: 12.5 }),
+        Message::Challenge(Challenge::SecretSumModulo(23)),
+        Message::Ping(Heartbeat { sequence: 1 }),
+        Message::Pong(Heartbeat { sequence: 1 }),
+        Message::TeamMessage(TeamMessage {
+            sender: "Player2".to_string(),
+            info: TeamInfo::DeadEnd(Cell { row: 2, column: -1 }),
+        }),
+    ]
+}
+
+/// Name of the variant, as it appears as the key of the serialized message.
+pub fn variant_name(message: &Message) -> &'static str {
+    match message {
+        Message::RegisterTeam(_) => "RegisterTeam",
+        Message::RegisterTeamResult(_) => "RegisterTeamResult",
+        Message::SubscribePlayer(_) => "SubscribePlayer",
+        Message::SubscribePlayerResult(_) => "SubscribePlayerResult",
+        Message::RadarView(_) => "RadarView",
+        Message::Action(_) => "Action",
+        Message::ActionError(_) => "ActionError",
+        Message::MessageError(_) => "MessageError",
+        Message::Hint(_) => "Hint",
+        Message::Challenge(_) => "Challenge",
+        Message::Ping(_) => "Ping",
+        Message::Pong(_) => "Pong",
+        Message::TeamMessage(_) => "TeamMessage",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    fn schema_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../docs/protocol.schema.json")
+    }
+
+    /// Every `Message` variant, as listed by the `oneOf` alternatives of its schema.
+    fn schema_variants() -> Vec<String> {
+        let schema = schemars::schema_for!(Message).to_value();
+        schema["oneOf"]
+            .as_array()
+            .expect("one alternative per variant")
+            .iter()
+            .map(|variant| variant["required"][0].as_str().expect("the variant name").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_examples_cover_every_variant() {
+        let examples = example_messages();
+        let names: Vec<&str> = examples.iter().map(variant_name).collect();
+        assert_eq!(names.iter().collect::<HashSet<_>>().len(), examples.len());
+        // Declaration order, one example per variant
+        assert_eq!(names, schema_variants());
+
+        for example in &examples {
+            let value = serde_json::to_value(example).unwrap();
+            assert!(value.get(variant_name(example)).is_some());
+        }
+    }
+
+    #[test]
+    fn test_schema_lists_every_variant() {
+        let schema = serde_json::to_string(&protocol_schema()).unwrap();
+        for example in example_messages() {
+            assert!(schema.contains(&format!("\"{}\"", variant_name(&example))));
+        }
+    }
+
+    /// Fails when the message types changed without regenerating the checked-in schema.
+    /// Run `UPDATE_SCHEMA=1 cargo test -p shared schema` (or `just schema`) to refresh it.
+    #[test]
+    fn test_schema_is_up_to_date() {
+        let generated = serde_json::to_string_pretty(&protocol_schema()).unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            std::fs::write(schema_path(), &generated).unwrap();
+        }
+
+        let committed = std::fs::read_to_string(schema_path()).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "docs/protocol.schema.json is outdated, regenerate it with `just schema`"
+        );
+    }
+}
@@ -1,42 +1,55 @@
 use std::fmt::Write;
 
+/// Data that can be read as a sequence of bytes by the radar and base64 functions.
 pub trait ToBinary {
-    fn to_binary(&self) -> Result<String, std::fmt::Error>;
-}
+    /// Returns the raw bytes of the value.
+    fn to_bytes(&self) -> Vec<u8>;
 
-impl ToBinary for &str {
+    /// Returns the bytes as a string of '0' and '1' characters, most significant bit first.
     fn to_binary(&self) -> Result<String, std::fmt::Error> {
-        self.chars().try_fold(String::with_capacity(self.len() * 8), |mut acc, c| {
-            write!(acc, "{:08b}", c as u8)?;
+        let bytes = self.to_bytes();
+        bytes.iter().try_fold(String::with_capacity(bytes.len() * 8), |mut acc, b| {
+            write!(acc, "{:08b}", b)?;
             Ok(acc)
         })
     }
 }
 
+/// Strings are read one byte per character, which is the format produced by
+/// `decode_base64`. Characters above U+00FF are truncated.
+impl ToBinary for &str {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.chars().map(|c| c as u8).collect()
+    }
+}
+
 impl ToBinary for &String {
-    fn to_binary(&self) -> Result<String, std::fmt::Error> {
-        self.chars().try_fold(String::with_capacity(self.len() * 8), |mut acc, c| {
-            write!(acc, "{:08b}", c as u8)?;
-            Ok(acc)
-        })
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_str().to_bytes()
+    }
+}
+
+impl ToBinary for &[u8] {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl ToBinary for &Vec<u8> {
+    fn to_bytes(&self) -> Vec<u8> {
+        (*self).clone()
     }
 }
 
 impl ToBinary for &[i32] {
-    fn to_binary(&self) -> Result<String, std::fmt::Error> {
-        self.iter().try_fold(String::with_capacity(self.len() * 8), |mut acc, &d| {
-            write!(acc, "{:08b}", d as u8)?;
-            Ok(acc)
-        })
+    fn to_bytes(&self) -> Vec<u8> {
+        self.iter().map(|&d| d as u8).collect()
     }
 }
 
 impl<const N: usize> ToBinary for &[i32; N] {
-    fn to_binary(&self) -> Result<String, std::fmt::Error> {
-        self.iter().try_fold(String::with_capacity(N * 8), |mut acc, &d| {
-            write!(acc, "{:08b}", d as u8)?;
-            Ok(acc)
-        })
+    fn to_bytes(&self) -> Vec<u8> {
+        self.iter().map(|&d| d as u8).collect()
     }
 }
 
@@ -79,6 +92,14 @@ mod tests {
         assert_eq!(result, "010000010100001001000011");
     }
 
+    #[test]
+    fn test_to_bytes() {
+        assert_eq!("AB".to_bytes(), vec![65, 66]);
+        assert_eq!("\u{e9}".to_bytes(), vec![0xE9]);
+        assert_eq!((&[1u8, 255][..]).to_binary().unwrap(), "0000000111111111");
+        assert_eq!((&vec![7u8]).to_bytes(), vec![7]);
+    }
+
     #[test]
     fn test_i32_array_to_binary() {
         let input = [49, 50, 51];
@@ -31,10 +31,29 @@ pub fn print_log(msg: &str, color: Color) {
     println!("{}{}{}\n", color, msg, ColorsAnsi::RESET);
 }
 
+/// Green component of the heatmap gradient, from 255 (yellow) at step 0 down to 0 (red) at
+/// `steps`, the highest step. Computed in 64 bits so long walks do not overflow.
+pub fn heat_green(step: i32, steps: i32) -> u8 {
+    let steps = i64::from(steps.max(1));
+    let step = i64::from(step).clamp(0, steps);
+    (255 - 255 * step / steps) as u8
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_heat_green() {
+        assert_eq!(heat_green(0, 10), 255);
+        assert_eq!(heat_green(5, 10), 128);
+        assert_eq!(heat_green(10, 10), 0);
+        assert_eq!(heat_green(0, 0), 255);
+        // 255 * step overflows an i32 past 8 421 504 steps
+        assert_eq!(heat_green(i32::MAX, i32::MAX), 0);
+        assert_eq!(heat_green(i32::MAX / 2, i32::MAX), 128);
+    }
+
     #[test]
     fn test_print_error() {
         print_error("Error message");
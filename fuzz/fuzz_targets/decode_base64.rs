@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::radar::{decode_base64, decode_base64_strict};
+
+fuzz_target!(|input: &str| {
+    let Ok(decoded) = decode_base64(input) else {
+        return;
+    };
+    assert!(decoded.chars().count() <= input.len() * 6 / 8);
+
+    // Only the length handling tells the lenient decoder apart from the strict one
+    if input.len() % 4 != 1 {
+        let strict = decode_base64_strict(input).unwrap();
+        assert!(decoded.chars().map(|c| c as u32).eq(strict.iter().map(|&b| u32::from(b))));
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use client::data_structures::maze_graph::MazeGraph;
+use client::maze_parser::{Player, maze_to_graph};
+use libfuzzer_sys::fuzz_target;
+use shared::messages::Direction;
+use shared::radar::{decode_base64, decode_radar, extract_data};
+
+fuzz_target!(|input: &str| {
+    if let Ok(radar) = decode_radar(input) {
+        assert_eq!((radar.horizontal.len(), radar.vertical.len(), radar.cells.len()), (12, 12, 9));
+    }
+
+    // Radar views arrive base64-encoded, so exercise the whole client pipeline.
+    let Ok(radar) = decode_base64(input).and_then(|decoded| extract_data(&decoded)) else {
+        return;
+    };
+
+    for direction in [Direction::Front, Direction::Right, Direction::Back, Direction::Left] {
+        let player = Player { direction, ..Player::new() };
+        let _ = maze_to_graph(&radar, &player, &mut MazeGraph::new());
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::maze::io::MazeFormat;
+
+fuzz_target!(|input: &str| {
+    for format in MazeFormat::ALL {
+        if let Ok((maze, _)) = format.read(input) {
+            assert_eq!(maze.map.len(), maze.row_len);
+            assert!(maze.map.iter().all(|row| row.len() == maze.col_len));
+        }
+    }
+});
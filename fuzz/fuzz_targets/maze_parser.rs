@@ -0,0 +1,12 @@
+#![no_main]
+
+use client::maze_parser::{maze_parser, maze_parser_unchecked};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(maze) = maze_parser_unchecked(input) {
+        assert_eq!(maze.map.len(), maze.row_len);
+        assert!(maze.map.iter().all(|row| row.len() == maze.col_len));
+        assert_eq!(maze_parser(input).is_ok(), maze.validate().is_ok());
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::messages::receive_message;
+
+fuzz_target!(|data: &[u8]| {
+    let mut stream = data;
+    while receive_message(&mut stream).is_ok() {}
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::radar::retrieve_cell;
+
+fuzz_target!(|octet: &str| {
+    if let Ok(cells) = retrieve_cell(octet) {
+        assert!(cells.len() <= 9);
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shared::radar::retrieve_passage;
+
+fuzz_target!(|data: &[u8]| {
+    let (horizontal, vertical) = data.split_at(data.len() / 2);
+    let (Ok(horizontal), Ok(vertical)) =
+        (std::str::from_utf8(horizontal), std::str::from_utf8(vertical))
+    else {
+        return;
+    };
+
+    if let Ok((horizontal, vertical)) = retrieve_passage(horizontal, vertical) {
+        assert!(horizontal.len() <= 12 && vertical.len() <= 12);
+    }
+});